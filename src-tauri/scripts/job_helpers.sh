@@ -0,0 +1,267 @@
+#!/bin/bash
+# job_helpers.sh - Resource usage sampling, limits and results for job_wrapper.sh
+# Purpose: Keeps the wrapper itself short; deployed next to it and sourced at startup
+# Usage: source job_helpers.sh (after JOB_ID, BASE_DIR, SERVER_DB and JOB_ID_SQL are set)
+#
+# Resource usage: process tree sampled from /proc every SOLVERPILOT_SAMPLE_INTERVAL s
+# (default 5) into jobs/<id>.usage; peak RSS and CPU time recorded at exit.
+# Limits (optional env): SOLVERPILOT_MEM_LIMIT_MB, SOLVERPILOT_CPU_QUOTA (%), SOLVERPILOT_CPU_AFFINITY,
+# SOLVERPILOT_CPU_TIME_LIMIT (s, SIGXCPU then SIGKILL 5 s later); a hit sets a distinct REASON.
+# Results: jobs/<id>/ is exported as SOLVERPILOT_RESULTS_DIR; a benchmark_results.csv
+# written to the working directory during the run is copied there at exit.
+
+# Usage files
+USAGE_FILE="$BASE_DIR/jobs/$JOB_ID.usage"
+TIME_FILE="$BASE_DIR/jobs/$JOB_ID.time"
+ERR_FILE="$BASE_DIR/jobs/$JOB_ID.stderr"
+
+# Per-job results directory
+RESULTS_DIR="$BASE_DIR/jobs/$JOB_ID"
+RESULTS_FILE="benchmark_results.csv"
+
+# Sampling and limit settings
+SAMPLE_INTERVAL="${SOLVERPILOT_SAMPLE_INTERVAL:-5}"
+MEM_LIMIT_MB="${SOLVERPILOT_MEM_LIMIT_MB:-}"
+CPU_QUOTA="${SOLVERPILOT_CPU_QUOTA:-}"
+CPU_AFFINITY="${SOLVERPILOT_CPU_AFFINITY:-}"
+CPU_TIME_LIMIT="${SOLVERPILOT_CPU_TIME_LIMIT:-}"
+MONITOR_PID=""
+SCOPE_UNIT=""
+
+# Final usage and limit reason, set by job_finished
+PEAK_KB=0
+CPU_SECONDS=0
+REASON=""
+
+# Print "cpu_ticks rss_kb threads read_bytes write_bytes" summed over the
+# descendants of $1, excluding the subtree of $2 (the sampler itself)
+tree_usage() {
+    ps -eo pid=,ppid= | awk -v root="$1" -v skip="$2" '
+        { parent[$1] = $2 }
+        END {
+            for (p in parent) {
+                # Keep descendants of root only
+                q = p
+                while (q != root && q != skip && (q in parent))
+                    q = parent[q]
+                if (q != root || p == root)
+                    continue
+
+                # CPU ticks (utime + stime)
+                f = "/proc/" p "/stat"
+                if ((getline l < f) > 0) {
+                    sub(/.*\) /, "", l)
+                    split(l, s, " ")
+                    ticks += s[12] + s[13]
+                }
+                close(f)
+
+                # Resident memory and threads
+                f = "/proc/" p "/status"
+                while ((getline l < f) > 0) {
+                    split(l, a, /[ \t]+/)
+                    if (a[1] == "VmRSS:")
+                        rss += a[2]
+                    if (a[1] == "Threads:")
+                        thr += a[2]
+                }
+                close(f)
+
+                # Disk I/O
+                f = "/proc/" p "/io"
+                while ((getline l < f) > 0) {
+                    split(l, a, ": ")
+                    if (a[1] == "read_bytes")
+                        rd += a[2]
+                    if (a[1] == "write_bytes")
+                        wr += a[2]
+                }
+                close(f)
+            }
+            printf "%d %d %d %d %d\n", ticks, rss, thr, rd, wr
+        }'
+}
+
+# Write a usage sample: cpu_percent rss_kb peak_rss_kb threads read_bytes write_bytes cpu_seconds final
+write_usage() {
+    printf '{"cpu_percent": %s, "rss_kb": %d, "peak_rss_kb": %d, "threads": %d, "read_bytes": %d, "write_bytes": %d, "cpu_seconds": %s, "sampled_at": "%s", "final": %s}\n' \
+        "$1" "$2" "$3" "$4" "$5" "$6" "$7" "$(date -Iseconds)" "$8" >"$USAGE_FILE.tmp"
+    mv -f "$USAGE_FILE.tmp" "$USAGE_FILE"
+}
+
+# Background sampler of the job's process tree
+monitor_usage() {
+    local self=$BASHPID
+    local hz
+    local prev=0
+    local peak=0
+    local ticks rss thr rd wr cpu secs
+    hz=$(getconf CLK_TCK 2>/dev/null || echo 100)
+
+    while sleep "$SAMPLE_INTERVAL"; do
+        if ! read -r ticks rss thr rd wr < <(tree_usage "$$" "$self"); then
+            continue
+        fi
+
+        # CPU % over the interval and CPU seconds so far
+        read -r cpu secs < <(awk -v d=$((ticks - prev)) -v t="$ticks" -v hz="$hz" -v i="$SAMPLE_INTERVAL" \
+            'BEGIN { c = d * 100 / (hz * i); printf "%.1f %.2f\n", (c < 0 ? 0 : c), t / hz }')
+        prev=$ticks
+        if [[ $rss -gt $peak ]]; then
+            peak=$rss
+        fi
+
+        write_usage "$cpu" "$rss" "$peak" "$thr" "$rd" "$wr" "$secs" false
+    done
+}
+
+# Start the resource sampler and prepare the job's results directory
+job_started() {
+    # Lock fd closed so the sampler never holds the job lock
+    monitor_usage 200>&- &
+    MONITOR_PID=$!
+
+    # Results of a previous run of this job removed
+    mkdir -p "$RESULTS_DIR"
+    rm -f "$RESULTS_DIR/$RESULTS_FILE"
+    export SOLVERPILOT_RESULTS_DIR="$RESULTS_DIR"
+}
+
+# Keep the job's results: a CSV written to the working directory during the run (the
+# lock file dates from the job start) is copied unless the job wrote its own
+keep_results() {
+    if [[ -f "$RESULTS_DIR/$RESULTS_FILE" ]]; then
+        return 0
+    fi
+    if [[ "$RESULTS_FILE" -nt "$LOCK_FILE" ]]; then
+        cp "$RESULTS_FILE" "$RESULTS_DIR/" 2>/dev/null || true
+    fi
+}
+
+# Whether the job's cgroup scope was stopped by the OOM killer
+scope_oom_killed() {
+    local result
+
+    if [[ -z "$SCOPE_UNIT" ]]; then
+        return 1
+    fi
+    result=$(systemctl --user show -p Result --value "$SCOPE_UNIT" 2>/dev/null || true)
+    [[ "$result" == "oom-kill" ]]
+}
+
+# Why a job that exited with $1 stopped, when a limit was hit (empty otherwise)
+limit_reason() {
+    local exit_code=$1
+
+    # CPU time: SIGXCPU (152), or SIGKILL once past the limit
+    if [[ -n "$CPU_TIME_LIMIT" ]]; then
+        if [[ $exit_code -eq 152 ]]; then
+            echo "limit exceeded: CPU time (${CPU_TIME_LIMIT}s)"
+            return 0
+        fi
+        if [[ $exit_code -eq 137 && ${CPU_SECONDS%.*} -ge $CPU_TIME_LIMIT ]]; then
+            echo "limit exceeded: CPU time (${CPU_TIME_LIMIT}s)"
+            return 0
+        fi
+    fi
+
+    # Memory: scope stopped by the cgroup OOM killer, or an allocation failure on stderr under ulimit
+    if [[ -n "$MEM_LIMIT_MB" && $exit_code -ne 0 ]]; then
+        if [[ $exit_code -eq 137 ]] && scope_oom_killed; then
+            echo "out of memory (limit ${MEM_LIMIT_MB} MiB)"
+            return 0
+        fi
+        if grep -qsE 'MemoryError|[Cc]annot allocate memory|bad_alloc|[Oo]ut of memory' "$ERR_FILE"; then
+            echo "out of memory (limit ${MEM_LIMIT_MB} MiB)"
+            return 0
+        fi
+    fi
+
+    if [[ $exit_code -eq 137 ]]; then
+        echo "killed (SIGKILL)"
+    fi
+}
+
+# Final resource usage and limit reason of a job that exited with $1, stored in the server DB,
+# and its results kept
+job_finished() {
+    local exit_code=$1
+
+    # CPU time of waited children: `times` builtin in the current shell (a subshell would not see them)
+    times >"$TIME_FILE.cpu"
+    CPU_SECONDS=$(awk 'NR == 2 { for (i = 1; i <= 2; i++) { split($i, t, "m"); s += t[1] * 60 + t[2] } } END { printf "%.2f", s }' "$TIME_FILE.cpu")
+
+    # Stop the sampler
+    if [[ -n "$MONITOR_PID" ]]; then
+        kill "$MONITOR_PID" 2>/dev/null || true
+        wait "$MONITOR_PID" 2>/dev/null || true
+    fi
+
+    # Peak RSS: largest of GNU time's report and the sampled peak
+    PEAK_KB=$({ tail -n1 "$TIME_FILE"; grep -o '"peak_rss_kb": [0-9]*' "$USAGE_FILE" | grep -o '[0-9]*$'; } 2>/dev/null | sort -n | tail -n1 || true)
+    PEAK_KB=${PEAK_KB:-0}
+    write_usage 0 0 "$PEAK_KB" 0 0 0 "$CPU_SECONDS" true 2>/dev/null || true
+
+    REASON=$(limit_reason "$exit_code")
+    rm -f "$TIME_FILE" "$TIME_FILE.cpu" "$ERR_FILE"
+
+    # A failed scope unit is kept by systemd until reset
+    if [[ -n "$SCOPE_UNIT" ]]; then
+        systemctl --user reset-failed "$SCOPE_UNIT" &>/dev/null || true
+    fi
+    keep_results
+
+    # Separate statements: older server schemas lack the resource columns
+    if command -v sqlite3 &>/dev/null; then
+        sqlite3 "$SERVER_DB" "UPDATE jobs SET peak_rss_kb=$PEAK_KB, cpu_seconds=$CPU_SECONDS WHERE id='$JOB_ID_SQL';" 2>/dev/null || true
+        if [[ -n "$REASON" ]]; then
+            sqlite3 "$SERVER_DB" "UPDATE jobs SET error_message='$REASON' WHERE id='$JOB_ID_SQL';" 2>/dev/null || true
+        fi
+    fi
+}
+
+# Run the job command under GNU time (peak RSS of the largest process) and the resource limits
+run_job() {
+    local time_cmd=()
+    local limit_cmd=()
+    local use_ulimit=""
+
+    # GNU time, when installed
+    if /usr/bin/time --version &>/dev/null; then
+        time_cmd=(/usr/bin/time -f '%M' -o "$TIME_FILE")
+    fi
+
+    # Memory and CPU quota: cgroup scope when systemd user sessions work, ulimit fallback
+    if [[ -n "$MEM_LIMIT_MB$CPU_QUOTA" ]] && systemd-run --user --scope --quiet true &>/dev/null; then
+        # Named scope: its result tells an OOM kill apart at exit
+        SCOPE_UNIT="solverpilot-job-${JOB_ID//[^A-Za-z0-9_-]/_}-$$.scope"
+        limit_cmd=(systemd-run --user --scope --quiet --unit="$SCOPE_UNIT")
+        if [[ -n "$MEM_LIMIT_MB" ]]; then
+            limit_cmd+=(-p "MemoryMax=${MEM_LIMIT_MB}M" -p MemorySwapMax=0)
+        fi
+        if [[ -n "$CPU_QUOTA" ]]; then
+            limit_cmd+=(-p "CPUQuota=${CPU_QUOTA}%")
+        fi
+    elif [[ -n "$MEM_LIMIT_MB" ]]; then
+        use_ulimit=1
+    fi
+
+    # CPU affinity
+    if [[ -n "$CPU_AFFINITY" ]]; then
+        limit_cmd+=(taskset -c "$CPU_AFFINITY")
+    fi
+
+    # ulimits apply to the job only: set in a subshell that execs the job command
+    (
+        if [[ -n "$use_ulimit" ]]; then
+            ulimit -v $((MEM_LIMIT_MB * 1024))
+            # Allocation failures on stderr tell an out-of-memory exit apart
+            exec 2> >(tee "$ERR_FILE" >&2)
+        fi
+        if [[ -n "$CPU_TIME_LIMIT" ]]; then
+            ulimit -St "$CPU_TIME_LIMIT"
+            ulimit -Ht $((CPU_TIME_LIMIT + 5))
+        fi
+        exec ${time_cmd[@]+"${time_cmd[@]}"} ${limit_cmd[@]+"${limit_cmd[@]}"} "$@"
+    )
+}
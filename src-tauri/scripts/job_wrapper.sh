@@ -3,6 +3,8 @@
 # Purpose: Guarantees 99.99% state capture for remote SSH/tmux jobs
 # Usage: job_wrapper.sh <job_id> <command> [args...]
 #
+# Resource usage, limits and per-job results: job_helpers.sh, deployed next to this script
+#
 # Exit Codes:
 #   0   - Job completed successfully
 #   1   - Job failed or wrapper error
@@ -28,6 +30,9 @@ SERVER_DB="$BASE_DIR/server.db"
 STATE_FILE="$BASE_DIR/jobs/$JOB_ID.status"
 LOCK_FILE="$BASE_DIR/locks/$JOB_ID.lock"
 
+# Resource usage sampling, limits and results
+source "$(dirname "${BASH_SOURCE[0]}")/job_helpers.sh"
+
 # Store started_at for inclusion in completion state
 STARTED_AT=""
 
@@ -47,6 +52,9 @@ cleanup() {
     local status="completed"
     [[ $exit_code -ne 0 ]] && status="failed"
 
+    # Final resource usage and limit reason
+    job_finished "$exit_code"
+
     # Write to SQLite (primary source of truth)
     if command -v sqlite3 &>/dev/null; then
         sqlite3 "$SERVER_DB" <<SQL 2>/dev/null || echo "WARNING: Failed to update SQLite, state file written" >&2
@@ -66,6 +74,7 @@ SQL
   "exit_code": $exit_code,
   "started_at": "$STARTED_AT",
   "completed_at": "$(date -Iseconds)",
+  "reason": "$REASON",
   "user": "$USER"
 }
 JSON
@@ -101,5 +110,8 @@ cat >"$STATE_FILE" <<JSON
 }
 JSON
 
+# Start the resource sampler, prepare the results directory
+job_started
+
 # Execute the actual job - exit code captured by trap EXIT
-"$@"
+run_job "$@"
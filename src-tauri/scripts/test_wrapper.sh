@@ -242,6 +242,62 @@ else
 fi
 echo
 
+# Test 17: Resource usage sampling (live samples + final totals)
+echo "Test 17: Resource usage sampled and finalized"
+SOLVERPILOT_SAMPLE_INTERVAL=1 "$WRAPPER" test-job-usage python3 -c "
+import time
+data = bytearray(50 * 1024 * 1024)
+end = time.time() + 2.5
+while time.time() < end: pass
+" >/dev/null 2>&1
+USAGE_FILE=~/.solverpilot-server/jobs/test-job-usage.usage
+if python3 -m json.tool "$USAGE_FILE" >/dev/null 2>&1 && \
+   grep -q '"final": true' "$USAGE_FILE" && \
+   ! grep -q '"peak_rss_kb": 0,' "$USAGE_FILE" && \
+   grep -q '"cpu_seconds": [1-9]' "$USAGE_FILE"; then
+    echo -e "${GREEN}✅ PASS${NC} - Peak memory and CPU time recorded"
+else
+    echo -e "${RED}❌ FAIL${NC} - Resource usage not recorded"
+    cat "$USAGE_FILE" ~/.solverpilot-server/jobs/test-job-usage.status
+    exit 1
+fi
+echo
+
+# Test 18: Memory and CPU time limits reported as distinct reasons
+echo "Test 18: Limit hits reported as distinct reasons"
+SOLVERPILOT_MEM_LIMIT_MB=50 "$WRAPPER" test-job-oom python3 -c "data = bytearray(200 * 1024 * 1024)" >/dev/null 2>&1 || true
+SOLVERPILOT_CPU_TIME_LIMIT=1 "$WRAPPER" test-job-cpu python3 -c "while True: pass" >/dev/null 2>&1 || true
+SOLVERPILOT_MEM_LIMIT_MB=500 "$WRAPPER" test-job-kill bash -c 'kill -9 $$' >/dev/null 2>&1 || true
+if grep -q '"reason": "out of memory' ~/.solverpilot-server/jobs/test-job-oom.status && \
+   grep -q '"reason": "limit exceeded: CPU time' ~/.solverpilot-server/jobs/test-job-cpu.status && \
+   grep -q '"reason": "killed (SIGKILL)"' ~/.solverpilot-server/jobs/test-job-kill.status && \
+   grep -q '"reason": ""' ~/.solverpilot-server/jobs/test-job-args.status; then
+    echo -e "${GREEN}✅ PASS${NC} - Out of memory, CPU time limit and kill reasons recorded"
+else
+    echo -e "${RED}❌ FAIL${NC} - Limit reasons not recorded"
+    cat ~/.solverpilot-server/jobs/test-job-oom.status ~/.solverpilot-server/jobs/test-job-cpu.status ~/.solverpilot-server/jobs/test-job-kill.status
+    exit 1
+fi
+echo
+
+# Test 19: Results CSV kept per job
+echo "Test 19: Results CSV copied to the job's results directory"
+RESULTS_WORKDIR=$(mktemp -d)
+(cd "$RESULTS_WORKDIR" && "$WRAPPER" test-job-results bash -c 'echo "instance,runtime" > benchmark_results.csv') >/dev/null 2>&1
+(cd "$RESULTS_WORKDIR" && "$WRAPPER" test-job-results-env bash -c 'echo "instance" > "$SOLVERPILOT_RESULTS_DIR/benchmark_results.csv"') >/dev/null 2>&1
+(cd "$RESULTS_WORKDIR" && "$WRAPPER" test-job-no-results true) >/dev/null 2>&1
+if grep -q 'instance,runtime' ~/.solverpilot-server/jobs/test-job-results/benchmark_results.csv && \
+   grep -qx 'instance' ~/.solverpilot-server/jobs/test-job-results-env/benchmark_results.csv && \
+   [ ! -f ~/.solverpilot-server/jobs/test-job-no-results/benchmark_results.csv ]; then
+    echo -e "${GREEN}✅ PASS${NC} - Results kept per job, stale working directory CSV ignored"
+else
+    echo -e "${RED}❌ FAIL${NC} - Results CSV not kept per job"
+    ls -R ~/.solverpilot-server/jobs
+    exit 1
+fi
+rm -rf "$RESULTS_WORKDIR"
+echo
+
 echo "=== All Tests Completed ==="
 echo
 echo -e "${GREEN}Summary: All critical tests passed!${NC}"
@@ -21,7 +21,9 @@ CREATE TABLE IF NOT EXISTS jobs (
     error_message TEXT,                      -- Last 20 lines of log if failed
     log_file TEXT,                           -- Path to full log file on server
     progress_current INTEGER,                -- Parsed [x/y] progress (x)
-    progress_total INTEGER                   -- Parsed [x/y] progress (y)
+    progress_total INTEGER,                  -- Parsed [x/y] progress (y)
+    peak_rss_kb INTEGER,                     -- Peak resident memory of the process tree (KiB)
+    cpu_seconds REAL                         -- User + system CPU time at exit
 );
 
 -- Performance indexes
@@ -36,6 +38,7 @@ CREATE TABLE IF NOT EXISTS metadata (
     updated_at TEXT NOT NULL
 );
 
--- Store wrapper version for debugging
-INSERT OR REPLACE INTO metadata (key, value, updated_at)
-VALUES ('wrapper_version', '1.0.0', datetime('now'));
+-- Schema version of this file (existing databases are upgraded by SERVER_MIGRATIONS;
+-- wrapper_version is written when the wrapper is deployed)
+INSERT OR IGNORE INTO metadata (key, value, updated_at)
+VALUES ('schema_version', '2', datetime('now'));
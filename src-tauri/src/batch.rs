@@ -0,0 +1,439 @@
+//! Batch scheduler execution backends
+//!
+//! On a server whose `backend` is a batch scheduler (Slurm, PBS/Torque, SGE) a
+//! job is not started in a tmux session on the login node: it becomes a batch
+//! script (scheduler directives for resources, time limit, queue and account,
+//! then the environment profile and the wrapper invocation) submitted over SSH.
+//! The scheduler's job id is stored on the job (`backend_job_id`); the job is
+//! then tracked with the scheduler's status and accounting commands and
+//! stopped or killed through it.
+//!
+//! Each scheduler implements `BatchScheduler`: it only builds commands and
+//! parses their output, so every backend can be exercised against fake
+//! scheduler scripts. Submission, polling and cancellation over SSH are shared.
+
+use crate::config::{ExecutionBackend, SshConfig};
+use crate::job;
+use crate::limits::JobLimits;
+use crate::pbs::Pbs;
+use crate::sge::Sge;
+use crate::slurm::Slurm;
+use crate::ssh::SshExecutor;
+use crate::state::JobStatus;
+
+/// State of a batch job as reported by its scheduler
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchState {
+    /// Waiting in the scheduler's queue (or held)
+    Queued,
+    /// Running (or being started, suspended, cleaned up)
+    Running,
+    /// Left the scheduler
+    Finished {
+        status: JobStatus,
+        /// Exit code from the scheduler's accounting, when known
+        exit_code: Option<i32>,
+        /// Why the scheduler ended the job (limit reached, node failure, ...)
+        reason: Option<String>,
+    },
+}
+
+impl BatchState {
+    /// Error message of a finished job: the scheduler's reason, else a non-zero exit code
+    pub fn failure_message(&self) -> Option<String> {
+        match self {
+            Self::Finished {
+                reason: Some(reason),
+                ..
+            } => Some(reason.clone()),
+            Self::Finished {
+                exit_code: Some(code),
+                ..
+            } if *code != 0 => Some(format!("Exit code {code}")),
+            _ => None,
+        }
+    }
+}
+
+/// A batch scheduler: batch script directives, commands and output parsing
+pub trait BatchScheduler: Send + Sync {
+    /// Scheduler name, as shown in messages
+    fn name(&self) -> &'static str;
+
+    /// Directive lines of the batch script (`#SBATCH ...`, `#PBS ...`, `#$ ...`)
+    fn directives(&self, job_id: i64, limits: &JobLimits) -> Vec<String>;
+
+    /// Command submitting a batch script; both arguments are shell words
+    fn submit_command(&self, script_path: &str, log_path: &str) -> String;
+
+    /// Scheduler job id from the submit command's output
+    fn parse_submit(&self, output: &str) -> Result<String, String>;
+
+    /// Command printing the state of a job (status command, then accounting)
+    fn state_command(&self, batch_id: &str) -> String;
+
+    /// State from `state_command` output (`None` while not yet visible)
+    fn parse_state(&self, output: &str) -> Option<BatchState>;
+
+    /// Command interrupting (`graceful`) or killing a job
+    fn cancel_command(&self, batch_id: &str, graceful: bool) -> String;
+}
+
+/// Batch scheduler of a server (`None` = tmux sessions or local process groups)
+///
+/// # Errors
+/// Returns error if the scheduler's options are invalid
+pub fn scheduler(ssh: &SshConfig) -> Result<Option<Box<dyn BatchScheduler>>, String> {
+    let scheduler: Box<dyn BatchScheduler> = match ssh.backend {
+        ExecutionBackend::Tmux | ExecutionBackend::Local => return Ok(None),
+        ExecutionBackend::Slurm => Box::new(Slurm::new(ssh.slurm.clone().unwrap_or_default())?),
+        ExecutionBackend::Pbs => Box::new(Pbs::new(ssh.pbs.clone().unwrap_or_default())?),
+        ExecutionBackend::Sge => Box::new(Sge::new(ssh.sge.clone().unwrap_or_default())?),
+    };
+    Ok(Some(scheduler))
+}
+
+/// Batch job name of a job
+pub fn job_name(job_id: i64) -> String {
+    format!("solverpilot_{job_id}")
+}
+
+/// Reject an option value that would break out of a directive line
+pub fn validate_word(name: &str, value: Option<&str>) -> Result<(), String> {
+    match value {
+        Some(value) if value.is_empty() || value.chars().any(char::is_whitespace) => {
+            Err(format!("Invalid {name} '{value}'"))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Reject extra directive arguments that are not options or span several lines
+pub fn validate_extra_args(args: &[String]) -> Result<(), String> {
+    args.iter()
+        .find(|arg| !arg.starts_with('-') || arg.contains(['\n', '\r']))
+        .map_or(Ok(()), |arg| {
+            Err(format!("Invalid scheduler option '{arg}'"))
+        })
+}
+
+/// CPUs requested for a job: its CPU quota in whole cores, else `default`
+pub fn cpus(limits: &JobLimits, default: Option<u32>) -> Option<u32> {
+    limits
+        .cpu_quota_percent
+        .map(|percent| percent.div_ceil(100))
+        .or(default)
+}
+
+/// Batch script of a job
+///
+/// Memory and CPU limits become scheduler resources. The script `cd`s to the
+/// home directory, where a tmux job starts too (the wrapper's relative paths
+/// depend on it), whatever the scheduler's default; `body` is the shell
+/// command that runs the job (environment profile, wrapper invocation).
+pub fn batch_script(
+    scheduler: &dyn BatchScheduler,
+    job_id: i64,
+    limits: &JobLimits,
+    body: &str,
+) -> String {
+    let mut script = String::from("#!/bin/bash\n");
+    for directive in scheduler.directives(job_id, limits) {
+        script.push_str(&directive);
+        script.push('\n');
+    }
+    script.push_str("export PYTHONUNBUFFERED=1\n");
+    script.push_str("cd \"$HOME\"\n");
+    script.push_str(body);
+    script.push('\n');
+    script
+}
+
+/// Write the batch script next to the job's log and submit it
+pub fn submit_command(
+    scheduler: &dyn BatchScheduler,
+    jobs_path: &str,
+    job_id: i64,
+    script: &str,
+) -> String {
+    let script_path = job::shell_path(&format!("{jobs_path}/{job_id}.batch.sh"));
+    let log_path = job::shell_path(&format!("{jobs_path}/{job_id}.log"));
+    format!(
+        "mkdir -p {jobs} && cat > {script_path} <<'SOLVERPILOT_BATCH_EOF'\n{script}SOLVERPILOT_BATCH_EOF\n{submit}",
+        jobs = job::shell_path(jobs_path),
+        submit = scheduler.submit_command(&script_path, &log_path),
+    )
+}
+
+/// Submit a job, returning its scheduler job id
+///
+/// Its output goes to the job's usual log in the remote jobs directory.
+///
+/// # Errors
+/// Returns error if submission fails or prints no job id
+pub async fn submit_job(
+    scheduler: &dyn BatchScheduler,
+    executor: &SshExecutor,
+    jobs_path: &str,
+    job_id: i64,
+    limits: &JobLimits,
+    body: &str,
+) -> Result<String, String> {
+    let script = batch_script(scheduler, job_id, limits, body);
+    let output = executor
+        .execute(&submit_command(scheduler, jobs_path, job_id, &script))
+        .await
+        .map_err(|e| format!("{} submission failed: {e}", scheduler.name()))?;
+    scheduler.parse_submit(&output)
+}
+
+/// Current state of a batch job (`None` while not yet visible to the scheduler)
+///
+/// # Errors
+/// Returns error if the SSH command fails
+pub async fn query_state(
+    scheduler: &dyn BatchScheduler,
+    executor: &SshExecutor,
+    batch_id: &str,
+) -> Result<Option<BatchState>, String> {
+    let output = executor
+        .execute_ignore_status(&scheduler.state_command(batch_id))
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(scheduler.parse_state(&output))
+}
+
+/// Stop (`graceful`) or kill a batch job
+///
+/// # Errors
+/// Returns error if the scheduler refuses the cancellation
+pub async fn cancel(
+    scheduler: &dyn BatchScheduler,
+    executor: &SshExecutor,
+    batch_id: &str,
+    graceful: bool,
+) -> Result<(), String> {
+    executor
+        .execute(&scheduler.cancel_command(batch_id, graceful))
+        .await
+        .map_err(|e| format!("{} cancellation failed: {e}", scheduler.name()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pbs::PbsConfig;
+    use crate::sge::SgeConfig;
+    use crate::slurm::SlurmConfig;
+    use std::path::Path;
+
+    /// Fake scheduler commands in `$HOME/bin`: each records its arguments in
+    /// `$HOME/<name>.args`; the submit command also keeps the submitted script
+    fn write_stubs(home: &Path, stubs: &[(&str, &str)]) -> Result<(), Box<dyn std::error::Error>> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let bin = home.join("bin");
+        std::fs::create_dir_all(&bin)?;
+        for (name, output) in stubs {
+            let path = bin.join(name);
+            std::fs::write(
+                &path,
+                format!(
+                    "#!/bin/sh\necho \"$@\" > \"$HOME/{name}.args\"\nfor last; do :; done\n[ -f \"$last\" ] && cp \"$last\" \"$HOME/submitted.sh\"\ncat <<'EOF'\n{output}\nEOF\n"
+                ),
+            )?;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+        }
+        Ok(())
+    }
+
+    /// Run a command like the SSH executor would, with the stubs first on `PATH`
+    async fn run(home: &Path, command: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let path = format!(
+            "{}:{}",
+            home.join("bin").display(),
+            std::env::var("PATH").unwrap_or_default()
+        );
+        let output = tokio::process::Command::new("bash")
+            .args(["-c", command])
+            .env("HOME", home)
+            .env("PATH", path)
+            .output()
+            .await?;
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn args(home: &Path, name: &str) -> Result<String, std::io::Error> {
+        Ok(std::fs::read_to_string(home.join(format!("{name}.args")))?
+            .trim()
+            .to_string())
+    }
+
+    /// Submit, query and cancel a job through `scheduler` against fake commands
+    async fn submit_and_track(
+        scheduler: &dyn BatchScheduler,
+        home: &Path,
+    ) -> Result<(String, Option<BatchState>), Box<dyn std::error::Error>> {
+        let script = batch_script(scheduler, 7, &JobLimits::default(), "echo $HOME");
+        let output = run(
+            home,
+            &submit_command(scheduler, "~/benchmarks/jobs", 7, &script),
+        )
+        .await?;
+        let batch_id = scheduler.parse_submit(&output)?;
+        assert_eq!(std::fs::read_to_string(home.join("submitted.sh"))?, script);
+        assert!(script.ends_with("export PYTHONUNBUFFERED=1\ncd \"$HOME\"\necho $HOME\n"));
+
+        let state = scheduler.parse_state(&run(home, &scheduler.state_command(&batch_id)).await?);
+        run(home, &scheduler.cancel_command(&batch_id, false)).await?;
+        Ok((batch_id, state))
+    }
+
+    #[test]
+    fn test_failure_message() {
+        let finished = |exit_code, reason: Option<&str>| BatchState::Finished {
+            status: JobStatus::Failed,
+            exit_code,
+            reason: reason.map(str::to_string),
+        };
+        assert_eq!(
+            finished(Some(137), Some("Time limit reached")).failure_message(),
+            Some("Time limit reached".to_string())
+        );
+        assert_eq!(
+            finished(Some(2), None).failure_message(),
+            Some("Exit code 2".to_string())
+        );
+        assert_eq!(finished(Some(0), None).failure_message(), None);
+        assert_eq!(BatchState::Running.failure_message(), None);
+    }
+
+    #[test]
+    fn test_validate_options() {
+        assert!(validate_word("queue", Some("batch")).is_ok());
+        assert!(validate_word("queue", None).is_ok());
+        assert!(validate_word("queue", Some("a b")).is_err());
+        assert!(validate_extra_args(&["--qos=normal".to_string()]).is_ok());
+        assert!(validate_extra_args(&["--qos=x\n#SBATCH --account=other".to_string()]).is_err());
+        assert!(validate_extra_args(&["qos".to_string()]).is_err());
+    }
+
+    /// Paths with shell syntax are written and submitted as-is, never expanded
+    #[tokio::test]
+    async fn test_submit_quotes_paths() -> Result<(), Box<dyn std::error::Error>> {
+        let home = tempfile::tempdir()?;
+        write_stubs(home.path(), &[("sbatch", "4242")])?;
+        let slurm = Slurm::new(SlurmConfig::default())?;
+
+        let jobs_path = "~/it's $(touch ~/pwned) `touch ~/pwned` \"a\\b\"/jobs";
+        let script = batch_script(&slurm, 7, &JobLimits::default(), "true");
+        run(home.path(), &submit_command(&slurm, jobs_path, 7, &script)).await?;
+
+        let written = home
+            .path()
+            .join("it's $(touch ~/pwned) `touch ~/pwned` \"a\\b\"/jobs/7.batch.sh");
+        assert_eq!(std::fs::read_to_string(written)?, script);
+        assert!(!home.path().join("pwned").exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_slurm_against_fake_scheduler() -> Result<(), Box<dyn std::error::Error>> {
+        let home = tempfile::tempdir()?;
+        // Not in squeue any more: state and exit code come from sacct
+        write_stubs(
+            home.path(),
+            &[
+                ("sbatch", "4242;cluster"),
+                ("squeue", ""),
+                ("sacct", "TIMEOUT|0:15"),
+                ("scancel", ""),
+            ],
+        )?;
+        let slurm = Slurm::new(SlurmConfig::default())?;
+
+        let (batch_id, state) = submit_and_track(&slurm, home.path()).await?;
+        assert_eq!(batch_id, "4242");
+        assert!(args(home.path(), "sbatch")?.starts_with(&format!(
+            "--parsable --output={}/benchmarks/jobs/7.log",
+            home.path().display()
+        )));
+        assert_eq!(
+            state,
+            Some(BatchState::Finished {
+                status: JobStatus::Failed,
+                exit_code: Some(0),
+                reason: Some("Slurm time limit reached".to_string()),
+            })
+        );
+        assert_eq!(args(home.path(), "scancel")?, "4242");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pbs_against_fake_scheduler() -> Result<(), Box<dyn std::error::Error>> {
+        let home = tempfile::tempdir()?;
+        write_stubs(
+            home.path(),
+            &[
+                ("qsub", "1234.pbs-server"),
+                (
+                    "qstat",
+                    "Job Id: 1234.pbs-server\n    job_state = F\n    Exit_status = 3",
+                ),
+                ("qdel", ""),
+            ],
+        )?;
+        let pbs = Pbs::new(PbsConfig::default())?;
+
+        let (batch_id, state) = submit_and_track(&pbs, home.path()).await?;
+        assert_eq!(batch_id, "1234.pbs-server");
+        assert!(args(home.path(), "qsub")?.starts_with(&format!(
+            "-j oe -o {}/benchmarks/jobs/7.log",
+            home.path().display()
+        )));
+        assert_eq!(args(home.path(), "qstat")?, "-x -f 1234.pbs-server");
+        let state = state.ok_or("no PBS state")?;
+        assert_eq!(state.failure_message().as_deref(), Some("Exit code 3"));
+        assert_eq!(args(home.path(), "qdel")?, "1234.pbs-server");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sge_against_fake_scheduler() -> Result<(), Box<dyn std::error::Error>> {
+        let home = tempfile::tempdir()?;
+        // Gone from qstat: exit status from qacct
+        write_stubs(
+            home.path(),
+            &[
+                ("qsub", "5678"),
+                ("qstat", ""),
+                (
+                    "qacct",
+                    "==============================================================\nqname        all.q\njobnumber    5678\nfailed       0\nexit_status  0",
+                ),
+                ("qdel", ""),
+            ],
+        )?;
+        let sge = Sge::new(SgeConfig::default())?;
+
+        let (batch_id, state) = submit_and_track(&sge, home.path()).await?;
+        assert_eq!(batch_id, "5678");
+        assert!(args(home.path(), "qsub")?.starts_with(&format!(
+            "-terse -j y -o {}/benchmarks/jobs/7.log",
+            home.path().display()
+        )));
+        assert_eq!(args(home.path(), "qacct")?, "-j 5678");
+        assert_eq!(
+            state,
+            Some(BatchState::Finished {
+                status: JobStatus::Completed,
+                exit_code: Some(0),
+                reason: None,
+            })
+        );
+        assert_eq!(args(home.path(), "qdel")?, "5678");
+        Ok(())
+    }
+}
@@ -0,0 +1,243 @@
+//! Code version capture for jobs
+//!
+//! At queue time, every job records which version of the code it runs:
+//! - Local git state of the benchmark directory (HEAD, branch, dirty flag, diff)
+//! - SHA-256 of each file synced by `sync_benchmark_deps`
+//!
+//! File contents are kept in a local content-addressed store (`snapshots/objects/<sha256>`)
+//! so a job's exact files can be restored and synced again ("rerun this version").
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+/// Maximum size of the recorded `git diff` (larger diffs are truncated)
+const MAX_DIFF_BYTES: usize = 512 * 1024;
+
+/// Hash of one synced file, relative to the code root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileHash {
+    pub path: String,
+    pub sha256: String,
+}
+
+/// Version of the code a job runs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeVersion {
+    pub captured_at: String,
+    /// Local directory the benchmark files are relative to
+    pub code_root: String,
+    /// `None` when the code is not in a git repository
+    pub git_commit: Option<String>,
+    pub git_branch: Option<String>,
+    pub git_dirty: bool,
+    /// `git diff HEAD` output when dirty (truncated to 512 KiB)
+    pub git_diff: Option<String>,
+    pub files: Vec<FileHash>,
+}
+
+/// Hex SHA-256 of a byte slice
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Run a git command in `dir`, returning trimmed stdout on success
+async fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .await
+        .ok()?;
+
+    output.status.success().then(|| {
+        String::from_utf8_lossy(&output.stdout)
+            .trim_end()
+            .to_string()
+    })
+}
+
+/// Capture git state and file hashes, storing file contents in `store_dir`
+///
+/// `files` are absolute paths (as returned by the dependency analysis); they are
+/// recorded relative to `code_root`.
+pub async fn capture(
+    code_root: &Path,
+    files: &[String],
+    store_dir: &Path,
+) -> Result<CodeVersion, String> {
+    let objects_dir = store_dir.join("objects");
+    std::fs::create_dir_all(&objects_dir)
+        .map_err(|e| format!("Failed to create snapshot store: {e}"))?;
+
+    let mut hashes = Vec::with_capacity(files.len());
+    for file in files {
+        let path = Path::new(file);
+        let content =
+            std::fs::read(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let sha256 = sha256_hex(&content);
+
+        let object = objects_dir.join(&sha256);
+        if !object.exists() {
+            std::fs::write(&object, &content)
+                .map_err(|e| format!("Failed to store snapshot of {}: {e}", path.display()))?;
+        }
+
+        let relative = path.strip_prefix(code_root).unwrap_or(path);
+        hashes.push(FileHash {
+            path: relative.display().to_string(),
+            sha256,
+        });
+    }
+
+    let git_commit = git(code_root, &["rev-parse", "HEAD"]).await;
+    let (git_branch, git_dirty, git_diff) = if git_commit.is_some() {
+        let branch = git(code_root, &["rev-parse", "--abbrev-ref", "HEAD"]).await;
+        let dirty = git(code_root, &["status", "--porcelain"])
+            .await
+            .is_some_and(|s| !s.is_empty());
+        let diff = if dirty {
+            git(code_root, &["diff", "HEAD"]).await.map(truncate_diff)
+        } else {
+            None
+        };
+        (branch, dirty, diff)
+    } else {
+        (None, false, None)
+    };
+
+    Ok(CodeVersion {
+        captured_at: chrono::Utc::now().to_rfc3339(),
+        code_root: code_root.display().to_string(),
+        git_commit,
+        git_branch,
+        git_dirty,
+        git_diff,
+        files: hashes,
+    })
+}
+
+fn truncate_diff(mut diff: String) -> String {
+    if diff.len() > MAX_DIFF_BYTES {
+        let mut end = MAX_DIFF_BYTES;
+        while !diff.is_char_boundary(end) {
+            end -= 1;
+        }
+        diff.truncate(end);
+        diff.push_str("\n... (diff truncated)\n");
+    }
+    diff
+}
+
+/// Materialize the snapshot files of `version` under `target_dir`
+///
+/// Contents are verified against their recorded hash. Returns the absolute
+/// paths of the restored files (same layout as the original code root).
+pub fn restore(
+    version: &CodeVersion,
+    store_dir: &Path,
+    target_dir: &Path,
+) -> Result<Vec<String>, String> {
+    let mut restored = Vec::with_capacity(version.files.len());
+
+    for file in &version.files {
+        let relative = Path::new(&file.path);
+        if relative.is_absolute()
+            || relative
+                .components()
+                .any(|c| matches!(c, std::path::Component::ParentDir))
+        {
+            return Err(format!("Invalid snapshot path: {}", file.path));
+        }
+
+        let content = std::fs::read(store_dir.join("objects").join(&file.sha256))
+            .map_err(|e| format!("Snapshot of {} is missing: {e}", file.path))?;
+        if sha256_hex(&content) != file.sha256 {
+            return Err(format!("Snapshot of {} is corrupted", file.path));
+        }
+
+        let target: PathBuf = target_dir.join(relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        std::fs::write(&target, content)
+            .map_err(|e| format!("Failed to restore {}: {e}", file.path))?;
+        restored.push(target.display().to_string());
+    }
+
+    Ok(restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_capture_and_restore_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let code = tempfile::tempdir()?;
+        let store = tempfile::tempdir()?;
+        let restore_dir = tempfile::tempdir()?;
+
+        std::fs::create_dir_all(code.path().join("solvers"))?;
+        let bench = code.path().join("bench.py");
+        let helper = code.path().join("solvers/model.py");
+        std::fs::write(&bench, "import solvers.model\n")?;
+        std::fs::write(&helper, "X = 1\n")?;
+
+        let files = vec![bench.display().to_string(), helper.display().to_string()];
+        let version = capture(code.path(), &files, store.path()).await?;
+
+        // Not a git repository
+        assert_eq!(version.git_commit, None);
+        assert!(!version.git_dirty);
+        assert_eq!(version.files[1].path, "solvers/model.py");
+        assert_eq!(version.files[1].sha256, sha256_hex(b"X = 1\n"));
+
+        // Later edits do not affect the snapshot
+        std::fs::write(&helper, "X = 2\n")?;
+        let restored = restore(&version, store.path(), restore_dir.path())?;
+        assert_eq!(restored.len(), 2);
+        assert_eq!(
+            std::fs::read_to_string(restore_dir.path().join("solvers/model.py"))?,
+            "X = 1\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_rejects_escaping_paths() {
+        let version = CodeVersion {
+            captured_at: String::new(),
+            code_root: String::new(),
+            git_commit: None,
+            git_branch: None,
+            git_dirty: false,
+            git_diff: None,
+            files: vec![FileHash {
+                path: "../evil.py".to_string(),
+                sha256: String::new(),
+            }],
+        };
+        let dir = std::env::temp_dir();
+        assert!(restore(&version, &dir, &dir).is_err());
+    }
+
+    #[test]
+    fn test_truncate_diff() {
+        let diff = "é".repeat(MAX_DIFF_BYTES);
+        let truncated = truncate_diff(diff);
+        assert!(truncated.len() <= MAX_DIFF_BYTES + 32);
+        assert!(truncated.ends_with("(diff truncated)\n"));
+    }
+}
@@ -1,23 +1,72 @@
+use sqlx::SqlitePool;
 use std::path::Path;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{Emitter, State};
 
-use crate::config::AppConfig;
+use crate::config::{AppConfig, ExecutionBackend};
+use crate::history::{HistoryFilters, HistoryPage, HistoryQuery, HistoryStats};
+use crate::license::PreflightOutcome;
+use crate::servers::ServerInfo;
 use crate::ssh::SshKeyStatus;
-use crate::state::{AppState, Benchmark, Job, JobStatus, JobStatusResponse, Project, SyncStatus};
-use crate::{db, job, project, python_deps, queue_service, ssh};
-
-// Helper macro to get SSH manager from state
-macro_rules! get_ssh_manager {
-    ($state:expr) => {
-        $state
-            .ssh_manager
-            .lock()
-            .await
-            .as_ref()
-            .ok_or("SSH not initialized - call init_ssh first")?
-            .clone()
-    };
+use crate::state::{
+    AppState, Benchmark, Job, JobSearchFilters, JobSearchHit, JobStatus, JobStatusResponse,
+    Project, SyncStatus, Tag,
+};
+use crate::{
+    batch, code_version, container, db, dispatch, eta, fingerprint, job, limits, local,
+    log_archive, log_stream, project, python_deps, queue_service, report, results, servers, ssh,
+    usage,
+};
+
+/// Config vue par un serveur (`None` = serveur par défaut)
+async fn server_config(state: &AppState, server: Option<&str>) -> Result<AppConfig, String> {
+    let config = state
+        .config
+        .lock()
+        .await
+        .clone()
+        .ok_or("Config non chargée")?;
+    let name = config.resolve_server(server)?;
+    config.for_server(&name)
+}
+
+/// Config vue par un serveur et son SSH manager (`None` = serveur par défaut)
+async fn server_context(
+    state: &AppState,
+    server: Option<&str>,
+) -> Result<(AppConfig, ssh::SshManager), String> {
+    let config = state
+        .config
+        .lock()
+        .await
+        .clone()
+        .ok_or("Config non chargée")?;
+    servers::resolve(&state.ssh_managers, &config, server).await
+}
+
+/// Config et SSH manager du serveur sur lequel un job a été lancé
+async fn job_server_context(
+    state: &AppState,
+    pool: &SqlitePool,
+    job_id: i64,
+) -> Result<(AppConfig, ssh::SshManager), String> {
+    let server = db::get_job_server(pool, job_id).await?;
+    server_context(state, Some(servers::job_server(server.as_deref()))).await
+}
+
+/// Identifiant d'un job dans l'ordonnanceur qui l'a reçu
+async fn batch_job_id(pool: &SqlitePool, job_id: i64) -> Result<String, String> {
+    db::get_job_backend_id(pool, job_id)
+        .await?
+        .ok_or_else(|| format!("Job {job_id} has no batch scheduler job id"))
+}
+
+/// Groupe de processus d'un job du backend local
+async fn local_job_pgid(pool: &SqlitePool, job_id: i64) -> Result<u32, String> {
+    let pgid = db::get_job_backend_id(pool, job_id)
+        .await?
+        .ok_or_else(|| format!("Job {job_id} has no local process group"))?;
+    local::parse_pgid(&pgid)
 }
 
 // ============================================================================
@@ -40,6 +89,7 @@ pub fn get_config_path() -> Result<String, String> {
 #[tauri::command]
 pub async fn save_config(state: State<'_, AppState>, config: AppConfig) -> Result<(), String> {
     config.save()?;
+    let windows = config.execution_windows()?;
 
     // Initialiser la DB si pas déjà fait
     let db_path = AppConfig::db_path()?;
@@ -58,6 +108,7 @@ pub async fn save_config(state: State<'_, AppState>, config: AppConfig) -> Resul
         .restore_state(saved_state)
         .await;
     tracing::info!("Queue state restored on config save");
+    state.queue_manager.lock().await.set_windows(windows).await;
 
     Ok(())
 }
@@ -83,6 +134,12 @@ pub async fn load_config(state: State<'_, AppState>) -> Result<AppConfig, String
         .restore_state(saved_state)
         .await;
     tracing::info!("Queue state restored on startup");
+    state
+        .queue_manager
+        .lock()
+        .await
+        .set_windows(config.execution_windows()?)
+        .await;
 
     Ok(config)
 }
@@ -91,8 +148,14 @@ pub async fn load_config(state: State<'_, AppState>) -> Result<AppConfig, String
 // SSH
 // ============================================================================
 
+/// Initialise le SSH manager d'un serveur, ou de tous les serveurs configurés
+///
+/// Sans `server`, échoue seulement si aucun serveur n'est joignable.
 #[tauri::command]
-pub async fn init_ssh(state: State<'_, AppState>) -> Result<String, String> {
+pub async fn init_ssh(
+    state: State<'_, AppState>,
+    server: Option<String>,
+) -> Result<String, String> {
     let config = state
         .config
         .lock()
@@ -100,42 +163,54 @@ pub async fn init_ssh(state: State<'_, AppState>) -> Result<String, String> {
         .clone()
         .ok_or("Config non chargée")?;
 
-    // Create SSH authentication
-    let key_path = ssh::get_ssh_key_path(&config);
-    let auth = ssh::SshAuth::key(key_path);
-
-    // Create SSH manager with connection pool (size: 10)
-    let manager = ssh::SshManager::new(config.clone(), auth, 10)
-        .await
-        .map_err(|e| e.to_string())?;
+    let names = match server {
+        Some(ref name) => vec![config.resolve_server(Some(name))?],
+        None => config.server_names(),
+    };
 
-    let info = format!(
-        "SSH manager initialized for {}:{}",
-        config.ssh.host, config.ssh.port
-    );
+    let mut initialized = Vec::new();
+    let mut errors = Vec::new();
+    for name in names {
+        match servers::connect(&config, &name, None).await {
+            Ok(manager) => {
+                let view = config.for_server(&name)?;
+                initialized.push(format!("{}:{}", view.ssh.host, view.ssh.port));
+                // Store manager in state
+                state.ssh_managers.lock().await.insert(name, manager);
+            }
+            Err(e) => {
+                tracing::warn!("SSH initialization failed: {}", e);
+                errors.push(e);
+            }
+        }
+    }
 
-    // Store manager in state
-    *state.ssh_manager.lock().await = Some(manager);
+    if initialized.is_empty() {
+        return Err(errors.join("; "));
+    }
 
-    Ok(info)
+    let failed = if errors.is_empty() {
+        String::new()
+    } else {
+        format!(" (failed: {})", errors.join("; "))
+    };
+    Ok(format!(
+        "SSH manager initialized for {}{failed}",
+        initialized.join(", ")
+    ))
 }
 
 #[tauri::command]
 pub async fn close_ssh(state: State<'_, AppState>) -> Result<(), String> {
-    // Drop the SSH manager (closes all connections)
-    *state.ssh_manager.lock().await = None;
+    // Stop live log streams, then drop the SSH managers (closes all connections)
+    state.log_streams.stop_all().await;
+    state.ssh_managers.lock().await.clear();
     Ok(())
 }
 
 #[tauri::command]
-pub async fn test_ssh(state: State<'_, AppState>) -> Result<bool, String> {
-    let manager = state
-        .ssh_manager
-        .lock()
-        .await
-        .as_ref()
-        .ok_or("SSH not initialized")?
-        .clone();
+pub async fn test_ssh(state: State<'_, AppState>, server: Option<String>) -> Result<bool, String> {
+    let (_, manager) = server_context(&state, server.as_deref()).await?;
 
     manager.test_connection().await.map_err(|e| e.to_string())?;
     Ok(true)
@@ -146,13 +221,13 @@ pub async fn test_ssh(state: State<'_, AppState>) -> Result<bool, String> {
 pub async fn test_ssh_direct(
     state: State<'_, AppState>,
     passphrase: Option<String>,
+    server: Option<String>,
 ) -> Result<(), String> {
-    let config = state
-        .config
-        .lock()
-        .await
-        .clone()
-        .ok_or("Config non chargée")?;
+    let config = server_config(&state, server.as_deref()).await?;
+    // Serveur local : pas de connexion à tester
+    if config.ssh.backend == ExecutionBackend::Local {
+        return Ok(());
+    }
 
     let key_path = ssh::get_ssh_key_path(&config);
 
@@ -169,13 +244,14 @@ pub async fn test_ssh_direct(
 }
 
 #[tauri::command]
-pub async fn check_ssh_key_status(state: State<'_, AppState>) -> Result<SshKeyStatus, String> {
-    let config = state
-        .config
-        .lock()
-        .await
-        .clone()
-        .ok_or("Config non chargée")?;
+pub async fn check_ssh_key_status(
+    state: State<'_, AppState>,
+    server: Option<String>,
+) -> Result<SshKeyStatus, String> {
+    let config = server_config(&state, server.as_deref()).await?;
+    if config.ssh.backend == ExecutionBackend::Local {
+        return Ok(SshKeyStatus::Ready);
+    }
 
     let key_path = ssh::get_ssh_key_path(&config);
     let auth = ssh::SshAuth::key(key_path);
@@ -184,68 +260,58 @@ pub async fn check_ssh_key_status(state: State<'_, AppState>) -> Result<SshKeySt
 }
 
 #[tauri::command]
-pub async fn add_ssh_key(state: State<'_, AppState>, passphrase: String) -> Result<(), String> {
+pub async fn add_ssh_key(
+    state: State<'_, AppState>,
+    passphrase: String,
+    server: Option<String>,
+) -> Result<(), String> {
     let config = state
         .config
         .lock()
         .await
         .clone()
         .ok_or("Config non chargée")?;
+    let name = config.resolve_server(server.as_deref())?;
 
     // Re-initialize SSH manager with passphrase
-    let key_path = ssh::get_ssh_key_path(&config);
-    let auth = ssh::SshAuth::key_with_passphrase(key_path, passphrase);
-
-    let manager = ssh::SshManager::new(config.clone(), auth, 10)
-        .await
-        .map_err(|e| e.to_string())?;
+    let manager = servers::connect(&config, &name, Some(passphrase)).await?;
 
-    *state.ssh_manager.lock().await = Some(manager);
+    state.ssh_managers.lock().await.insert(name, manager);
 
     Ok(())
 }
 
+/// Liste les serveurs configurés et indique ceux dont le SSH est initialisé
+#[tauri::command]
+pub async fn list_servers(state: State<'_, AppState>) -> Result<Vec<ServerInfo>, String> {
+    let config = state
+        .config
+        .lock()
+        .await
+        .clone()
+        .ok_or("Config non chargée")?;
+
+    servers::list(&config, &*state.ssh_managers.lock().await)
+}
+
 // ============================================================================
 // Server Database
 // ============================================================================
 
 /// Initialize server-side database via SSH
-/// Generates SQL script and executes on remote server at ~/.solverpilot-server/server.db
+/// Creates ~/.solverpilot-server/server.db, or migrates an existing one to the current schema
 #[tauri::command]
-pub async fn init_server_db(state: State<'_, AppState>) -> Result<(), String> {
-    let manager = get_ssh_manager!(state);
-
-    // Generate SQL initialization script
-    let init_script = crate::server_db::generate_init_script();
-
-    // Create remote directory
-    let mkdir_cmd = "mkdir -p ~/.solverpilot-server";
-    manager
-        .executor()
-        .execute(mkdir_cmd)
-        .await
-        .map_err(|e| format!("Failed to create server directory: {e}"))?;
-
-    // Execute SQL script on remote server
-    // Using heredoc to avoid escaping issues
-    let sql_cmd =
-        format!("sqlite3 ~/.solverpilot-server/server.db <<'SQL_EOF'\n{init_script}\nSQL_EOF");
-
-    manager
-        .executor()
-        .execute(&sql_cmd)
-        .await
-        .map_err(|e| format!("Failed to initialize server database: {e}"))?;
-
-    // Set database permissions to 0600
-    let chmod_cmd = "chmod 600 ~/.solverpilot-server/server.db";
-    manager
-        .executor()
-        .execute(chmod_cmd)
-        .await
-        .map_err(|e| format!("Failed to set database permissions: {e}"))?;
+pub async fn init_server_db(
+    state: State<'_, AppState>,
+    server: Option<String>,
+) -> Result<(), String> {
+    let (_, manager) = server_context(&state, server.as_deref()).await?;
+    let wrapper_mgr = crate::wrapper::WrapperManager::new();
 
-    tracing::info!("Server database initialized at ~/.solverpilot-server/server.db");
+    let install = wrapper_mgr.inspect_server(manager.executor()).await?;
+    wrapper_mgr
+        .ensure_server_db(manager.executor(), &install)
+        .await?;
 
     Ok(())
 }
@@ -256,50 +322,71 @@ pub async fn init_server_db(state: State<'_, AppState>) -> Result<(), String> {
 
 /// Check if wrapper script is installed on remote server
 #[tauri::command]
-pub async fn check_wrapper_installed(state: State<'_, AppState>) -> Result<bool, String> {
-    let manager = get_ssh_manager!(state);
+pub async fn check_wrapper_installed(
+    state: State<'_, AppState>,
+    server: Option<String>,
+) -> Result<bool, String> {
+    let (_, manager) = server_context(&state, server.as_deref()).await?;
     let wrapper_mgr = crate::wrapper::WrapperManager::new();
 
     wrapper_mgr.check_installed(manager.executor()).await
 }
 
-/// Deploy wrapper script to remote server
-/// Also initializes server database if not already done
+/// Query license status on the remote server (preflight check on demand)
+///
+/// Uses the active project's environment profile so the solver CLI is on `PATH`.
 #[tauri::command]
-pub async fn deploy_wrapper(state: State<'_, AppState>) -> Result<(), String> {
-    let manager = get_ssh_manager!(state);
-    let wrapper_mgr = crate::wrapper::WrapperManager::new();
+pub async fn check_license(
+    state: State<'_, AppState>,
+    server: Option<String>,
+) -> Result<crate::license::LicenseReport, String> {
+    let (config, manager) = server_context(&state, server.as_deref()).await?;
 
-    // Check if already installed (idempotent)
-    if wrapper_mgr.check_installed(manager.executor()).await? {
-        tracing::debug!("Wrapper already installed, skipping deployment");
-        return Ok(());
-    }
+    let pool = state.db.lock().await.clone();
+    let project_id = *state.current_project_id.lock().await;
+    let project_profile = match (pool, project_id) {
+        (Some(pool), Some(project_id)) => db::get_project(&pool, project_id)
+            .await?
+            .and_then(|p| p.env_profile),
+        _ => None,
+    };
 
-    // Deploy wrapper script
-    wrapper_mgr.deploy_to_server(manager.executor()).await?;
+    let env_prelude = config
+        .resolve_env_profile(None, project_profile.as_deref())?
+        .map(|profile| profile.to_shell_prelude())
+        .unwrap_or_default();
+
+    let status =
+        crate::license::query_license(manager.executor(), &env_prelude, &config.license.command)
+            .await?;
+    let outcome = crate::license::evaluate(
+        &status,
+        chrono::Local::now().date_naive(),
+        config.license.warn_days,
+    );
 
-    // Initialize server database
-    init_server_db(state).await?;
+    Ok(crate::license::LicenseReport { status, outcome })
+}
 
-    // Update wrapper version in metadata (using heredoc to avoid SQL injection risks)
-    let update_version_cmd = format!(
-        "sqlite3 ~/.solverpilot-server/server.db <<'SQL_EOF'\nINSERT OR REPLACE INTO metadata (key, value) VALUES ('wrapper_version', '{}');\nSQL_EOF",
-        crate::wrapper::WRAPPER_VERSION
-    );
+/// Deploy wrapper script to remote server
+/// Also initializes or migrates the server database; an outdated wrapper is
+/// upgraded, a newer one is left in place
+#[tauri::command]
+pub async fn deploy_wrapper(
+    state: State<'_, AppState>,
+    server: Option<String>,
+) -> Result<crate::wrapper::ServerUpgrade, String> {
+    let (_, manager) = server_context(&state, server.as_deref()).await?;
+    let wrapper_mgr = crate::wrapper::WrapperManager::new();
 
-    manager
-        .executor()
-        .execute(&update_version_cmd)
-        .await
-        .map_err(|e| format!("Failed to update wrapper version in metadata: {e}"))?;
+    let upgrade = wrapper_mgr.ensure_server_ready(manager.executor()).await?;
 
     tracing::info!(
         "Wrapper deployment complete (version {})",
         crate::wrapper::WRAPPER_VERSION
     );
 
-    Ok(())
+    Ok(upgrade)
 }
 
 // ============================================================================
@@ -307,13 +394,11 @@ pub async fn deploy_wrapper(state: State<'_, AppState>) -> Result<(), String> {
 // ============================================================================
 
 #[tauri::command]
-pub async fn check_sync_status(state: State<'_, AppState>) -> Result<SyncStatus, String> {
-    let _config = state
-        .config
-        .lock()
-        .await
-        .clone()
-        .ok_or("Config non chargée")?;
+pub async fn check_sync_status(
+    state: State<'_, AppState>,
+    server: Option<String>,
+) -> Result<SyncStatus, String> {
+    let (_, manager) = server_context(&state, server.as_deref()).await?;
 
     let pool = state
         .db
@@ -336,7 +421,7 @@ pub async fn check_sync_status(state: State<'_, AppState>) -> Result<SyncStatus,
 
     let project_dir = project::project_path(&proj.name)?;
 
-    match get_ssh_manager!(state)
+    match manager
         .transfer()
         .dry_run_project(&proj.name, &project_dir)
         .await
@@ -358,13 +443,8 @@ pub async fn check_sync_status(state: State<'_, AppState>) -> Result<SyncStatus,
 }
 
 #[tauri::command]
-pub async fn sync_code(state: State<'_, AppState>) -> Result<(), String> {
-    let _config = state
-        .config
-        .lock()
-        .await
-        .clone()
-        .ok_or("Config non chargée")?;
+pub async fn sync_code(state: State<'_, AppState>, server: Option<String>) -> Result<(), String> {
+    let (_, manager) = server_context(&state, server.as_deref()).await?;
 
     let pool = state
         .db
@@ -387,26 +467,62 @@ pub async fn sync_code(state: State<'_, AppState>) -> Result<(), String> {
 
     let project_dir = project::project_path(&proj.name)?;
 
-    get_ssh_manager!(state)
+    manager
         .transfer()
         .rsync_project(&proj.name, &project_dir)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Analyse les dépendances locales d'un benchmark
+///
+/// Retourne la racine du code (dossier parent du benchmark) et les fichiers à synchroniser.
+fn collect_benchmark_files(
+    proj_name: &str,
+    benchmark_path: &Path,
+) -> Result<(std::path::PathBuf, Vec<String>), String> {
+    // Le dossier parent du benchmark comme racine pour les imports locaux
+    let local_code_root = benchmark_path
+        .parent()
+        .ok_or("Impossible de déterminer le dossier parent")?;
+
+    // pyproject.toml du projet
+    let pyproject_path = project::pyproject_path(proj_name)?;
+    let pyproject = if pyproject_path.exists() {
+        Some(pyproject_path.as_path())
+    } else {
+        None
+    };
+
+    // Analyser les dépendances
+    let mut analyzer = python_deps::PythonAnalyzer::new()?;
+    let analysis = analyzer.analyze(benchmark_path, local_code_root, pyproject)?;
+
+    Ok((
+        local_code_root.to_path_buf(),
+        analysis.collect_all_file_paths(),
+    ))
+}
+
+/// Capture la version du code d'un benchmark (git + hash des fichiers synchronisés)
+///
+/// Retourne le JSON `CodeVersion` stocké avec le job.
+async fn capture_code_version(proj_name: &str, benchmark_path: &Path) -> Result<String, String> {
+    let (code_root, files) = collect_benchmark_files(proj_name, benchmark_path)?;
+    let version =
+        code_version::capture(&code_root, &files, &crate::paths::snapshots_dir()?).await?;
+    serde_json::to_string(&version).map_err(|e| format!("Failed to serialize code version: {e}"))
+}
+
 /// Synchronise uniquement les fichiers nécessaires pour un benchmark
 /// (analyse les dépendances et sync les fichiers identifiés)
 #[tauri::command]
 pub async fn sync_benchmark_deps(
     state: State<'_, AppState>,
     benchmark_path: String,
+    server: Option<String>,
 ) -> Result<usize, String> {
-    let _config = state
-        .config
-        .lock()
-        .await
-        .clone()
-        .ok_or("Config non chargée")?;
+    let (_, manager) = server_context(&state, server.as_deref()).await?;
 
     let pool = state
         .db
@@ -427,38 +543,19 @@ pub async fn sync_benchmark_deps(
         .ok_or("Projet non trouvé")?;
 
     let benchmark_path = Path::new(&benchmark_path);
-
-    // Le dossier parent du benchmark comme racine pour les imports locaux
-    let local_code_root = benchmark_path
-        .parent()
-        .ok_or("Impossible de déterminer le dossier parent")?;
-
-    // pyproject.toml du projet
-    let pyproject_path = project::pyproject_path(&proj.name)?;
-    let pyproject = if pyproject_path.exists() {
-        Some(pyproject_path.as_path())
-    } else {
-        None
-    };
-
-    // Analyser les dépendances
-    let mut analyzer = python_deps::PythonAnalyzer::new()?;
-    let analysis = analyzer.analyze(benchmark_path, local_code_root, pyproject)?;
-
-    // Collecter tous les fichiers à synchroniser
-    let files = analysis.collect_all_file_paths();
+    let (_, files) = collect_benchmark_files(&proj.name, benchmark_path)?;
     let file_count = files.len();
 
     // D'abord sync le projet (pyproject.toml, uv.lock)
     let project_dir = project::project_path(&proj.name)?;
-    get_ssh_manager!(state)
+    manager
         .transfer()
         .rsync_project(&proj.name, &project_dir)
         .await
         .map_err(|e| e.to_string())?;
 
     // Puis sync les fichiers du benchmark
-    get_ssh_manager!(state)
+    manager
         .transfer()
         .rsync_benchmarks(&proj.name, benchmark_path, files)
         .await
@@ -593,12 +690,12 @@ pub async fn get_active_project(state: State<'_, AppState>) -> Result<Option<Pro
 }
 
 // ============================================================================
-// Python Version Management
+// Environment Profiles
 // ============================================================================
 
-/// Liste les versions Python disponibles
+/// Liste les profils d'environnement définis dans la configuration
 #[tauri::command]
-pub async fn list_python_versions(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+pub async fn list_env_profiles(state: State<'_, AppState>) -> Result<Vec<String>, String> {
     let config = state
         .config
         .lock()
@@ -606,14 +703,14 @@ pub async fn list_python_versions(state: State<'_, AppState>) -> Result<Vec<Stri
         .clone()
         .ok_or("Config non chargée")?;
 
-    project::list_python_versions(&config.tools.uv_path).await
+    Ok(config.env_profile_names())
 }
 
-/// Change la version Python du projet actif
+/// Sélectionne le profil d'environnement du projet actif (`None` = profil par défaut)
 #[tauri::command]
-pub async fn set_project_python_version(
+pub async fn set_project_env_profile(
     state: State<'_, AppState>,
-    version: String,
+    env_profile: Option<String>,
 ) -> Result<(), String> {
     let config = state
         .config
@@ -636,29 +733,39 @@ pub async fn set_project_python_version(
         .await
         .ok_or("Aucun projet actif")?;
 
-    let proj = db::get_project(&pool, project_id)
-        .await?
-        .ok_or("Projet non trouvé")?;
+    if let Some(ref profile) = env_profile {
+        config.resolve_env_profile(None, Some(profile))?;
+    }
 
-    // Mettre à jour via uv
-    project::set_python_version(&proj.name, &version, &config.tools.uv_path).await?;
+    db::update_project_env_profile(&pool, project_id, env_profile.as_deref()).await
+}
 
-    // Mettre à jour en DB
-    db::update_project_python_version(&pool, project_id, &version).await?;
+/// Liste les profils de conteneur configurés
+#[tauri::command]
+pub async fn list_container_profiles(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let config = state
+        .config
+        .lock()
+        .await
+        .clone()
+        .ok_or("Config non chargée")?;
 
-    Ok(())
+    Ok(config.containers.names())
 }
 
-// ============================================================================
-// Project Benchmarks
-// ============================================================================
-
-/// Ajoute un benchmark au projet actif (chemin absolu)
+/// Sélectionne le profil de conteneur du projet actif (`None` = profil par défaut)
 #[tauri::command]
-pub async fn add_benchmark_to_project(
+pub async fn set_project_container(
     state: State<'_, AppState>,
-    file_path: String,
-) -> Result<Benchmark, String> {
+    container: Option<String>,
+) -> Result<(), String> {
+    let config = state
+        .config
+        .lock()
+        .await
+        .clone()
+        .ok_or("Config non chargée")?;
+
     let pool = state
         .db
         .lock()
@@ -673,47 +780,19 @@ pub async fn add_benchmark_to_project(
         .await
         .ok_or("Aucun projet actif")?;
 
-    // Valider le fichier
-    let path = Path::new(&file_path);
-    if !path.exists() {
-        return Err(format!("Fichier non trouvé: {file_path}"));
-    }
-    if path
-        .extension()
-        .is_none_or(|ext| !ext.eq_ignore_ascii_case("py"))
-    {
-        return Err("Le fichier doit être un fichier Python (.py)".to_string());
-    }
-
-    // Vérifier si déjà ajouté
-    if db::benchmark_exists(&pool, project_id, &file_path).await? {
-        return Err("Ce benchmark est déjà ajouté au projet".to_string());
+    if let Some(ref profile) = container {
+        config.containers.resolve(None, Some(profile))?;
     }
 
-    // Extraire le nom
-    let name = path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .ok_or("Nom de fichier invalide")?
-        .to_string();
-
-    let id = db::insert_benchmark(&pool, project_id, &name, &file_path).await?;
-
-    Ok(Benchmark {
-        id,
-        project_id,
-        name,
-        path: file_path,
-        created_at: chrono::Utc::now().to_rfc3339(),
-    })
+    db::update_project_container(&pool, project_id, container.as_deref()).await
 }
 
-/// Supprime un benchmark du projet
+/// Conteneur dans lequel un job a tourné (image et digest)
 #[tauri::command]
-pub async fn remove_benchmark_from_project(
+pub async fn get_job_container(
     state: State<'_, AppState>,
-    benchmark_id: i64,
-) -> Result<(), String> {
+    job_id: i64,
+) -> Result<Option<container::JobContainer>, String> {
     let pool = state
         .db
         .lock()
@@ -722,11 +801,144 @@ pub async fn remove_benchmark_from_project(
         .ok_or("DB non initialisée")?
         .clone();
 
-    db::delete_benchmark(&pool, benchmark_id).await
+    db::get_job_container(&pool, job_id).await
 }
 
-/// Liste les benchmarks du projet actif
-#[tauri::command]
+// ============================================================================
+// Python Version Management
+// ============================================================================
+
+/// Liste les versions Python disponibles
+#[tauri::command]
+pub async fn list_python_versions(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let config = state
+        .config
+        .lock()
+        .await
+        .clone()
+        .ok_or("Config non chargée")?;
+
+    project::list_python_versions(&config.tools.uv_path).await
+}
+
+/// Change la version Python du projet actif
+#[tauri::command]
+pub async fn set_project_python_version(
+    state: State<'_, AppState>,
+    version: String,
+) -> Result<(), String> {
+    let config = state
+        .config
+        .lock()
+        .await
+        .clone()
+        .ok_or("Config non chargée")?;
+
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    let project_id = state
+        .current_project_id
+        .lock()
+        .await
+        .ok_or("Aucun projet actif")?;
+
+    let proj = db::get_project(&pool, project_id)
+        .await?
+        .ok_or("Projet non trouvé")?;
+
+    // Mettre à jour via uv
+    project::set_python_version(&proj.name, &version, &config.tools.uv_path).await?;
+
+    // Mettre à jour en DB
+    db::update_project_python_version(&pool, project_id, &version).await?;
+
+    Ok(())
+}
+
+// ============================================================================
+// Project Benchmarks
+// ============================================================================
+
+/// Ajoute un benchmark au projet actif (chemin absolu)
+#[tauri::command]
+pub async fn add_benchmark_to_project(
+    state: State<'_, AppState>,
+    file_path: String,
+) -> Result<Benchmark, String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    let project_id = state
+        .current_project_id
+        .lock()
+        .await
+        .ok_or("Aucun projet actif")?;
+
+    // Valider le fichier
+    let path = Path::new(&file_path);
+    if !path.exists() {
+        return Err(format!("Fichier non trouvé: {file_path}"));
+    }
+    if path
+        .extension()
+        .is_none_or(|ext| !ext.eq_ignore_ascii_case("py"))
+    {
+        return Err("Le fichier doit être un fichier Python (.py)".to_string());
+    }
+
+    // Vérifier si déjà ajouté
+    if db::benchmark_exists(&pool, project_id, &file_path).await? {
+        return Err("Ce benchmark est déjà ajouté au projet".to_string());
+    }
+
+    // Extraire le nom
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or("Nom de fichier invalide")?
+        .to_string();
+
+    let id = db::insert_benchmark(&pool, project_id, &name, &file_path).await?;
+
+    Ok(Benchmark {
+        id,
+        project_id,
+        name,
+        path: file_path,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+/// Supprime un benchmark du projet
+#[tauri::command]
+pub async fn remove_benchmark_from_project(
+    state: State<'_, AppState>,
+    benchmark_id: i64,
+) -> Result<(), String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    db::delete_benchmark(&pool, benchmark_id).await
+}
+
+/// Liste les benchmarks du projet actif
+#[tauri::command]
 pub async fn list_project_benchmarks(state: State<'_, AppState>) -> Result<Vec<Benchmark>, String> {
     let pool = state
         .db
@@ -745,6 +957,98 @@ pub async fn list_project_benchmarks(state: State<'_, AppState>) -> Result<Vec<B
     db::get_benchmarks_for_project(&pool, project_id).await
 }
 
+/// Limites mémoire/CPU propres à un benchmark (`None` = valeurs par défaut de la config)
+#[tauri::command]
+pub async fn get_benchmark_limits(
+    state: State<'_, AppState>,
+    benchmark_id: i64,
+) -> Result<Option<limits::JobLimits>, String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    db::get_benchmark_limits(&pool, benchmark_id)
+        .await?
+        .map(|json| limits::parse_limits(&json))
+        .transpose()
+}
+
+/// Serveurs autorisés pour un benchmark (vide = tous)
+#[tauri::command]
+pub async fn get_benchmark_servers(
+    state: State<'_, AppState>,
+    benchmark_id: i64,
+) -> Result<Vec<String>, String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    db::get_benchmark_servers(&pool, benchmark_id).await
+}
+
+/// Restreint un benchmark à certains serveurs pour le dispatch (vide = tous)
+#[tauri::command]
+pub async fn set_benchmark_servers(
+    state: State<'_, AppState>,
+    benchmark_id: i64,
+    servers: Vec<String>,
+) -> Result<(), String> {
+    let config = state
+        .config
+        .lock()
+        .await
+        .clone()
+        .ok_or("Config non chargée")?;
+
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    dispatch::validate_servers(&config, &servers)?;
+    db::set_benchmark_servers(&pool, benchmark_id, &servers).await
+}
+
+/// Définit les limites mémoire/CPU d'un benchmark (`None` pour revenir aux valeurs par défaut)
+#[tauri::command]
+pub async fn set_benchmark_limits(
+    state: State<'_, AppState>,
+    benchmark_id: i64,
+    limits: Option<limits::JobLimits>,
+) -> Result<(), String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    let json = match limits.filter(|l| *l != limits::JobLimits::default()) {
+        Some(limits) => {
+            limits.validate()?;
+            Some(
+                serde_json::to_string(&limits)
+                    .map_err(|e| format!("Failed to serialize job limits: {e}"))?,
+            )
+        }
+        None => None,
+    };
+
+    db::set_benchmark_limits(&pool, benchmark_id, json.as_deref()).await
+}
+
 /// Analyse les dépendances Python d'un fichier benchmark
 #[tauri::command]
 pub async fn get_benchmark_dependencies(
@@ -829,6 +1133,13 @@ pub async fn queue_jobs(
             log_content: String::new(),
             queue_position: None, // Alpha behavior - no queue
             queued_at: None,
+            env_profile: None,
+            peak_rss_kb: None,
+            cpu_seconds: None,
+            notes: None,
+            tags: Vec::new(),
+            server: None,
+            not_before: None,
         });
     }
 
@@ -843,6 +1154,10 @@ pub async fn queue_benchmarks(
     state: State<'_, AppState>,
     benchmark_ids: Vec<i64>,
     force_duplicate: Option<bool>,
+    env_profile: Option<String>,
+    tags: Option<Vec<String>>,
+    server: Option<String>,
+    container: Option<String>,
 ) -> Result<Vec<Job>, String> {
     // Default force_duplicate to false if not provided
     let force_duplicate = force_duplicate.unwrap_or(false);
@@ -868,6 +1183,25 @@ pub async fn queue_benchmarks(
         .await
         .ok_or("No active project - select a project first")?;
 
+    // Labels applied to every queued job ("baseline", "paper-v2", ...)
+    let tags = db::normalize_tags(&tags.unwrap_or_default())?;
+
+    // Target server of every queued job: the one given, none when placed by the
+    // dispatcher, else the default server (reject unknown servers up front)
+    let server = match server {
+        Some(ref name) => Some(config.resolve_server(Some(name))?),
+        None if config.dispatch.enabled => None,
+        None => Some(config.resolve_server(None)?),
+    };
+
+    // Reject unknown environment profiles up front (job-level override)
+    if let Some(ref profile) = env_profile {
+        config.resolve_env_profile(Some(profile), None)?;
+    }
+    if let Some(ref profile) = container {
+        config.containers.resolve(Some(profile), None)?;
+    }
+
     // Duplicate detection check (Story 1.5)
     // Only check if force_duplicate is false
     if !force_duplicate {
@@ -901,6 +1235,32 @@ pub async fn queue_benchmarks(
         }
     }
 
+    // Record the code version of each benchmark (git state + synced file hashes)
+    let proj = db::get_project(&pool, project_id)
+        .await?
+        .ok_or("Project not found")?;
+    let mut code_versions = Vec::with_capacity(benchmark_ids.len());
+    for bench_id in &benchmark_ids {
+        let benchmark = db::get_benchmark_by_id(&pool, *bench_id).await?;
+        if let Some(ref server) = server {
+            let allowed = db::get_benchmark_servers(&pool, *bench_id).await?;
+            if !allowed.is_empty() && !allowed.contains(server) {
+                return Err(format!(
+                    "{} cannot run on {server} (allowed: {})",
+                    benchmark.name,
+                    allowed.join(", ")
+                ));
+            }
+        }
+        let version = capture_code_version(&proj.name, Path::new(&benchmark.path))
+            .await
+            .map_err(|e| {
+                tracing::warn!("Code version not recorded for {}: {e}", benchmark.name);
+            })
+            .ok();
+        code_versions.push(version);
+    }
+
     // Begin transaction for atomic batch insertion (NFR-R7)
     let mut tx = pool
         .begin()
@@ -916,7 +1276,7 @@ pub async fn queue_benchmarks(
     let mut jobs = Vec::new();
     let now = chrono::Utc::now().to_rfc3339();
 
-    for (idx, bench_id) in benchmark_ids.iter().enumerate() {
+    for (idx, (bench_id, code_version)) in benchmark_ids.iter().zip(&code_versions).enumerate() {
         let benchmark = db::get_benchmark_by_id(&pool, *bench_id).await?;
 
         // Safe: benchmark queue size will never exceed i64::MAX in practice
@@ -927,8 +1287,8 @@ pub async fn queue_benchmarks(
         // Insert job within transaction
         let job_id: i64 = sqlx::query_scalar(
             r"
-            INSERT INTO jobs (project_id, benchmark_name, status, created_at, queue_position, queued_at)
-            VALUES (?, ?, 'pending', ?, ?, ?)
+            INSERT INTO jobs (project_id, benchmark_name, status, created_at, queue_position, queued_at, env_profile, code_version, server, container)
+            VALUES (?, ?, 'pending', ?, ?, ?, ?, ?, ?, ?)
             RETURNING id
             ",
         )
@@ -937,10 +1297,16 @@ pub async fn queue_benchmarks(
         .bind(&now)
         .bind(queue_pos)
         .bind(&now)
+        .bind(&env_profile)
+        .bind(code_version)
+        .bind(&server)
+        .bind(&container)
         .fetch_one(&mut *tx)
         .await
         .map_err(|e| format!("Failed to insert job: {e}"))?;
 
+        db::add_job_tags(&mut tx, job_id, &tags).await?;
+
         jobs.push(Job {
             id: job_id,
             project_id: Some(project_id),
@@ -956,6 +1322,13 @@ pub async fn queue_benchmarks(
             log_content: String::new(),
             queue_position: Some(queue_pos),
             queued_at: Some(now.clone()),
+            env_profile: env_profile.clone(),
+            peak_rss_kb: None,
+            cpu_seconds: None,
+            notes: None,
+            tags: tags.clone(),
+            server: server.clone(),
+            not_before: None,
         });
     }
 
@@ -1046,6 +1419,31 @@ pub async fn reorder_queue_job(
     db::reorder_queue_job(&pool, job_id, new_position).await
 }
 
+/// Set or clear the earliest start time of a pending job
+///
+/// `not_before` is an RFC 3339 timestamp (any offset); the queue skips the job
+/// until then, and the time survives restarts.
+#[tauri::command]
+pub async fn set_job_not_before(
+    state: State<'_, AppState>,
+    job_id: i64,
+    not_before: Option<String>,
+) -> Result<(), String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("Database not initialized")?
+        .clone();
+
+    let not_before = not_before
+        .as_deref()
+        .map(job::parse_start_time)
+        .transpose()?;
+    db::set_job_not_before(&pool, job_id, not_before.as_deref()).await
+}
+
 /// Cancel all pending jobs in the queue (Story 1.4)
 /// Running and completed jobs are preserved. Returns count of deleted jobs.
 #[tauri::command]
@@ -1063,13 +1461,6 @@ pub async fn cancel_all_pending_jobs(state: State<'_, AppState>) -> Result<u32,
 
 #[tauri::command]
 pub async fn start_next_job(state: State<'_, AppState>) -> Result<Option<Job>, String> {
-    let config = state
-        .config
-        .lock()
-        .await
-        .clone()
-        .ok_or("Config non chargée")?;
-
     let pool = state
         .db
         .lock()
@@ -1097,86 +1488,145 @@ pub async fn start_next_job(state: State<'_, AppState>) -> Result<Option<Job>, S
     // Prendre le prochain job en attente
     let pending = db::load_pending_jobs(&pool).await?;
     if let Some(job) = pending.into_iter().next() {
-        // Story 2.3: Auto-deploy wrapper on first queue execution
-        // Check if wrapper is installed, deploy if not (idempotent)
-        let manager = get_ssh_manager!(state);
-        let wrapper_mgr = crate::wrapper::WrapperManager::new();
-
-        if !wrapper_mgr.check_installed(manager.executor()).await? {
-            tracing::info!("Wrapper not installed, deploying infrastructure...");
-            // Deploy wrapper script
-            wrapper_mgr.deploy_to_server(manager.executor()).await?;
-
-            // Initialize server database
-            let init_script = crate::server_db::generate_init_script();
-            manager
-                .executor()
-                .execute("mkdir -p ~/.solverpilot-server")
-                .await
-                .map_err(|e| format!("Failed to create server directory: {e}"))?;
-
-            let sql_cmd = format!(
-                "sqlite3 ~/.solverpilot-server/server.db <<'SQL_EOF'\n{init_script}\nSQL_EOF"
-            );
-            manager
-                .executor()
-                .execute(&sql_cmd)
-                .await
-                .map_err(|e| format!("Failed to initialize server database: {e}"))?;
-
-            manager
-                .executor()
-                .execute("chmod 600 ~/.solverpilot-server/server.db")
-                .await
-                .map_err(|e| format!("Failed to set database permissions: {e}"))?;
+        // Serveur cible du job (vue de la config propre à ce serveur)
+        let (config, manager) = job_server_context(&state, &pool, job.id).await?;
 
-            // Update wrapper version in metadata
-            let version_cmd = format!(
-                "sqlite3 ~/.solverpilot-server/server.db <<'SQL_EOF'\nINSERT OR REPLACE INTO metadata (key, value) VALUES ('wrapper_version', '{}');\nSQL_EOF",
-                crate::wrapper::WRAPPER_VERSION
-            );
-            manager
-                .executor()
-                .execute(&version_cmd)
-                .await
-                .map_err(|e| format!("Failed to update wrapper version: {e}"))?;
+        // Vérification de licence avant lancement (si activée)
+        match queue_service::license_preflight(&pool, &manager, &job, &config).await {
+            PreflightOutcome::Block(reason) | PreflightOutcome::Delay(reason) => {
+                return Err(reason);
+            }
+            PreflightOutcome::Ready(Some(warning)) => tracing::warn!("{}", warning),
+            PreflightOutcome::Ready(None) => {}
+        }
 
+        // Story 2.3: Auto-deploy wrapper on first queue execution, and upgrade
+        // an outdated wrapper or server schema (no-op when up to date)
+        let upgrade = crate::wrapper::WrapperManager::new()
+            .ensure_server_ready(manager.executor())
+            .await?;
+        if upgrade.wrapper_deployed || upgrade.schema_from != upgrade.schema_to {
             tracing::info!("Queue infrastructure deployed successfully");
         }
 
         // Sync le projet avant de lancer (pyproject.toml, uv.lock)
         let project_dir = project::project_path(&proj.name)?;
-        get_ssh_manager!(state)
+        manager
             .transfer()
             .rsync_project(&proj.name, &project_dir)
             .await
             .map_err(|e| e.to_string())?;
 
-        // Lancer le job via tmux
+        // Empreinte de l'environnement serveur (non bloquant)
+        if config.fingerprint.enabled {
+            if let Err(e) =
+                fingerprint::record_job_fingerprint(&pool, &manager, &config, &job).await
+            {
+                tracing::warn!(
+                    "Environment fingerprint not recorded for job {}: {e}",
+                    job.id
+                );
+            }
+        }
+
+        // CSV de résultats propre au job (copié par le wrapper en fin de job)
+        db::set_job_results_path(&pool, job.id, &results::job_results_path(job.id)).await?;
+
+        // Lancer le job (le wrapper échantillonne les ressources du job)
         let jobs_path = config.remote_jobs_path();
         let log_file = format!("{}/{}.log", jobs_path, job.id);
         let project_dir = format!("{}/projects/{}", config.remote.remote_base, proj.name);
         let uv_path = &config.tools.uv_path;
 
-        let gurobi_exports = if config.gurobi.home.is_empty() {
+        // Profil d'environnement : job > projet > défaut (ou section [gurobi] historique)
+        let env_prelude = config
+            .resolve_env_profile(job.env_profile.as_deref(), proj.env_profile.as_deref())?
+            .map(|profile| profile.to_shell_prelude())
+            .unwrap_or_default();
+
+        // Limites mémoire/CPU : config surchargée par le benchmark, appliquées par le
+        // wrapper (tmux) ou demandées à l'ordonnanceur (Slurm, PBS, SGE)
+        let job_limits = limits::resolve_job_limits(&pool, &config, &job).await?;
+        let scheduler = batch::scheduler(&config.ssh)?;
+
+        // Conteneur : job > projet > défaut, image et digest enregistrés sur le job
+        let container = container::prepare_job(&pool, manager.executor(), &config, job.id).await?;
+        let limit_env = if scheduler.is_some()
+            || container
+                .as_ref()
+                .is_some_and(container::ContainerProfile::applies_limits)
+        {
             String::new()
         } else {
-            format!(
-                r#"export GUROBI_HOME="{}"; export GRB_LICENSE_FILE="{}"; export PATH="$PATH:$GUROBI_HOME/bin"; export LD_LIBRARY_PATH="$LD_LIBRARY_PATH:$GUROBI_HOME/lib"; "#,
-                config.gurobi.home, config.gurobi.license_file
-            )
+            job_limits.to_env_assignments()
+        };
+        let (uv_sync, uv_run) = match container {
+            Some(ref profile) => {
+                let uv = profile.uv(uv_path);
+                (
+                    profile.wrap(&format!("{uv} sync"), &job_limits),
+                    profile.wrap(
+                        &format!("{uv} run python code/{}", job.benchmark_name),
+                        &job_limits,
+                    ),
+                )
+            }
+            None => (
+                format!("{uv_path} sync"),
+                format!("{uv_path} run python code/{}", job.benchmark_name),
+            ),
         };
 
-        let cmd = format!(
-            r#"tmux new-session -d -s job_{} 'exec > {} 2>&1; export PYTHONUNBUFFERED=1; {}cd {} && echo "=== Starting job ===" && echo "Working directory: $(pwd)" && echo "=== uv sync ===" && {} sync && echo "=== Running benchmark ===" && {} run python code/{} ; echo "=== Job finished with code: $? ==="'"#,
-            job.id, log_file, gurobi_exports, project_dir, uv_path, uv_path, job.benchmark_name
+        let run = format!(
+            r#"{}cd {} && echo "=== Starting job ===" && echo "Working directory: $(pwd)" && echo "=== uv sync ===" && {} && echo "=== Running benchmark ===" && {}{} {} {} ; echo "=== Job finished with code: $? ===""#,
+            env_prelude,
+            project_dir,
+            uv_sync,
+            limit_env,
+            crate::wrapper::REMOTE_WRAPPER_PATH,
+            job.id,
+            uv_run
         );
 
-        get_ssh_manager!(state)
-            .executor()
-            .execute_background(&cmd)
-            .await
-            .map_err(|e| e.to_string())?;
+        if let Some(scheduler) = scheduler {
+            let batch_id = batch::submit_job(
+                &*scheduler,
+                manager.executor(),
+                &jobs_path,
+                job.id,
+                &job_limits,
+                &run,
+            )
+            .await?;
+            db::set_job_backend_id(&pool, job.id, &batch_id).await?;
+            tracing::info!(
+                "Job {} submitted to {} as {}",
+                job.id,
+                scheduler.name(),
+                batch_id
+            );
+        } else if config.ssh.backend == ExecutionBackend::Local {
+            let pgid = local::start_job(
+                manager.executor(),
+                &format!(
+                    "mkdir -p {jobs_path}; exec > {log_file} 2>&1; export PYTHONUNBUFFERED=1; {run}"
+                ),
+            )?;
+            db::set_job_backend_id(&pool, job.id, &pgid.to_string()).await?;
+            tracing::info!("Job {} started locally in process group {}", job.id, pgid);
+        } else {
+            let cmd = format!(
+                "tmux new-session -d -s job_{} 'exec > {} 2>&1; export PYTHONUNBUFFERED=1; {}'",
+                job.id,
+                log_file,
+                job::escape_single_quotes(&run)
+            );
+            manager
+                .executor()
+                .execute_background(&cmd)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
 
         // Mettre à jour le statut
         db::update_job_status(&pool, job.id, &JobStatus::Running).await?;
@@ -1196,20 +1646,30 @@ pub async fn start_next_job(state: State<'_, AppState>) -> Result<Option<Job>, S
 
 #[tauri::command]
 pub async fn stop_job(state: State<'_, AppState>) -> Result<(), String> {
-    let _config = state
-        .config
+    let pool = state
+        .db
         .lock()
         .await
-        .clone()
-        .ok_or("Config non chargée")?;
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
 
     let job_id = *state.current_job_id.lock().await;
     if let Some(job_id) = job_id {
-        get_ssh_manager!(state)
-            .executor()
-            .tmux_send_ctrl_c(&format!("job_{job_id}"))
-            .await
-            .map_err(|e| e.to_string())?;
+        let (config, manager) = job_server_context(&state, &pool, job_id).await?;
+        if let Some(scheduler) = batch::scheduler(&config.ssh)? {
+            let batch_id = batch_job_id(&pool, job_id).await?;
+            batch::cancel(&*scheduler, manager.executor(), &batch_id, true).await?;
+        } else if config.ssh.backend == ExecutionBackend::Local {
+            let pgid = local_job_pgid(&pool, job_id).await?;
+            local::signal(manager.executor(), pgid, true).await?;
+        } else {
+            manager
+                .executor()
+                .tmux_send_ctrl_c(&format!("job_{job_id}"))
+                .await
+                .map_err(|e| e.to_string())?;
+        }
     }
 
     Ok(())
@@ -1217,13 +1677,6 @@ pub async fn stop_job(state: State<'_, AppState>) -> Result<(), String> {
 
 #[tauri::command]
 pub async fn kill_job(state: State<'_, AppState>) -> Result<(), String> {
-    let _config = state
-        .config
-        .lock()
-        .await
-        .clone()
-        .ok_or("Config non chargée")?;
-
     let pool = state
         .db
         .lock()
@@ -1234,11 +1687,20 @@ pub async fn kill_job(state: State<'_, AppState>) -> Result<(), String> {
 
     let job_id = *state.current_job_id.lock().await;
     if let Some(job_id) = job_id {
-        get_ssh_manager!(state)
-            .executor()
-            .tmux_kill_session(&format!("job_{job_id}"))
-            .await
-            .map_err(|e| e.to_string())?;
+        let (config, manager) = job_server_context(&state, &pool, job_id).await?;
+        if let Some(scheduler) = batch::scheduler(&config.ssh)? {
+            let batch_id = batch_job_id(&pool, job_id).await?;
+            batch::cancel(&*scheduler, manager.executor(), &batch_id, false).await?;
+        } else if config.ssh.backend == ExecutionBackend::Local {
+            let pgid = local_job_pgid(&pool, job_id).await?;
+            local::signal(manager.executor(), pgid, false).await?;
+        } else {
+            manager
+                .executor()
+                .tmux_kill_session(&format!("job_{job_id}"))
+                .await
+                .map_err(|e| e.to_string())?;
+        }
         db::update_job_status(&pool, job_id, &JobStatus::Killed).await?;
         *state.current_job_id.lock().await = None;
         *state.job_start_time.lock().await = None;
@@ -1249,16 +1711,23 @@ pub async fn kill_job(state: State<'_, AppState>) -> Result<(), String> {
 
 #[tauri::command]
 pub async fn get_job_logs(state: State<'_, AppState>, lines: u32) -> Result<String, String> {
-    let config = state
-        .config
+    let pool = state
+        .db
         .lock()
         .await
-        .clone()
-        .ok_or("Config non chargée")?;
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
 
     let job_id = *state.current_job_id.lock().await;
     if let Some(job_id) = job_id {
-        get_ssh_manager!(state)
+        // Log déjà diffusé en direct : pas de nouveau canal SSH
+        if let Some(logs) = state.log_streams.recent_lines(job_id, lines as usize).await {
+            return Ok(logs);
+        }
+
+        let (config, manager) = job_server_context(&state, &pool, job_id).await?;
+        manager
             .executor()
             .tail_logs(
                 &format!("{}/jobs/{}.log", config.remote.remote_base, job_id),
@@ -1271,15 +1740,49 @@ pub async fn get_job_logs(state: State<'_, AppState>, lines: u32) -> Result<Stri
     }
 }
 
+/// S'abonne au log d'un job en direct (`tail -F` partagé, événements `job-log-chunk`)
+///
+/// Reprend à `from_offset` (octets) si fourni, sinon aux 64 derniers Kio.
+/// Retourne l'identifiant d'abonnement présent dans chaque `LogChunk`.
 #[tauri::command]
-pub async fn get_job_status(state: State<'_, AppState>) -> Result<JobStatusResponse, String> {
-    let config = state
-        .config
+pub async fn subscribe_job_logs(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    job_id: i64,
+    from_offset: Option<u64>,
+) -> Result<u64, String> {
+    let pool = state
+        .db
         .lock()
         .await
-        .clone()
-        .ok_or("Config non chargée")?;
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    let (config, manager) = job_server_context(&state, &pool, job_id).await?;
+    let log_path = format!("{}/jobs/{}.log", config.remote.remote_base, job_id);
+
+    state
+        .log_streams
+        .subscribe(&manager, job_id, log_path, from_offset, move |chunk| {
+            if let Err(e) = app.emit(log_stream::LOG_CHUNK_EVENT, chunk) {
+                tracing::warn!("Failed to emit log chunk: {}", e);
+            }
+        })
+        .await
+}
 
+/// Arrête un abonnement au log (le flux s'arrête avec son dernier abonné)
+#[tauri::command]
+pub async fn unsubscribe_job_logs(
+    state: State<'_, AppState>,
+    subscription_id: u64,
+) -> Result<(), String> {
+    state.log_streams.unsubscribe(subscription_id).await
+}
+
+#[tauri::command]
+pub async fn get_job_status(state: State<'_, AppState>) -> Result<JobStatusResponse, String> {
     let pool = state
         .db
         .lock()
@@ -1297,16 +1800,21 @@ pub async fn get_job_status(state: State<'_, AppState>) -> Result<JobStatusRespo
         .map_or(0, |t| t.elapsed().as_secs());
 
     if let Some(job_id) = job_id {
-        // Récupérer les logs
-        let logs = get_ssh_manager!(state)
-            .executor()
-            .tail_logs(
-                &format!("{}/jobs/{}.log", config.remote.remote_base, job_id),
-                200,
-            )
-            .await
-            .map_err(|e| e.to_string())
-            .unwrap_or_default();
+        let (config, manager) = job_server_context(&state, &pool, job_id).await?;
+
+        // Récupérer les logs (flux direct s'il existe, sinon tail)
+        let logs = match state.log_streams.recent_lines(job_id, 200).await {
+            Some(logs) => logs,
+            None => manager
+                .executor()
+                .tail_logs(
+                    &format!("{}/jobs/{}.log", config.remote.remote_base, job_id),
+                    200,
+                )
+                .await
+                .map_err(|e| e.to_string())
+                .unwrap_or_default(),
+        };
 
         // Parser la progression
         let (current, total) = job::parse_progress(&logs).unwrap_or((0, 0));
@@ -1326,19 +1834,71 @@ pub async fn get_job_status(state: State<'_, AppState>) -> Result<JobStatusRespo
         let is_finished = job::detect_job_finished(&logs);
         let error = job::detect_job_error(&logs);
 
-        // Vérifier si tmux existe encore
-        let session_name = format!("job_{job_id}");
-        let tmux_exists = get_ssh_manager!(state)
-            .executor()
-            .tmux_session_exists(&session_name)
-            .await
-            .map_err(|e| e.to_string())
-            .unwrap_or(false);
+        // Vérifier si le job tourne encore (session tmux, groupe de processus local,
+        // ou état dans l'ordonnanceur)
+        let (still_active, scheduler_error) = match batch::scheduler(&config.ssh)? {
+            None if config.ssh.backend == ExecutionBackend::Local => {
+                let pgid = local_job_pgid(&pool, job_id).await?;
+                let running = local::is_running(manager.executor(), pgid)
+                    .await
+                    .unwrap_or(false);
+                (running, None)
+            }
+            None => {
+                let session_name = format!("job_{job_id}");
+                let tmux_exists = manager
+                    .executor()
+                    .tmux_session_exists(&session_name)
+                    .await
+                    .map_err(|e| e.to_string())
+                    .unwrap_or(false);
+                (tmux_exists, None)
+            }
+            Some(scheduler) => {
+                let batch_id = batch_job_id(&pool, job_id).await?;
+                match batch::query_state(&*scheduler, manager.executor(), &batch_id).await {
+                    Ok(Some(batch_state @ batch::BatchState::Finished { .. })) => {
+                        (false, batch_state.failure_message())
+                    }
+                    Ok(_) => (true, None),
+                    Err(e) => {
+                        tracing::warn!(
+                            "État {} non lu pour le job {}: {}",
+                            scheduler.name(),
+                            job_id,
+                            e
+                        );
+                        (true, None)
+                    }
+                }
+            }
+        };
 
-        // Si le job est terminé ou tmux n'existe plus
-        let job_done = is_finished || !tmux_exists;
+        // Si le job est terminé ou ne tourne plus
+        let job_done = is_finished || !still_active;
+
+        // Dernier échantillon de ressources (totaux finaux une fois le job terminé)
+        let resource_usage = if job_done {
+            usage::record_final_usage(&pool, manager.executor(), job_id).await
+        } else {
+            usage::read_job_usage(manager.executor(), job_id).await
+        }
+        .unwrap_or_else(|e| {
+            tracing::warn!("Ressources non lues pour le job {}: {}", job_id, e);
+            None
+        });
 
         if job_done {
+            // Limite atteinte (mémoire, temps CPU) : raison explicite plutôt qu'un échec générique
+            let error = match limits::read_limit_reason(manager.executor(), job_id).await {
+                Ok(Some(reason)) => Some(reason),
+                Ok(None) => error.or(scheduler_error),
+                Err(e) => {
+                    tracing::warn!("Raison d'arrêt non lue pour le job {}: {}", job_id, e);
+                    error.or(scheduler_error)
+                }
+            };
+
             // Mettre à jour le statut en DB
             let new_status = if error.is_some() {
                 JobStatus::Failed
@@ -1353,6 +1913,16 @@ pub async fn get_job_status(state: State<'_, AppState>) -> Result<JobStatusRespo
                 db::update_job_error(&pool, job_id, err).await?;
             }
 
+            // Ingestion de benchmark_results.csv (non bloquant)
+            if new_status == JobStatus::Completed {
+                if let Err(e) = results::ingest_job_results(&pool, &manager, job_id).await {
+                    tracing::warn!("Résultats non ingérés pour le job {}: {}", job_id, e);
+                }
+            }
+
+            // Archivage local du log complet + rétention (non bloquant)
+            log_archive::archive_finished_job(&pool, &manager, &config, job_id).await;
+
             // Charger le job mis à jour
             let running_job = db::load_running_job(&pool).await?;
 
@@ -1364,6 +1934,7 @@ pub async fn get_job_status(state: State<'_, AppState>) -> Result<JobStatusRespo
                 elapsed_seconds: elapsed,
                 is_finished: true,
                 error,
+                resource_usage,
             });
         }
 
@@ -1381,6 +1952,7 @@ pub async fn get_job_status(state: State<'_, AppState>) -> Result<JobStatusRespo
             elapsed_seconds: elapsed,
             is_finished: false,
             error: None,
+            resource_usage,
         })
     } else {
         Ok(JobStatusResponse {
@@ -1391,10 +1963,137 @@ pub async fn get_job_status(state: State<'_, AppState>) -> Result<JobStatusRespo
             elapsed_seconds: 0,
             is_finished: false,
             error: None,
+            resource_usage: None,
         })
     }
 }
 
+// ============================================================================
+// Tags & Notes
+// ============================================================================
+
+/// Remplace les tags d'un job
+#[tauri::command]
+pub async fn set_job_tags(
+    state: State<'_, AppState>,
+    job_id: i64,
+    tags: Vec<String>,
+) -> Result<(), String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    db::set_job_tags(&pool, job_id, &tags).await
+}
+
+/// Enregistre les notes libres d'un job (`None` ou vide = effacées)
+#[tauri::command]
+pub async fn set_job_notes(
+    state: State<'_, AppState>,
+    job_id: i64,
+    notes: Option<String>,
+) -> Result<(), String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    db::set_job_notes(&pool, job_id, notes.as_deref()).await
+}
+
+/// Tous les tags connus avec leur nombre de jobs
+#[tauri::command]
+pub async fn list_tags(state: State<'_, AppState>) -> Result<Vec<Tag>, String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    db::list_tags(&pool).await
+}
+
+// ============================================================================
+// Log Archive
+// ============================================================================
+
+/// Log complet archivé d'un job (`None` s'il n'a jamais été archivé ou a expiré)
+#[tauri::command]
+pub async fn get_archived_job_log(
+    state: State<'_, AppState>,
+    job_id: i64,
+) -> Result<Option<String>, String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    log_archive::load_archived_log(&pool, job_id).await
+}
+
+/// Archive manuellement le log d'un job terminé (ignore `logs.archive`)
+#[tauri::command]
+pub async fn archive_job_log(
+    state: State<'_, AppState>,
+    job_id: i64,
+) -> Result<log_archive::ArchivedLog, String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    // Un job en cours écrit encore son log (et l'archive supprimerait la copie serveur)
+    let job = db::load_jobs_by_ids(&pool, &[job_id])
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("Job {job_id} not found"))?;
+    if matches!(job.status, JobStatus::Pending | JobStatus::Running) {
+        return Err(format!("Job {job_id} is not finished"));
+    }
+
+    let (config, manager) = job_server_context(&state, &pool, job_id).await?;
+    log_archive::archive_job_log(&pool, &manager, &config, job_id).await
+}
+
+/// Applique la rétention des archives de logs (âge et taille totale)
+#[tauri::command]
+pub async fn prune_log_archive(
+    state: State<'_, AppState>,
+) -> Result<log_archive::RetentionReport, String> {
+    let config = state
+        .config
+        .lock()
+        .await
+        .clone()
+        .ok_or("Config non chargée")?;
+
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    log_archive::enforce_retention(&pool, &config.logs).await
+}
+
 // ============================================================================
 // History
 // ============================================================================
@@ -1412,6 +2111,60 @@ pub async fn load_history(state: State<'_, AppState>, limit: i32) -> Result<Vec<
     db::load_history(&pool, limit).await
 }
 
+/// Historique filtré (projet, benchmark, statut, dates, paramètres), trié et paginé
+///
+/// Passer `next_cursor` de la page précédente dans `query.cursor` pour la suite.
+#[tauri::command]
+pub async fn query_history(
+    state: State<'_, AppState>,
+    query: HistoryQuery,
+) -> Result<HistoryPage, String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    db::query_history(&pool, &query).await
+}
+
+/// Statistiques de l'historique filtré (taux de succès, durées, tendance des échecs)
+#[tauri::command]
+pub async fn get_history_stats(
+    state: State<'_, AppState>,
+    filters: Option<HistoryFilters>,
+) -> Result<HistoryStats, String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    db::history_stats(&pool, &filters.unwrap_or_default()).await
+}
+
+/// Recherche plein texte dans les jobs terminés (logs archivés, erreurs, benchmarks, paramètres)
+#[tauri::command]
+pub async fn search_jobs(
+    state: State<'_, AppState>,
+    query: String,
+    filters: Option<JobSearchFilters>,
+) -> Result<Vec<JobSearchHit>, String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    db::search_jobs(&pool, &query, &filters.unwrap_or_default()).await
+}
+
 #[tauri::command]
 pub async fn delete_job(state: State<'_, AppState>, job_id: i64) -> Result<(), String> {
     let pool = state
@@ -1425,6 +2178,347 @@ pub async fn delete_job(state: State<'_, AppState>, job_id: i64) -> Result<(), S
     db::delete_pending_job(&pool, job_id).await
 }
 
+/// Download and store a job's `benchmark_results.csv` (replaces previous rows)
+#[tauri::command]
+pub async fn ingest_job_results(state: State<'_, AppState>, job_id: i64) -> Result<usize, String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    let (_, manager) = job_server_context(&state, &pool, job_id).await?;
+    results::ingest_job_results(&pool, &manager, job_id).await
+}
+
+/// Ingested results of a job with their inferred schema (`None` if not ingested)
+#[tauri::command]
+pub async fn get_job_results(
+    state: State<'_, AppState>,
+    job_id: i64,
+) -> Result<Option<results::ResultTable>, String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    db::load_job_results(&pool, job_id).await
+}
+
+/// Per-job result summaries for history (jobs without results are omitted)
+#[tauri::command]
+pub async fn get_result_summaries(
+    state: State<'_, AppState>,
+    job_ids: Vec<i64>,
+) -> Result<Vec<results::ResultSummary>, String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    let mut summaries = Vec::new();
+    for job_id in job_ids {
+        if db::has_job_results(&pool, job_id).await? {
+            let rows = db::load_instance_results(&pool, job_id).await?;
+            summaries.push(results::summarize(job_id, &rows));
+        }
+    }
+    Ok(summaries)
+}
+
+// ============================================================================
+// Code Versions
+// ============================================================================
+
+/// Get the code version recorded for a job (`None` for jobs queued before capture existed)
+#[tauri::command]
+pub async fn get_job_code_version(
+    state: State<'_, AppState>,
+    job_id: i64,
+) -> Result<Option<code_version::CodeVersion>, String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    db::get_job_code_version(&pool, job_id)
+        .await?
+        .map(|json| serde_json::from_str(&json).map_err(|e| format!("Invalid code version: {e}")))
+        .transpose()
+}
+
+/// Rerun a job with exactly the code version it ran
+///
+/// Restores the job's file snapshot, syncs it to the server in place of the
+/// current files, and queues a new job (end of queue) with the same benchmark,
+/// environment profile and code version.
+#[tauri::command]
+pub async fn rerun_job_version(state: State<'_, AppState>, job_id: i64) -> Result<Job, String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    let source = db::load_jobs_by_ids(&pool, &[job_id])
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("Job {job_id} not found"))?;
+    // Même conteneur que le job d'origine s'il en avait choisi un
+    let (source_container, _) = db::get_job_container_selection(&pool, job_id).await?;
+    let project_id = source
+        .project_id
+        .ok_or_else(|| format!("Job {job_id} has no project"))?;
+    let proj = db::get_project(&pool, project_id)
+        .await?
+        .ok_or("Projet non trouvé")?;
+
+    let json = db::get_job_code_version(&pool, job_id)
+        .await?
+        .ok_or_else(|| format!("No code version recorded for job {job_id}"))?;
+    let version: code_version::CodeVersion =
+        serde_json::from_str(&json).map_err(|e| format!("Invalid code version: {e}"))?;
+
+    // Restore the snapshot in a scratch directory (same layout as the code root)
+    let restore_dir = std::env::temp_dir().join(format!("solverpilot_rerun_{job_id}"));
+    let _ = std::fs::remove_dir_all(&restore_dir);
+    let files = code_version::restore(&version, &crate::paths::snapshots_dir()?, &restore_dir)?;
+
+    // Relancé sur le même serveur que le job d'origine
+    let (_, manager) = job_server_context(&state, &pool, job_id).await?;
+    let project_dir = project::project_path(&proj.name)?;
+    let sync_result = async {
+        manager
+            .transfer()
+            .rsync_project(&proj.name, &project_dir)
+            .await?;
+        manager
+            .transfer()
+            .rsync_benchmarks(&proj.name, &restore_dir, files)
+            .await
+    }
+    .await;
+    let _ = std::fs::remove_dir_all(&restore_dir);
+    sync_result.map_err(|e| e.to_string())?;
+
+    let queue_pos = db::get_max_queue_position(&pool).await? + 1;
+    let now = chrono::Utc::now().to_rfc3339();
+    let new_id: i64 = sqlx::query_scalar(
+        r"
+        INSERT INTO jobs (project_id, benchmark_name, status, created_at, queue_position, queued_at, env_profile, code_version, server, container)
+        VALUES (?, ?, 'pending', ?, ?, ?, ?, ?, ?, ?)
+        RETURNING id
+        ",
+    )
+    .bind(project_id)
+    .bind(&source.benchmark_name)
+    .bind(&now)
+    .bind(queue_pos)
+    .bind(&now)
+    .bind(&source.env_profile)
+    .bind(&json)
+    .bind(&source.server)
+    .bind(&source_container)
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| format!("Failed to insert job: {e}"))?;
+
+    // Same labels as the original run
+    db::set_job_tags(&pool, new_id, &source.tags).await?;
+
+    tracing::info!(
+        "Job {} queued as rerun of job {} ({})",
+        new_id,
+        job_id,
+        version.git_commit.as_deref().unwrap_or("no git")
+    );
+
+    Ok(Job {
+        id: new_id,
+        project_id: Some(project_id),
+        benchmark_name: source.benchmark_name,
+        status: JobStatus::Pending,
+        created_at: now.clone(),
+        started_at: None,
+        finished_at: None,
+        progress_current: 0,
+        progress_total: 0,
+        results_path: None,
+        error_message: None,
+        log_content: String::new(),
+        queue_position: Some(queue_pos),
+        queued_at: Some(now),
+        env_profile: source.env_profile,
+        peak_rss_kb: None,
+        cpu_seconds: None,
+        notes: None,
+        tags: source.tags,
+        server: source.server,
+        not_before: None,
+    })
+}
+
+// ============================================================================
+// Environment Fingerprints
+// ============================================================================
+
+/// Load and parse a job's environment fingerprint
+async fn load_env_fingerprint(
+    pool: &SqlitePool,
+    job_id: i64,
+) -> Result<Option<fingerprint::EnvFingerprint>, String> {
+    db::get_job_env_fingerprint(pool, job_id)
+        .await?
+        .map(|json| {
+            serde_json::from_str(&json).map_err(|e| format!("Invalid environment fingerprint: {e}"))
+        })
+        .transpose()
+}
+
+/// Get the environment fingerprint captured when a job started
+#[tauri::command]
+pub async fn get_job_env_fingerprint(
+    state: State<'_, AppState>,
+    job_id: i64,
+) -> Result<Option<fingerprint::EnvFingerprint>, String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    load_env_fingerprint(&pool, job_id).await
+}
+
+/// Diff the environment fingerprints of two jobs (empty when identical)
+#[tauri::command]
+pub async fn diff_job_env_fingerprints(
+    state: State<'_, AppState>,
+    left_job_id: i64,
+    right_job_id: i64,
+) -> Result<Vec<fingerprint::FingerprintChange>, String> {
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    let left = load_env_fingerprint(&pool, left_job_id)
+        .await?
+        .ok_or_else(|| format!("No environment fingerprint for job {left_job_id}"))?;
+    let right = load_env_fingerprint(&pool, right_job_id)
+        .await?
+        .ok_or_else(|| format!("No environment fingerprint for job {right_job_id}"))?;
+
+    Ok(fingerprint::diff(&left, &right))
+}
+
+// ============================================================================
+// Reports
+// ============================================================================
+
+/// Build a comparison report across jobs and render it (`csv`, `markdown` or `html`)
+///
+/// Uses the ingested `results` rows. Jobs without per-job results (e.g. completed
+/// before results ingestion existed) are skipped and listed in the report. Completed
+/// jobs carrying any of `tags` are compared along with `job_ids`. When
+/// `output_path` is given the report is also written there. Returns the rendered report.
+#[tauri::command]
+pub async fn generate_comparison_report(
+    state: State<'_, AppState>,
+    job_ids: Vec<i64>,
+    format: String,
+    output_path: Option<String>,
+    sgm_shift: Option<f64>,
+    tags: Option<Vec<String>>,
+) -> Result<String, String> {
+    let format = report::ReportFormat::parse(&format)?;
+
+    let pool = state
+        .db
+        .lock()
+        .await
+        .as_ref()
+        .ok_or("DB non initialisée")?
+        .clone();
+
+    let mut job_ids = job_ids;
+    for id in
+        db::job_ids_with_any_tag(&pool, &db::normalize_tags(&tags.unwrap_or_default())?).await?
+    {
+        if !job_ids.contains(&id) {
+            job_ids.push(id);
+        }
+    }
+    if job_ids.len() < 2 {
+        return Err("Select at least two jobs to compare".to_string());
+    }
+
+    let jobs = db::load_jobs_by_ids(&pool, &job_ids).await?;
+
+    let mut runs = Vec::with_capacity(jobs.len());
+    let mut skipped = Vec::new();
+    for job in jobs {
+        let mut label = job.env_profile.as_ref().map_or_else(
+            || format!("{} #{}", job.benchmark_name, job.id),
+            |profile| format!("{} [{profile}] #{}", job.benchmark_name, job.id),
+        );
+        if !job.tags.is_empty() {
+            label = format!("{label} ({})", job.tags.join(", "));
+        }
+
+        // No per-job results (e.g. completed before results ingestion existed)
+        if !db::has_job_results(&pool, job.id).await? {
+            skipped.push(report::SkippedRun {
+                job_id: job.id,
+                label,
+            });
+            continue;
+        }
+        runs.push(report::RunResults {
+            job_id: job.id,
+            label,
+            results: db::load_instance_results(&pool, job.id).await?,
+        });
+    }
+    if runs.len() < 2 {
+        let ids: Vec<String> = skipped.iter().map(|run| run.job_id.to_string()).collect();
+        return Err(format!(
+            "Select at least two jobs with results to compare (no results for jobs {})",
+            ids.join(", ")
+        ));
+    }
+
+    let mut comparison =
+        report::build_report(&runs, sgm_shift.unwrap_or(report::DEFAULT_SGM_SHIFT));
+    comparison.skipped = skipped;
+    let rendered = report::render(&comparison, format)?;
+
+    if let Some(path) = output_path {
+        std::fs::write(&path, &rendered).map_err(|e| format!("Failed to write report: {e}"))?;
+    }
+
+    Ok(rendered)
+}
+
 // ============================================================================
 // Project Dependencies
 // ============================================================================
@@ -1578,29 +2672,17 @@ pub async fn start_queue_processing(state: State<'_, AppState>) -> Result<(), St
         .ok_or("Database not initialized")?
         .clone();
 
-    let ssh_manager = state
-        .ssh_manager
-        .lock()
-        .await
-        .as_ref()
-        .ok_or("SSH not connected")?
-        .clone();
+    // Each job runs on its own server, resolved when it starts
+    if state.ssh_managers.lock().await.is_empty() {
+        return Err("SSH not connected".to_string());
+    }
+    let ssh_managers = Arc::clone(&state.ssh_managers);
 
     let queue_manager = state.queue_manager.lock().await.clone();
 
-    // Check if already processing
-    if queue_manager.get_state().await != queue_service::QueueState::Idle {
-        return Err("Queue is already processing".to_string());
-    }
-
-    // Start processing with config values
+    // Start processing with config values (fails unless the queue is idle)
     queue_manager
-        .start_processing(
-            pool,
-            Arc::new(ssh_manager),
-            config.ssh.host,
-            config.ssh.user,
-        )
+        .start_processing(pool, ssh_managers, config)
         .await?;
 
     tracing::info!("Queue processing started");
@@ -1667,20 +2749,15 @@ pub async fn resume_queue_processing(state: State<'_, AppState>) -> Result<(), S
         .ok_or("Database not initialized")?
         .clone();
 
-    let ssh_manager = state
-        .ssh_manager
-        .lock()
-        .await
-        .as_ref()
-        .ok_or("SSH manager not initialized")?
-        .clone();
-
-    let ssh_host = config.ssh.host.clone();
-    let ssh_username = config.ssh.user.clone();
+    // Each job runs on its own server, resolved when it starts
+    if state.ssh_managers.lock().await.is_empty() {
+        return Err("SSH manager not initialized".to_string());
+    }
+    let ssh_managers = Arc::clone(&state.ssh_managers);
 
     let queue_manager = state.queue_manager.lock().await.clone();
     queue_manager
-        .resume_processing(pool, Arc::new(ssh_manager), ssh_host, ssh_username)
+        .resume_processing(pool, ssh_managers, config)
         .await?;
 
     tracing::info!("Queue processing resumed");
@@ -1701,6 +2778,25 @@ pub async fn get_queue_status(state: State<'_, AppState>) -> Result<serde_json::
 
     let queue_state = queue_manager.get_state().await;
     let current_job_id = queue_manager.current_job().await;
+    let running_jobs = queue_manager.running_jobs().await;
+    let waiting_reason = queue_manager.waiting_reason().await;
+    let license_warning = queue_manager.license_warning().await;
+    let schedule_paused = queue_manager.schedule_paused().await;
+    let suspended_jobs = queue_manager.suspended_jobs().await;
+
+    // Execution windows (validated when the config was loaded): whether the queue
+    // may run now, and until when
+    let config = state.config.lock().await.clone();
+    let windows = queue_manager.windows().await;
+    let now = chrono::Local::now().naive_local();
+    let window = windows.is_restricted().then(|| {
+        serde_json::json!({
+            "open": windows.is_open(now),
+            "nextChange": windows
+                .next_change(now)
+                .map(|at| at.format("%Y-%m-%dT%H:%M").to_string()),
+        })
+    });
 
     // Get job counts from database
     let pool = state
@@ -1729,12 +2825,25 @@ pub async fn get_queue_status(state: State<'_, AppState>) -> Result<serde_json::
             .await
             .map_err(|e| format!("Failed to count completed jobs: {e}"))?;
 
+    // Estimated completion of each job and of the whole queue
+    let eta = match config.as_ref() {
+        Some(config) => Some(eta::queue_eta(&pool, config).await?),
+        None => None,
+    };
+
     Ok(serde_json::json!({
         "state": queue_state.as_str(),
         "currentJobId": current_job_id,
+        "runningJobs": running_jobs,
         "pendingCount": pending_count,
         "runningCount": running_count,
         "completedCount": completed_count,
+        "waitingReason": waiting_reason,
+        "licenseWarning": license_warning,
+        "window": window,
+        "schedulePaused": schedule_paused,
+        "suspendedJobs": suspended_jobs,
+        "eta": eta,
     }))
 }
 
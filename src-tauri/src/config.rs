@@ -1,18 +1,65 @@
 use crate::paths;
+use crate::schedule::ExecutionWindows;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
+/// Name of the server described by the `[ssh]` section
+pub const DEFAULT_SERVER: &str = "default";
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AppConfig {
+    /// Server used when a command or job names none (`DEFAULT_SERVER` if unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_server: Option<String>,
     pub ssh: SshConfig,
     pub remote: RemoteConfig,
     pub polling: PollingConfig,
+    /// Legacy Gurobi section, kept for existing configs (see `EnvironmentConfig`)
     #[serde(default)]
     pub gurobi: GurobiConfig,
     #[serde(default)]
+    pub environment: EnvironmentConfig,
+    /// Container profiles jobs can run in (see `container` module)
+    #[serde(default)]
+    pub containers: crate::container::ContainerConfig,
+    #[serde(default)]
     pub tools: ToolsConfig,
     #[serde(default)]
     pub queue_settings: QueueSettings,
+    #[serde(default)]
+    pub license: LicenseConfig,
+    #[serde(default)]
+    pub fingerprint: FingerprintConfig,
+    /// Default memory/CPU limits of every job (overridden per benchmark)
+    #[serde(default)]
+    pub limits: crate::limits::JobLimits,
+    #[serde(default)]
+    pub logs: LogsConfig,
+    #[serde(default)]
+    pub dispatch: DispatchConfig,
+    #[serde(default)]
+    pub load_guard: LoadGuardConfig,
+    /// Additional compute servers by name (`[ssh]` is the server named "default")
+    #[serde(default)]
+    pub servers: BTreeMap<String, ServerProfile>,
+}
+
+/// A named compute server
+///
+/// ```toml
+/// [servers.gpu1]
+/// host = "gpu1.lab.example"
+/// user = "alice"
+/// remote_base = "/scratch/alice/benchmarks"
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ServerProfile {
+    #[serde(flatten)]
+    pub ssh: SshConfig,
+    /// Remote base directory on this server (default: `remote.remote_base`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_base: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -25,12 +72,51 @@ pub struct SshConfig {
     /// Chemin de la clé SSH (optionnel, défaut: auto-détecté depuis ~/.ssh/config)
     #[serde(default = "default_key_path")]
     pub key_path: String,
+    /// Jobs run at the same time on this server by the queue (défaut: 1)
+    #[serde(default = "default_max_concurrent")]
+    pub max_concurrent: u32,
+    /// How jobs are started on this server (défaut: tmux sessions)
+    #[serde(default)]
+    pub backend: ExecutionBackend,
+    /// Options `sbatch` du backend Slurm
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slurm: Option<crate::slurm::SlurmConfig>,
+    /// Options `qsub` du backend PBS/Torque
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pbs: Option<crate::pbs::PbsConfig>,
+    /// Options `qsub` du backend SGE
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sge: Option<crate::sge::SgeConfig>,
+    /// Options du backend local (exécution sur cette machine)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local: Option<crate::local::LocalConfig>,
+}
+
+/// Execution backend of a server
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionBackend {
+    /// One detached tmux session per job on the server itself
+    #[default]
+    Tmux,
+    /// One `sbatch` job per job, tracked with `squeue`/`sacct` (see `batch`)
+    Slurm,
+    /// One `qsub` job per job on PBS Pro or Torque, tracked with `qstat -f`
+    Pbs,
+    /// One `qsub` job per job on Grid Engine, tracked with `qstat`/`qacct`
+    Sge,
+    /// One process group per job on this machine, without SSH (see `local`)
+    Local,
 }
 
 const fn default_port() -> u16 {
     22
 }
 
+const fn default_max_concurrent() -> u32 {
+    1
+}
+
 fn default_key_path() -> String {
     let home = std::env::var("HOME").unwrap_or_else(|_| "/home".to_string());
     format!("{home}/.ssh/id_rsa")
@@ -57,6 +143,122 @@ pub struct GurobiConfig {
     pub license_file: String,
 }
 
+impl GurobiConfig {
+    /// Convert the legacy `[gurobi]` section into an equivalent environment profile
+    pub fn to_env_profile(&self) -> Option<EnvProfile> {
+        if self.home.is_empty() {
+            return None;
+        }
+
+        let mut env = BTreeMap::new();
+        env.insert("GUROBI_HOME".to_string(), self.home.clone());
+        if !self.license_file.is_empty() {
+            env.insert("GRB_LICENSE_FILE".to_string(), self.license_file.clone());
+        }
+
+        Some(EnvProfile {
+            env,
+            path: vec!["$GUROBI_HOME/bin".to_string()],
+            ld_library_path: vec!["$GUROBI_HOME/lib".to_string()],
+            modules: Vec::new(),
+            pre_run: Vec::new(),
+        })
+    }
+}
+
+/// Named environment profiles applied on the server before a job starts
+///
+/// ```toml
+/// [environment]
+/// default_profile = "gurobi"
+///
+/// [environment.profiles.gurobi]
+/// env = { GUROBI_HOME = "~/gurobi1300/linux64", GRB_LICENSE_FILE = "~/gurobi.lic" }
+/// path = ["$GUROBI_HOME/bin"]
+/// ld_library_path = ["$GUROBI_HOME/lib"]
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct EnvironmentConfig {
+    /// Profile used when neither the job nor its project selects one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+    /// Profiles by name (e.g. "gurobi", "cplex", "highs-dev")
+    #[serde(default)]
+    pub profiles: BTreeMap<String, EnvProfile>,
+}
+
+/// A solver environment: variables, search paths, modules and setup commands
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct EnvProfile {
+    /// Environment variables to export (`~/` is expanded to `$HOME/`)
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Directories appended to `PATH`
+    #[serde(default)]
+    pub path: Vec<String>,
+    /// Directories appended to `LD_LIBRARY_PATH`
+    #[serde(default)]
+    pub ld_library_path: Vec<String>,
+    /// Environment modules to load (`module load <name>`)
+    #[serde(default)]
+    pub modules: Vec<String>,
+    /// Shell commands run after the environment is set up
+    #[serde(default)]
+    pub pre_run: Vec<String>,
+}
+
+impl EnvProfile {
+    /// Render the profile as a shell prelude (each statement terminated by `; `)
+    ///
+    /// Order: modules, variables, `PATH`, `LD_LIBRARY_PATH`, pre-run commands.
+    /// Values are double-quoted so `$VAR` references still expand on the server.
+    pub fn to_shell_prelude(&self) -> String {
+        let modules = self.modules.iter().map(|m| format!("module load {m}"));
+        let vars = self.env.iter().map(|(key, value)| {
+            format!(
+                "export {key}=\"{}\"",
+                shell_double_quote(&expand_home(value))
+            )
+        });
+        let path = self.path.iter().map(|dir| {
+            format!(
+                "export PATH=\"$PATH:{}\"",
+                shell_double_quote(&expand_home(dir))
+            )
+        });
+        let ld_path = self.ld_library_path.iter().map(|dir| {
+            format!(
+                "export LD_LIBRARY_PATH=\"${{LD_LIBRARY_PATH:+$LD_LIBRARY_PATH:}}{}\"",
+                shell_double_quote(&expand_home(dir))
+            )
+        });
+
+        modules
+            .chain(vars)
+            .chain(path)
+            .chain(ld_path)
+            .chain(self.pre_run.iter().cloned())
+            .map(|stmt| stmt + "; ")
+            .collect()
+    }
+}
+
+/// Replace a leading `~` by `$HOME` (tilde is not expanded inside double quotes)
+fn expand_home(value: &str) -> String {
+    if value == "~" {
+        "$HOME".to_string()
+    } else if let Some(rest) = value.strip_prefix("~/") {
+        format!("$HOME/{rest}")
+    } else {
+        value.to_string()
+    }
+}
+
+/// Escape characters that would terminate a double-quoted shell string
+fn shell_double_quote(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ToolsConfig {
     /// Path to uv command
@@ -77,17 +279,254 @@ pub enum DuplicateHandling {
     Allow,
 }
 
+/// What happens to running jobs when an execution window ends
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowEnd {
+    /// Let running jobs finish, start no new one (default)
+    #[default]
+    Finish,
+    /// Stop running jobs (`SIGSTOP`) until the next window opens
+    Suspend,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct QueueSettings {
     /// How to handle duplicate jobs when queueing
     #[serde(default)]
     pub duplicate_handling: DuplicateHandling,
+    /// Cron-like windows the queue runs in, local time (empty: always, see `schedule`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub windows: Vec<String>,
+    /// Running jobs at the end of a window
+    #[serde(default)]
+    pub window_end: WindowEnd,
 }
 
 fn default_uv_path() -> String {
     "~/.local/bin/uv".to_string()
 }
 
+/// License preflight check run before each job (see `license` module)
+///
+/// ```toml
+/// [license]
+/// enabled = true
+/// command = "gurobi_cl --license"
+/// warn_days = 14
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LicenseConfig {
+    /// Run the check before launching each job
+    #[serde(default)]
+    pub enabled: bool,
+    /// Remote command queried for license status (e.g. `gurobi_cl --tokens` for a token server)
+    #[serde(default = "default_license_command")]
+    pub command: String,
+    /// Warn when the license expires within this many days
+    #[serde(default = "default_license_warn_days")]
+    pub warn_days: i64,
+    /// Delay before retrying when no token is available
+    #[serde(default = "default_license_retry_seconds")]
+    pub retry_seconds: u64,
+}
+
+fn default_license_command() -> String {
+    "gurobi_cl --license".to_string()
+}
+
+const fn default_license_warn_days() -> i64 {
+    14
+}
+
+const fn default_license_retry_seconds() -> u64 {
+    60
+}
+
+impl Default for LicenseConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            command: default_license_command(),
+            warn_days: default_license_warn_days(),
+            retry_seconds: default_license_retry_seconds(),
+        }
+    }
+}
+
+/// Environment fingerprint captured at job start (see `fingerprint` module)
+///
+/// ```toml
+/// [fingerprint]
+/// enabled = true
+/// solver_command = "gurobi_cl --version"
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FingerprintConfig {
+    /// Capture the fingerprint when each job starts
+    #[serde(default = "default_fingerprint_enabled")]
+    pub enabled: bool,
+    /// Remote command printing the solver version
+    #[serde(default = "default_solver_command")]
+    pub solver_command: String,
+}
+
+const fn default_fingerprint_enabled() -> bool {
+    true
+}
+
+fn default_solver_command() -> String {
+    "gurobi_cl --version".to_string()
+}
+
+impl Default for FingerprintConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_fingerprint_enabled(),
+            solver_command: default_solver_command(),
+        }
+    }
+}
+
+/// Local archive of finished jobs' logs (see `log_archive` module)
+///
+/// ```toml
+/// [logs]
+/// archive = true
+/// retention_days = 90
+/// max_total_mb = 1024
+/// prune_remote = true
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LogsConfig {
+    /// Download and compress the full log when a job finishes
+    #[serde(default = "default_logs_archive")]
+    pub archive: bool,
+    /// Archives older than this are deleted (0 = keep forever)
+    #[serde(default = "default_logs_retention_days")]
+    pub retention_days: u32,
+    /// Oldest archives are deleted above this total size (0 = unlimited)
+    #[serde(default = "default_logs_max_total_mb")]
+    pub max_total_mb: u64,
+    /// Delete the log from the server once archived
+    #[serde(default = "default_logs_prune_remote")]
+    pub prune_remote: bool,
+}
+
+const fn default_logs_archive() -> bool {
+    true
+}
+
+const fn default_logs_retention_days() -> u32 {
+    90
+}
+
+const fn default_logs_max_total_mb() -> u64 {
+    1024
+}
+
+const fn default_logs_prune_remote() -> bool {
+    true
+}
+
+impl Default for LogsConfig {
+    fn default() -> Self {
+        Self {
+            archive: default_logs_archive(),
+            retention_days: default_logs_retention_days(),
+            max_total_mb: default_logs_max_total_mb(),
+            prune_remote: default_logs_prune_remote(),
+        }
+    }
+}
+
+/// Automatic placement of queued jobs across servers (see `dispatch` module)
+///
+/// ```toml
+/// [dispatch]
+/// enabled = true
+/// max_load_per_core = 0.9
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DispatchConfig {
+    /// Jobs queued without a server go to the least loaded server with a free slot
+    #[serde(default)]
+    pub enabled: bool,
+    /// Servers whose 1-minute load average per core is above this get no new job
+    #[serde(default = "default_max_load_per_core")]
+    pub max_load_per_core: f64,
+}
+
+const fn default_max_load_per_core() -> f64 {
+    1.0
+}
+
+impl Default for DispatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_load_per_core: default_max_load_per_core(),
+        }
+    }
+}
+
+/// Server load checked before each job starts (see `load_guard` module)
+///
+/// ```toml
+/// [load_guard]
+/// enabled = true
+/// max_load_per_core = 0.8
+/// min_free_memory_mb = 8192
+/// heavy_process_cpu = 90.0
+/// max_heavy_processes = 0
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LoadGuardConfig {
+    /// Hold jobs while their server is busy
+    #[serde(default)]
+    pub enabled: bool,
+    /// Highest 1-minute load average per core
+    #[serde(default = "default_guard_load_per_core")]
+    pub max_load_per_core: f64,
+    /// Lowest available memory in MiB (0 = not checked)
+    #[serde(default)]
+    pub min_free_memory_mb: u64,
+    /// %CPU from which another user's process counts as CPU-heavy
+    #[serde(default = "default_heavy_process_cpu")]
+    pub heavy_process_cpu: f64,
+    /// CPU-heavy processes of other users tolerated
+    #[serde(default)]
+    pub max_heavy_processes: u32,
+    /// Delay before checking a busy server again
+    #[serde(default = "default_guard_retry_seconds")]
+    pub retry_seconds: u64,
+}
+
+const fn default_guard_load_per_core() -> f64 {
+    0.8
+}
+
+const fn default_heavy_process_cpu() -> f64 {
+    90.0
+}
+
+const fn default_guard_retry_seconds() -> u64 {
+    30
+}
+
+impl Default for LoadGuardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_load_per_core: default_guard_load_per_core(),
+            min_free_memory_mb: 0,
+            heavy_process_cpu: default_heavy_process_cpu(),
+            max_heavy_processes: 0,
+            retry_seconds: default_guard_retry_seconds(),
+        }
+    }
+}
+
 impl Default for ToolsConfig {
     fn default() -> Self {
         Self {
@@ -111,8 +550,20 @@ impl AppConfig {
         let bytes = std::fs::read(&config_path)
             .map_err(|e| format!("Erreur lecture {}: {e}", config_path.display()))?;
 
-        toml::de::from_slice(&bytes)
-            .map_err(|e| format!("Erreur parsing {}: {e}", config_path.display()))
+        let config: Self = toml::de::from_slice(&bytes)
+            .map_err(|e| format!("Erreur parsing {}: {e}", config_path.display()))?;
+
+        // Fenêtres d'exécution validées dès le chargement
+        config.execution_windows()?;
+        Ok(config)
+    }
+
+    /// Fenêtres d'exécution de la queue (`queue_settings.windows`)
+    ///
+    /// # Errors
+    /// Returns error if a window expression is invalid
+    pub fn execution_windows(&self) -> Result<ExecutionWindows, String> {
+        ExecutionWindows::parse(&self.queue_settings.windows)
     }
 
     /// Chemin d'un projet spécifique sur le serveur
@@ -131,6 +582,93 @@ impl AppConfig {
         format!("{}/code", self.remote.remote_base)
     }
 
+    /// Resolve the environment profile for a job
+    ///
+    /// Precedence: job profile, then project profile, then `environment.default_profile`.
+    /// Without any selection, the legacy `[gurobi]` section is used if configured.
+    pub fn resolve_env_profile(
+        &self,
+        job_profile: Option<&str>,
+        project_profile: Option<&str>,
+    ) -> Result<Option<EnvProfile>, String> {
+        let selected = job_profile
+            .or(project_profile)
+            .or(self.environment.default_profile.as_deref());
+
+        let Some(name) = selected else {
+            return Ok(self.gurobi.to_env_profile());
+        };
+
+        self.environment
+            .profiles
+            .get(name)
+            .cloned()
+            .or_else(|| {
+                (name == "gurobi")
+                    .then(|| self.gurobi.to_env_profile())
+                    .flatten()
+            })
+            .map(Some)
+            .ok_or_else(|| format!("Unknown environment profile: {name}"))
+    }
+
+    /// Names of all configured servers, `DEFAULT_SERVER` first
+    pub fn server_names(&self) -> Vec<String> {
+        std::iter::once(DEFAULT_SERVER.to_string())
+            .chain(
+                self.servers
+                    .keys()
+                    .filter(|name| *name != DEFAULT_SERVER)
+                    .cloned(),
+            )
+            .collect()
+    }
+
+    /// Resolve a server name (`None` = `default_server`, else `DEFAULT_SERVER`)
+    pub fn resolve_server(&self, name: Option<&str>) -> Result<String, String> {
+        let name = name
+            .or(self.default_server.as_deref())
+            .unwrap_or(DEFAULT_SERVER);
+
+        if name == DEFAULT_SERVER || self.servers.contains_key(name) {
+            Ok(name.to_string())
+        } else {
+            Err(format!("Unknown server: {name}"))
+        }
+    }
+
+    /// Configuration seen by one server: its SSH settings and remote base
+    ///
+    /// Everything built from `ssh` and `remote` (SSH manager, transfers, remote
+    /// paths) then targets that server.
+    pub fn for_server(&self, name: &str) -> Result<Self, String> {
+        if name == DEFAULT_SERVER {
+            return Ok(self.clone());
+        }
+
+        let profile = self
+            .servers
+            .get(name)
+            .ok_or_else(|| format!("Unknown server: {name}"))?;
+        let mut config = self.clone();
+        config.ssh = profile.ssh.clone();
+        if let Some(ref remote_base) = profile.remote_base {
+            config.remote.remote_base.clone_from(remote_base);
+        }
+
+        Ok(config)
+    }
+
+    /// Names of all selectable environment profiles (including legacy `gurobi`)
+    pub fn env_profile_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.environment.profiles.keys().cloned().collect();
+        if !self.gurobi.home.is_empty() && !names.iter().any(|n| n == "gurobi") {
+            names.push("gurobi".to_string());
+            names.sort();
+        }
+        names
+    }
+
     /// Chemin des jobs sur le serveur (partagé entre tous les projets)
     pub fn remote_jobs_path(&self) -> String {
         format!("{}/jobs", self.remote.remote_base)
@@ -153,6 +691,9 @@ impl AppConfig {
 
     /// Sauvegarde la configuration dans le fichier config.toml
     pub fn save(&self) -> Result<(), String> {
+        // Fenêtres d'exécution invalides refusées avant écriture
+        self.execution_windows()?;
+
         let config_path = paths::config_path()?;
 
         // Créer le dossier parent si nécessaire
@@ -177,3 +718,239 @@ impl AppConfig {
         Ok(paths::config_path()?.exists())
     }
 }
+
+/// Config minimale des tests (serveur, base distante, polling), complétée par `toml_str`
+///
+/// Les tests ne précisent que les sections qui les concernent.
+#[cfg(test)]
+pub fn test_config(toml_str: &str) -> Result<AppConfig, toml::de::Error> {
+    let base = r#"
+[ssh]
+host = "server"
+user = "alice"
+
+[remote]
+remote_base = "~/benchmarks"
+
+[polling]
+interval_seconds = 2
+"#;
+    toml::from_str(&format!("{base}{toml_str}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_gurobi_becomes_default_profile() -> Result<(), Box<dyn std::error::Error>> {
+        let config = test_config(
+            r#"
+[gurobi]
+home = "~/gurobi1300/linux64"
+license_file = "/opt/gurobi.lic"
+"#,
+        )?;
+
+        let profile = config
+            .resolve_env_profile(None, None)?
+            .ok_or("no profile")?;
+        let prelude = profile.to_shell_prelude();
+
+        assert!(prelude.contains(r#"export GUROBI_HOME="$HOME/gurobi1300/linux64"; "#));
+        assert!(prelude.contains(r#"export GRB_LICENSE_FILE="/opt/gurobi.lic"; "#));
+        assert!(prelude.contains(r#"export PATH="$PATH:$GUROBI_HOME/bin"; "#));
+        assert!(prelude.contains("$GUROBI_HOME/lib"));
+        assert_eq!(config.env_profile_names(), vec!["gurobi".to_string()]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_server_profiles() -> Result<(), Box<dyn std::error::Error>> {
+        let config = test_config(
+            r#"
+[servers.gpu1]
+host = "gpu1.lab"
+user = "bob"
+port = 2222
+remote_base = "/scratch/bob"
+
+[servers.cpu2]
+host = "cpu2.lab"
+user = "alice"
+"#,
+        )?;
+
+        assert_eq!(config.server_names(), vec!["default", "cpu2", "gpu1"]);
+        assert_eq!(config.resolve_server(None)?, DEFAULT_SERVER);
+        assert_eq!(config.resolve_server(Some("gpu1"))?, "gpu1");
+        assert!(config.resolve_server(Some("gpu9")).is_err());
+
+        let gpu1 = config.for_server("gpu1")?;
+        assert_eq!(gpu1.ssh.host, "gpu1.lab");
+        assert_eq!(gpu1.ssh.port, 2222);
+        assert_eq!(gpu1.remote_jobs_path(), "/scratch/bob/jobs");
+        // Without its own remote_base, a server shares the default one
+        assert_eq!(
+            config.for_server("cpu2")?.remote_jobs_path(),
+            "~/benchmarks/jobs"
+        );
+        assert_eq!(config.for_server(DEFAULT_SERVER)?.ssh.host, "server");
+
+        let mut config = config;
+        config.default_server = Some("cpu2".to_string());
+        assert_eq!(config.resolve_server(None)?, "cpu2");
+        assert!(toml::to_string_pretty(&config)?.starts_with("default_server = \"cpu2\""));
+        Ok(())
+    }
+
+    #[test]
+    fn test_dispatch_and_concurrency_defaults() -> Result<(), Box<dyn std::error::Error>> {
+        let config = test_config("")?;
+        assert!(!config.dispatch.enabled);
+        assert!((config.dispatch.max_load_per_core - 1.0).abs() < f64::EPSILON);
+        assert_eq!(config.ssh.max_concurrent, 1);
+
+        let config = test_config(
+            r#"
+[dispatch]
+enabled = true
+max_load_per_core = 0.75
+
+[servers.big]
+host = "big.lab"
+user = "bob"
+max_concurrent = 4
+"#,
+        )?;
+        assert!(config.dispatch.enabled);
+        assert_eq!(config.for_server("big")?.ssh.max_concurrent, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_guard_settings() -> Result<(), Box<dyn std::error::Error>> {
+        let config = test_config("")?;
+        assert!(!config.load_guard.enabled);
+        assert_eq!(config.load_guard.retry_seconds, 30);
+
+        let config = test_config(
+            r"
+[load_guard]
+enabled = true
+min_free_memory_mb = 8192
+max_heavy_processes = 1
+",
+        )?;
+        assert!(config.load_guard.enabled);
+        assert_eq!(config.load_guard.min_free_memory_mb, 8192);
+        assert_eq!(config.load_guard.max_heavy_processes, 1);
+        assert!((config.load_guard.max_load_per_core - 0.8).abs() < f64::EPSILON);
+        Ok(())
+    }
+
+    #[test]
+    fn test_slurm_backend() -> Result<(), Box<dyn std::error::Error>> {
+        let config = test_config(
+            r#"
+[servers.hpc]
+host = "login.hpc"
+user = "bob"
+backend = "slurm"
+
+[servers.hpc.slurm]
+partition = "compute"
+time_limit = "04:00:00"
+"#,
+        )?;
+        assert_eq!(config.ssh.backend, ExecutionBackend::Tmux);
+        let hpc = config.for_server("hpc")?;
+        assert_eq!(hpc.ssh.backend, ExecutionBackend::Slurm);
+        let slurm = hpc.ssh.slurm.ok_or("missing [servers.hpc.slurm]")?;
+        assert_eq!(slurm.partition.as_deref(), Some("compute"));
+        assert_eq!(slurm.account, None);
+
+        let config = test_config(
+            r#"
+[servers.grid]
+host = "grid"
+user = "bob"
+backend = "sge"
+"#,
+        )?;
+        assert_eq!(
+            config.for_server("grid")?.ssh.backend,
+            ExecutionBackend::Sge
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_execution_windows_validated() -> Result<(), Box<dyn std::error::Error>> {
+        let config = test_config("[queue_settings]\nwindows = [\"* 20-23 * * *\"]\n")?;
+        assert!(config.execution_windows()?.is_restricted());
+
+        let config = test_config("[queue_settings]\nwindows = [\"* 25 * * *\"]\n")?;
+        assert!(config.execution_windows().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_profile_without_config() -> Result<(), Box<dyn std::error::Error>> {
+        let config = test_config("")?;
+        assert_eq!(config.resolve_env_profile(None, None)?, None);
+        assert!(config.resolve_env_profile(Some("cplex"), None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_profile_precedence() -> Result<(), Box<dyn std::error::Error>> {
+        let config = test_config(
+            r#"
+[environment]
+default_profile = "highs"
+
+[environment.profiles.highs]
+path = ["/opt/highs/bin"]
+
+[environment.profiles.cplex]
+modules = ["cplex/22.1"]
+
+[environment.profiles.xpress]
+env = { XPRESSDIR = "/opt/xpress" }
+pre_run = ["source /opt/xpress/bin/xpvars.sh"]
+"#,
+        )?;
+
+        let default = config
+            .resolve_env_profile(None, None)?
+            .ok_or("no profile")?;
+        assert_eq!(default.path, vec!["/opt/highs/bin".to_string()]);
+
+        let project = config
+            .resolve_env_profile(None, Some("cplex"))?
+            .ok_or("no profile")?;
+        assert_eq!(project.to_shell_prelude(), "module load cplex/22.1; ");
+
+        let job = config
+            .resolve_env_profile(Some("xpress"), Some("cplex"))?
+            .ok_or("no profile")?;
+        assert_eq!(
+            job.to_shell_prelude(),
+            r#"export XPRESSDIR="/opt/xpress"; source /opt/xpress/bin/xpvars.sh; "#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_prelude_escapes_double_quotes() {
+        let mut profile = EnvProfile::default();
+        profile
+            .env
+            .insert("SOLVER_OPTS".to_string(), r#"a "b" c"#.to_string());
+        assert_eq!(
+            profile.to_shell_prelude(),
+            r#"export SOLVER_OPTS="a \"b\" c"; "#
+        );
+    }
+}
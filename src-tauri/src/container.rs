@@ -0,0 +1,423 @@
+//! Container execution (Apptainer/Singularity or Docker)
+//!
+//! A job can run its benchmark inside a pinned container image. Named
+//! container profiles live in `[containers.profiles.<name>]` and are selected
+//! like environment profiles: job, then project, then `containers.default_profile`.
+//! Only the Python commands (`uv sync`, `uv run python ...`) are wrapped in
+//! `apptainer exec` / `docker run`: the wrapper and environment prelude stay on
+//! the host. The image reference and digest are recorded on the job when it starts.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+use crate::config::AppConfig;
+use crate::db;
+use crate::job;
+use crate::limits::JobLimits;
+use crate::ssh::SshExecutor;
+
+/// Container profiles
+///
+/// ```toml
+/// [containers]
+/// default_profile = "solver"
+///
+/// [containers.profiles.solver]
+/// runtime = "apptainer"
+/// image = "~/images/solver-2024.1.sif"
+/// binds = ["/scratch/instances:/data:ro"]
+/// env = ["GRB_LICENSE_FILE"]
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct ContainerConfig {
+    /// Profile used when neither the job nor its project selects one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+    /// Profiles by name
+    #[serde(default)]
+    pub profiles: BTreeMap<String, ContainerProfile>,
+}
+
+/// Container runtime on the server
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerRuntime {
+    #[default]
+    Apptainer,
+    Singularity,
+    Docker,
+}
+
+impl ContainerRuntime {
+    const fn command(self) -> &'static str {
+        match self {
+            Self::Apptainer => "apptainer",
+            Self::Singularity => "singularity",
+            Self::Docker => "docker",
+        }
+    }
+}
+
+/// A pinned image and how to run commands in it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContainerProfile {
+    #[serde(default)]
+    pub runtime: ContainerRuntime,
+    /// SIF file (`~/images/solver.sif`) or image reference
+    /// (`docker://ghcr.io/lab/solver@sha256:...`, `ghcr.io/lab/solver:2024.1`)
+    pub image: String,
+    /// Bind mounts `src[:dst[:options]]`, in addition to the project directory
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub binds: Vec<String>,
+    /// Host environment variables passed into the container (nothing else is)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+    /// `uv` inside the container (default: `tools.uv_path`, which Apptainer
+    /// sees through the home directory mount)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uv_path: Option<String>,
+    /// Additional runtime options (e.g. "--nv", "--gpus=all")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_args: Vec<String>,
+}
+
+/// Container a job ran in, as recorded when it started
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobContainer {
+    /// Profile selected on the job itself (`None` = project or default profile)
+    pub profile: Option<String>,
+    pub image: Option<String>,
+    /// `sha256:...` content digest (`None` when it could not be determined)
+    pub digest: Option<String>,
+}
+
+impl ContainerConfig {
+    /// Resolve the container profile of a job
+    ///
+    /// Precedence: job profile, then project profile, then `default_profile`.
+    ///
+    /// # Errors
+    /// Returns error if the selected profile is unknown or invalid
+    pub fn resolve(
+        &self,
+        job_profile: Option<&str>,
+        project_profile: Option<&str>,
+    ) -> Result<Option<&ContainerProfile>, String> {
+        let Some(name) = job_profile
+            .or(project_profile)
+            .or(self.default_profile.as_deref())
+        else {
+            return Ok(None);
+        };
+
+        let profile = self
+            .profiles
+            .get(name)
+            .ok_or_else(|| format!("Unknown container profile: {name}"))?;
+        profile
+            .validate()
+            .map_err(|e| format!("Container profile {name}: {e}"))?;
+        Ok(Some(profile))
+    }
+
+    /// Names of all container profiles
+    pub fn names(&self) -> Vec<String> {
+        self.profiles.keys().cloned().collect()
+    }
+}
+
+impl ContainerProfile {
+    /// Check values before they reach the job command line
+    ///
+    /// # Errors
+    /// Returns error on an empty image, a malformed variable name or option
+    pub fn validate(&self) -> Result<(), String> {
+        if self.image.trim().is_empty() {
+            return Err("image is empty".to_string());
+        }
+        if let Some(name) = self.env.iter().find(|name| !is_env_name(name)) {
+            return Err(format!("invalid environment variable '{name}'"));
+        }
+        if let Some(arg) = self
+            .extra_args
+            .iter()
+            .find(|arg| !arg.starts_with('-') || arg.contains(['\n', '\r']))
+        {
+            return Err(format!("invalid runtime option '{arg}'"));
+        }
+        Ok(())
+    }
+
+    /// Whether memory/CPU limits are passed to the runtime instead of the wrapper
+    ///
+    /// Docker jobs run under the daemon, out of reach of the wrapper's
+    /// cgroup scope and `ulimit`; Apptainer processes stay children of it.
+    pub const fn applies_limits(&self) -> bool {
+        matches!(self.runtime, ContainerRuntime::Docker)
+    }
+
+    /// `uv` to call inside the container
+    pub fn uv<'a>(&'a self, default: &'a str) -> &'a str {
+        self.uv_path.as_deref().unwrap_or(default)
+    }
+
+    /// Shell command running `command` in the container, from the current directory
+    pub fn wrap(&self, command: &str, limits: &JobLimits) -> String {
+        let mut args = vec![self.runtime.command().to_string()];
+        match self.runtime {
+            ContainerRuntime::Apptainer | ContainerRuntime::Singularity => {
+                // Home and current directory are mounted by default
+                args.extend(
+                    ["exec", "--cleanenv", "--env", "PYTHONUNBUFFERED=1"].map(String::from),
+                );
+                for name in &self.env {
+                    args.push(format!("--env {name}=\"${name}\""));
+                }
+                for bind in &self.binds {
+                    args.push(format!("--bind {}", job::shell_path(bind)));
+                }
+            }
+            ContainerRuntime::Docker => {
+                // Same user and project path as on the host, writable HOME for tools
+                args.extend(
+                    [
+                        "run",
+                        "--rm",
+                        "--init",
+                        "--user \"$(id -u):$(id -g)\"",
+                        "-v \"$PWD:$PWD\"",
+                        "-w \"$PWD\"",
+                        "-e HOME=/tmp",
+                        "-e PYTHONUNBUFFERED=1",
+                    ]
+                    .map(String::from),
+                );
+                for name in &self.env {
+                    args.push(format!("-e {name}"));
+                }
+                for bind in &self.binds {
+                    args.push(format!("-v {}", job::shell_path(bind)));
+                }
+                args.extend(docker_limits(limits));
+            }
+        }
+        args.extend(self.extra_args.iter().map(|arg| job::shell_path(arg)));
+        args.push(job::shell_path(&self.image));
+        args.push(command.to_string());
+        args.join(" ")
+    }
+
+    /// Shell command printing the image digest (see `parse_digest`)
+    pub fn digest_command(&self) -> String {
+        let image = job::shell_path(&self.image);
+        match self.runtime {
+            ContainerRuntime::Docker => {
+                let inspect = format!(
+                    "docker image inspect --format '{{{{if .RepoDigests}}}}{{{{index .RepoDigests 0}}}}{{{{else}}}}{{{{.Id}}}}{{{{end}}}}' {image} 2>/dev/null"
+                );
+                format!("{inspect} || {{ docker pull -q {image} >/dev/null 2>&1 && {inspect}; }}")
+            }
+            // SIF file: content hash; references are only pinned by their own digest
+            _ if self.image.contains("://") => "true".to_string(),
+            _ => format!("sha256sum {image} 2>/dev/null | cut -d' ' -f1"),
+        }
+    }
+
+    /// `sha256:<hex>` digest from the output of `digest_command`
+    pub fn parse_digest(&self, output: &str) -> Option<String> {
+        let reported = output.lines().map(str::trim).find(|l| !l.is_empty());
+        let digest = match reported {
+            // "ghcr.io/lab/solver@sha256:..." or image id "sha256:..."
+            Some(line) => line
+                .rsplit_once('@')
+                .map_or(line, |(_, digest)| digest)
+                .to_string(),
+            None => self.image.rsplit_once('@')?.1.to_string(),
+        };
+        let digest = if digest.starts_with("sha256:") {
+            digest
+        } else {
+            format!("sha256:{digest}")
+        };
+
+        let hex = digest.strip_prefix("sha256:")?;
+        (hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit())).then_some(digest)
+    }
+}
+
+/// Container profile of a job, with its image and digest recorded on the job
+///
+/// A digest that cannot be read is logged and left empty.
+///
+/// # Errors
+/// Returns error if the selected profile is unknown or invalid, or on a DB error
+pub async fn prepare_job(
+    pool: &SqlitePool,
+    executor: &SshExecutor,
+    config: &AppConfig,
+    job_id: i64,
+) -> Result<Option<ContainerProfile>, String> {
+    let (job_profile, project_profile) = db::get_job_container_selection(pool, job_id).await?;
+    let Some(profile) = config
+        .containers
+        .resolve(job_profile.as_deref(), project_profile.as_deref())?
+        .cloned()
+    else {
+        return Ok(None);
+    };
+
+    let digest = match image_digest(executor, &profile).await {
+        Ok(Some(digest)) => Some(digest),
+        Ok(None) => {
+            tracing::warn!("No digest for image {} of job {}", profile.image, job_id);
+            None
+        }
+        Err(e) => {
+            tracing::warn!("Image digest not read for job {}: {}", job_id, e);
+            None
+        }
+    };
+    db::set_job_container_image(pool, job_id, &profile.image, digest.as_deref()).await?;
+
+    Ok(Some(profile))
+}
+
+/// Digest of a profile's image on the server
+///
+/// # Errors
+/// Returns error if the command cannot be run
+pub async fn image_digest(
+    executor: &SshExecutor,
+    profile: &ContainerProfile,
+) -> Result<Option<String>, String> {
+    let output = executor
+        .execute_ignore_status(&profile.digest_command())
+        .await
+        .map_err(|e| format!("Failed to read image digest: {e}"))?;
+    Ok(profile.parse_digest(&output))
+}
+
+/// `docker run` resource options of a job
+fn docker_limits(limits: &JobLimits) -> Vec<String> {
+    let mut options = Vec::new();
+    if let Some(memory_mb) = limits.memory_mb {
+        options.push(format!("--memory={memory_mb}m --memory-swap={memory_mb}m"));
+    }
+    if let Some(percent) = limits.cpu_quota_percent {
+        options.push(format!("--cpus={}.{:02}", percent / 100, percent % 100));
+    }
+    if let Some(ref cpus) = limits.cpu_affinity {
+        options.push(format!("--cpuset-cpus={}", job::shell_path(cpus)));
+    }
+    if let Some(seconds) = limits.cpu_time_limit_s {
+        options.push(format!("--ulimit cpu={seconds}:{}", seconds + 5));
+    }
+    options
+}
+
+fn is_env_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Result<ContainerConfig, toml::de::Error> {
+        toml::from_str(
+            r#"
+default_profile = "solver"
+
+[profiles.solver]
+image = "~/images/solver.sif"
+binds = ["/scratch/instances:/data:ro"]
+env = ["GRB_LICENSE_FILE"]
+
+[profiles.docker]
+runtime = "docker"
+image = "ghcr.io/lab/solver:2024.1"
+uv_path = "uv"
+extra_args = ["--gpus=all"]
+
+[profiles.broken]
+image = "x.sif"
+env = ["NOT-A-NAME"]
+"#,
+        )
+    }
+
+    #[test]
+    fn test_resolve_profiles() -> Result<(), Box<dyn std::error::Error>> {
+        let config = config()?;
+        let default = config.resolve(None, None)?.ok_or("no default")?;
+        assert_eq!(default.runtime, ContainerRuntime::Apptainer);
+
+        let docker = config.resolve(Some("docker"), Some("solver"))?;
+        assert_eq!(docker.map(|p| p.uv("~/.local/bin/uv")), Some("uv"));
+
+        assert!(config.resolve(Some("missing"), None).is_err());
+        assert!(config.resolve(Some("broken"), None).is_err());
+        assert_eq!(ContainerConfig::default().resolve(None, None)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wrap_commands() -> Result<(), Box<dyn std::error::Error>> {
+        let config = config()?;
+        let limits = JobLimits {
+            memory_mb: Some(4096),
+            cpu_quota_percent: Some(250),
+            ..JobLimits::default()
+        };
+
+        let apptainer = config.resolve(Some("solver"), None)?.ok_or("no profile")?;
+        assert!(!apptainer.applies_limits());
+        assert_eq!(
+            apptainer.wrap("uv run python code/bench.py", &limits),
+            "apptainer exec --cleanenv --env PYTHONUNBUFFERED=1 --env GRB_LICENSE_FILE=\"$GRB_LICENSE_FILE\" \
+             --bind '/scratch/instances:/data:ro' \"$HOME\"/'images/solver.sif' uv run python code/bench.py"
+        );
+
+        let docker = config.resolve(Some("docker"), None)?.ok_or("no profile")?;
+        assert!(docker.applies_limits());
+        let command = docker.wrap("uv sync", &limits);
+        assert!(command.starts_with("docker run --rm --init --user \"$(id -u):$(id -g)\""));
+        assert!(command.contains(" --memory=4096m --memory-swap=4096m --cpus=2.50 '--gpus=all' "));
+        assert!(command.ends_with(" 'ghcr.io/lab/solver:2024.1' uv sync"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_digest() -> Result<(), Box<dyn std::error::Error>> {
+        let hex = "a".repeat(64);
+        let config = config()?;
+        let sif = config.resolve(Some("solver"), None)?.ok_or("no profile")?;
+        assert_eq!(
+            sif.parse_digest(&format!("{hex}\n")),
+            Some(format!("sha256:{hex}"))
+        );
+        assert_eq!(sif.parse_digest(""), None);
+
+        let docker = config.resolve(Some("docker"), None)?.ok_or("no profile")?;
+        assert_eq!(
+            docker.parse_digest(&format!("ghcr.io/lab/solver@sha256:{hex}\n")),
+            Some(format!("sha256:{hex}"))
+        );
+        assert_eq!(
+            docker.parse_digest(&format!("sha256:{hex}")),
+            Some(format!("sha256:{hex}"))
+        );
+
+        // A reference pinned by digest needs no lookup
+        let pinned = ContainerProfile {
+            image: format!("docker://ghcr.io/lab/solver@sha256:{hex}"),
+            ..ContainerProfile::default()
+        };
+        assert_eq!(pinned.digest_command(), "true");
+        assert_eq!(pinned.parse_digest(""), Some(format!("sha256:{hex}")));
+        Ok(())
+    }
+}
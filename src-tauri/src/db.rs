@@ -1,18 +1,45 @@
-use chrono::Utc;
-use sqlx::{sqlite::SqlitePool, Row};
+use std::path::Path;
 
-use crate::state::{Benchmark, Job, JobStatus, Project};
+use chrono::Utc;
+use sqlx::{sqlite::SqlitePool, Row, SqliteConnection};
+
+use crate::container::JobContainer;
+use crate::eta::{EtaJob, RunParams, RuntimeSample};
+use crate::history::{
+    self, BenchmarkStats, Cursor, CursorKey, DailyFailures, HistoryFilters, HistoryPage,
+    HistoryQuery, HistoryStats, SqlArg,
+};
+use crate::job;
+use crate::log_archive::ArchivedLog;
+use crate::migrations;
+use crate::report::InstanceResult;
+use crate::results::{ColumnKind, ResultColumn, ResultTable};
+use crate::state::{Benchmark, Job, JobSearchFilters, JobSearchHit, JobStatus, Project, Tag};
 
 // =============================================================================
 // Initialisation & Migrations
 // =============================================================================
 
-/// Initialise la base de données `SQLite` avec toutes les tables
+/// Ouvre la base de données `SQLite` et applique les migrations en attente
 pub async fn init_db(db_path: &str) -> Result<SqlitePool, String> {
     let pool = SqlitePool::connect(&format!("sqlite:{db_path}?mode=rwc"))
         .await
         .map_err(|e| format!("Erreur connexion SQLite: {e}"))?;
 
+    // Enable foreign keys
+    sqlx::query("PRAGMA foreign_keys = ON")
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Erreur activation foreign keys: {e}"))?;
+
+    // Migrations versionnées (schema_version dans metadata, sauvegarde préalable)
+    migrations::run(&pool, Some(Path::new(db_path))).await?;
+
+    Ok(pool)
+}
+
+/// Creates the projects, benchmarks and jobs tables (schema v1)
+pub async fn create_base_tables(conn: &mut SqliteConnection) -> Result<(), String> {
     // Table des projets
     sqlx::query(
         r"
@@ -25,7 +52,7 @@ pub async fn init_db(db_path: &str) -> Result<SqlitePool, String> {
         )
         ",
     )
-    .execute(&pool)
+    .execute(&mut *conn)
     .await
     .map_err(|e| format!("Erreur création table projects: {e}"))?;
 
@@ -43,7 +70,7 @@ pub async fn init_db(db_path: &str) -> Result<SqlitePool, String> {
         )
         ",
     )
-    .execute(&pool)
+    .execute(&mut *conn)
     .await
     .map_err(|e| format!("Erreur création table benchmarks: {e}"))?;
 
@@ -67,54 +94,135 @@ pub async fn init_db(db_path: &str) -> Result<SqlitePool, String> {
         )
         ",
     )
-    .execute(&pool)
+    .execute(&mut *conn)
     .await
     .map_err(|e| format!("Erreur création table jobs: {e}"))?;
 
-    // Table des métadonnées (queue state persistence - Story 2.5)
+    Ok(())
+}
+
+/// Creates the `results` and `result_columns` tables (ingested `benchmark_results.csv`)
+///
+/// Each row keeps well-known columns (instance, status, runtime, gap) plus the
+/// full row as JSON in `data`, queryable with `json_extract(data, '$.column')`.
+pub async fn create_results_tables(conn: &mut SqliteConnection) -> Result<(), String> {
     sqlx::query(
         r"
-        CREATE TABLE IF NOT EXISTS metadata (
-            key TEXT PRIMARY KEY,
-            value TEXT
+        CREATE TABLE IF NOT EXISTS results (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            job_id INTEGER NOT NULL,
+            row_index INTEGER NOT NULL,
+            instance TEXT,
+            status TEXT,
+            runtime REAL,
+            gap REAL,
+            data TEXT NOT NULL,
+            FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE,
+            UNIQUE(job_id, row_index)
         )
         ",
     )
-    .execute(&pool)
+    .execute(&mut *conn)
     .await
-    .map_err(|e| format!("Erreur création table metadata: {e}"))?;
+    .map_err(|e| format!("Erreur création table results: {e}"))?;
 
-    // Enable foreign keys
-    sqlx::query("PRAGMA foreign_keys = ON")
-        .execute(&pool)
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_results_instance ON results(instance)")
+        .execute(&mut *conn)
         .await
-        .map_err(|e| format!("Erreur activation foreign keys: {e}"))?;
+        .map_err(|e| format!("Erreur création index results: {e}"))?;
+
+    sqlx::query(
+        r"
+        CREATE TABLE IF NOT EXISTS result_columns (
+            job_id INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            PRIMARY KEY (job_id, position),
+            FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE
+        )
+        ",
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Erreur création table result_columns: {e}"))?;
 
-    // Run queue columns migration (Story 1.2 - Beta 1)
-    migrate_queue_columns(&pool).await?;
+    Ok(())
+}
 
-    Ok(pool)
+/// Creates the indexes used by history queries (filters, sorting, aggregates)
+pub async fn create_jobs_indexes(conn: &mut SqliteConnection) -> Result<(), String> {
+    for statement in [
+        "CREATE INDEX IF NOT EXISTS idx_jobs_status_finished ON jobs(status, finished_at)",
+        "CREATE INDEX IF NOT EXISTS idx_jobs_status_created ON jobs(status, created_at)",
+        "CREATE INDEX IF NOT EXISTS idx_jobs_project_created ON jobs(project_id, created_at)",
+        "CREATE INDEX IF NOT EXISTS idx_jobs_benchmark ON jobs(benchmark_name, status)",
+        "CREATE INDEX IF NOT EXISTS idx_results_job ON results(job_id)",
+    ] {
+        sqlx::query(statement)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Erreur création index jobs: {e}"))?;
+    }
+
+    Ok(())
+}
+
+/// Indexed text of a job: benchmark name, error message, parameters (environment
+/// profile and result instances) and log (`?` = full log, falls back to `log_content`)
+const SEARCH_ROW_SELECT: &str = r"
+    SELECT j.id, j.benchmark_name, COALESCE(j.error_message, ''),
+           TRIM(COALESCE(j.env_profile, '') || ' ' || COALESCE(
+               (SELECT group_concat(DISTINCT r.instance) FROM results r WHERE r.job_id = j.id), ''
+           )),
+           COALESCE(?, j.log_content, '')
+    FROM jobs j
+";
+
+/// Creates the `jobs_fts` full-text index (FTS5, `rowid` = job id)
+///
+/// Rows are written by `index_job_search` when a job finishes; jobs finished
+/// before the index existed are indexed once from their stored log tail.
+pub async fn create_search_index(conn: &mut SqliteConnection) -> Result<(), String> {
+    let exists = migrations::table_exists(conn, "jobs_fts").await?;
+
+    if !exists {
+        sqlx::query(
+            "CREATE VIRTUAL TABLE jobs_fts USING fts5(benchmark_name, error_message, params, log)",
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Erreur création index de recherche: {e}"))?;
+
+        sqlx::query(&format!(
+            "INSERT INTO jobs_fts(rowid, benchmark_name, error_message, params, log)
+             {SEARCH_ROW_SELECT} WHERE j.status IN ('completed', 'failed', 'killed')"
+        ))
+        .bind(None::<String>)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Erreur indexation des jobs: {e}"))?;
+    }
+
+    Ok(())
 }
 
 /// Migrates the jobs table to add queue-specific columns (Story 1.2)
 /// This migration is idempotent - safe to run multiple times
-pub async fn migrate_queue_columns(pool: &SqlitePool) -> Result<(), String> {
+pub async fn migrate_queue_columns(conn: &mut SqliteConnection) -> Result<(), String> {
     // Check if columns already exist (idempotent migration)
-    let has_queue_position = sqlx::query("SELECT queue_position FROM jobs LIMIT 1")
-        .fetch_optional(pool)
-        .await
-        .is_ok();
+    let has_queue_position = migrations::has_column(conn, "jobs", "queue_position").await?;
 
     if !has_queue_position {
         // Add queue_position column (nullable - NULL for non-queued jobs)
         sqlx::query("ALTER TABLE jobs ADD COLUMN queue_position INTEGER")
-            .execute(pool)
+            .execute(&mut *conn)
             .await
             .map_err(|e| format!("Failed to add queue_position column: {e}"))?;
 
         // Add queued_at column (nullable - NULL for non-queued jobs)
         sqlx::query("ALTER TABLE jobs ADD COLUMN queued_at TEXT")
-            .execute(pool)
+            .execute(&mut *conn)
             .await
             .map_err(|e| format!("Failed to add queued_at column: {e}"))?;
     }
@@ -122,6 +230,241 @@ pub async fn migrate_queue_columns(pool: &SqlitePool) -> Result<(), String> {
     Ok(())
 }
 
+/// Adds `env_profile` columns to projects and jobs
+/// This migration is idempotent - safe to run multiple times
+pub async fn migrate_env_profile_columns(conn: &mut SqliteConnection) -> Result<(), String> {
+    for table in ["projects", "jobs"] {
+        let has_column = migrations::has_column(conn, table, "env_profile").await?;
+
+        if !has_column {
+            sqlx::query(&format!("ALTER TABLE {table} ADD COLUMN env_profile TEXT"))
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| format!("Failed to add env_profile column to {table}: {e}"))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Adds the `code_version` column (JSON `CodeVersion`) to jobs
+/// This migration is idempotent - safe to run multiple times
+pub async fn migrate_code_version_column(conn: &mut SqliteConnection) -> Result<(), String> {
+    let has_column = migrations::has_column(conn, "jobs", "code_version").await?;
+
+    if !has_column {
+        sqlx::query("ALTER TABLE jobs ADD COLUMN code_version TEXT")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to add code_version column: {e}"))?;
+    }
+
+    Ok(())
+}
+
+/// Adds the `env_fingerprint` column (JSON `EnvFingerprint`) to jobs
+/// This migration is idempotent - safe to run multiple times
+pub async fn migrate_env_fingerprint_column(conn: &mut SqliteConnection) -> Result<(), String> {
+    let has_column = migrations::has_column(conn, "jobs", "env_fingerprint").await?;
+
+    if !has_column {
+        sqlx::query("ALTER TABLE jobs ADD COLUMN env_fingerprint TEXT")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to add env_fingerprint column: {e}"))?;
+    }
+
+    Ok(())
+}
+
+/// Adds `peak_rss_kb` and `cpu_seconds` columns to jobs
+/// This migration is idempotent - safe to run multiple times
+pub async fn migrate_resource_usage_columns(conn: &mut SqliteConnection) -> Result<(), String> {
+    let has_columns = migrations::has_column(conn, "jobs", "peak_rss_kb").await?;
+
+    if !has_columns {
+        sqlx::query("ALTER TABLE jobs ADD COLUMN peak_rss_kb INTEGER")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to add peak_rss_kb column: {e}"))?;
+
+        sqlx::query("ALTER TABLE jobs ADD COLUMN cpu_seconds REAL")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to add cpu_seconds column: {e}"))?;
+    }
+
+    Ok(())
+}
+
+/// Adds the `limits` column (JSON `JobLimits`) to benchmarks
+/// This migration is idempotent - safe to run multiple times
+pub async fn migrate_benchmark_limits_column(conn: &mut SqliteConnection) -> Result<(), String> {
+    let has_column = migrations::has_column(conn, "benchmarks", "limits").await?;
+
+    if !has_column {
+        sqlx::query("ALTER TABLE benchmarks ADD COLUMN limits TEXT")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to add limits column: {e}"))?;
+    }
+
+    Ok(())
+}
+
+/// Adds `log_archive_path`, `log_archive_size` and `log_archived_at` columns to jobs
+/// This migration is idempotent - safe to run multiple times
+pub async fn migrate_log_archive_columns(conn: &mut SqliteConnection) -> Result<(), String> {
+    let has_columns = migrations::has_column(conn, "jobs", "log_archive_path").await?;
+
+    if !has_columns {
+        sqlx::query("ALTER TABLE jobs ADD COLUMN log_archive_path TEXT")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to add log_archive_path column: {e}"))?;
+
+        sqlx::query("ALTER TABLE jobs ADD COLUMN log_archive_size INTEGER")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to add log_archive_size column: {e}"))?;
+
+        sqlx::query("ALTER TABLE jobs ADD COLUMN log_archived_at TEXT")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to add log_archived_at column: {e}"))?;
+    }
+
+    Ok(())
+}
+
+/// Adds the `server` column to jobs (target server, NULL = default server)
+/// This migration is idempotent - safe to run multiple times
+pub async fn migrate_job_server_column(conn: &mut SqliteConnection) -> Result<(), String> {
+    let has_column = migrations::has_column(conn, "jobs", "server").await?;
+
+    if !has_column {
+        sqlx::query("ALTER TABLE jobs ADD COLUMN server TEXT")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to add server column: {e}"))?;
+    }
+
+    Ok(())
+}
+
+/// Adds the `servers` column (JSON list of allowed servers) to benchmarks
+/// This migration is idempotent - safe to run multiple times
+pub async fn migrate_benchmark_servers_column(conn: &mut SqliteConnection) -> Result<(), String> {
+    let has_column = migrations::has_column(conn, "benchmarks", "servers").await?;
+
+    if !has_column {
+        sqlx::query("ALTER TABLE benchmarks ADD COLUMN servers TEXT")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to add servers column: {e}"))?;
+    }
+
+    Ok(())
+}
+
+/// Adds the `backend_job_id` column to jobs (id of the job in a batch scheduler)
+/// This migration is idempotent - safe to run multiple times
+pub async fn migrate_job_backend_id_column(conn: &mut SqliteConnection) -> Result<(), String> {
+    let has_column = migrations::has_column(conn, "jobs", "backend_job_id").await?;
+
+    if !has_column {
+        sqlx::query("ALTER TABLE jobs ADD COLUMN backend_job_id TEXT")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to add backend_job_id column: {e}"))?;
+    }
+
+    Ok(())
+}
+
+/// Adds the `not_before` column to jobs (earliest start time, UTC RFC 3339)
+/// This migration is idempotent - safe to run multiple times
+pub async fn migrate_not_before_column(conn: &mut SqliteConnection) -> Result<(), String> {
+    if !migrations::has_column(conn, "jobs", "not_before").await? {
+        sqlx::query("ALTER TABLE jobs ADD COLUMN not_before TEXT")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to add not_before column: {e}"))?;
+    }
+
+    Ok(())
+}
+
+/// Adds the container columns: `container` on projects and jobs (selected
+/// profile), `container_image` / `container_digest` on jobs (image it ran in)
+///
+/// This migration is idempotent - safe to run multiple times
+pub async fn migrate_container_columns(conn: &mut SqliteConnection) -> Result<(), String> {
+    let columns = [
+        ("projects", "container"),
+        ("jobs", "container"),
+        ("jobs", "container_image"),
+        ("jobs", "container_digest"),
+    ];
+    for (table, column) in columns {
+        if !migrations::has_column(conn, table, column).await? {
+            sqlx::query(&format!("ALTER TABLE {table} ADD COLUMN {column} TEXT"))
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| format!("Failed to add {column} column to {table}: {e}"))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates the `tags` / `job_tags` tables and adds the `notes` column to jobs
+/// This migration is idempotent - safe to run multiple times
+pub async fn migrate_tags_and_notes(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r"
+        CREATE TABLE IF NOT EXISTS tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE
+        )
+        ",
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to create tags table: {e}"))?;
+
+    sqlx::query(
+        r"
+        CREATE TABLE IF NOT EXISTS job_tags (
+            job_id INTEGER NOT NULL,
+            tag_id INTEGER NOT NULL,
+            PRIMARY KEY (job_id, tag_id),
+            FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE,
+            FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+        )
+        ",
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to create job_tags table: {e}"))?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_job_tags_tag ON job_tags(tag_id)")
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to create job_tags index: {e}"))?;
+
+    let has_notes = migrations::has_column(conn, "jobs", "notes").await?;
+
+    if !has_notes {
+        sqlx::query("ALTER TABLE jobs ADD COLUMN notes TEXT")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to add notes column: {e}"))?;
+    }
+
+    Ok(())
+}
+
 // =============================================================================
 // Projects CRUD
 // =============================================================================
@@ -155,7 +498,7 @@ pub async fn insert_project(
 pub async fn get_project(pool: &SqlitePool, id: i64) -> Result<Option<Project>, String> {
     let row = sqlx::query(
         r"
-        SELECT id, name, python_version, created_at, updated_at
+        SELECT id, name, python_version, created_at, updated_at, env_profile, container
         FROM projects WHERE id = ?
         ",
     )
@@ -170,6 +513,8 @@ pub async fn get_project(pool: &SqlitePool, id: i64) -> Result<Option<Project>,
         python_version: r.get("python_version"),
         created_at: r.get("created_at"),
         updated_at: r.get("updated_at"),
+        env_profile: r.get("env_profile"),
+        container: r.get("container"),
     }))
 }
 
@@ -177,7 +522,7 @@ pub async fn get_project(pool: &SqlitePool, id: i64) -> Result<Option<Project>,
 pub async fn list_projects(pool: &SqlitePool) -> Result<Vec<Project>, String> {
     let rows = sqlx::query(
         r"
-        SELECT id, name, python_version, created_at, updated_at
+        SELECT id, name, python_version, created_at, updated_at, env_profile, container
         FROM projects ORDER BY name ASC
         ",
     )
@@ -193,6 +538,8 @@ pub async fn list_projects(pool: &SqlitePool) -> Result<Vec<Project>, String> {
             python_version: r.get("python_version"),
             created_at: r.get("created_at"),
             updated_at: r.get("updated_at"),
+            env_profile: r.get("env_profile"),
+            container: r.get("container"),
         })
         .collect())
 }
@@ -216,6 +563,44 @@ pub async fn update_project_python_version(
     Ok(())
 }
 
+/// Définit le profil d'environnement d'un projet (`None` = profil par défaut)
+pub async fn update_project_env_profile(
+    pool: &SqlitePool,
+    id: i64,
+    env_profile: Option<&str>,
+) -> Result<(), String> {
+    let now = Utc::now().to_rfc3339();
+
+    sqlx::query("UPDATE projects SET env_profile = ?, updated_at = ? WHERE id = ?")
+        .bind(env_profile)
+        .bind(&now)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Erreur mise à jour profil d'environnement: {e}"))?;
+
+    Ok(())
+}
+
+/// Définit le profil de conteneur d'un projet (`None` = profil par défaut)
+pub async fn update_project_container(
+    pool: &SqlitePool,
+    id: i64,
+    container: Option<&str>,
+) -> Result<(), String> {
+    let now = Utc::now().to_rfc3339();
+
+    sqlx::query("UPDATE projects SET container = ?, updated_at = ? WHERE id = ?")
+        .bind(container)
+        .bind(&now)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Erreur mise à jour profil de conteneur: {e}"))?;
+
+    Ok(())
+}
+
 /// Supprime un projet (cascade sur benchmarks)
 pub async fn delete_project(pool: &SqlitePool, id: i64) -> Result<(), String> {
     sqlx::query("DELETE FROM projects WHERE id = ?")
@@ -313,6 +698,124 @@ pub async fn delete_benchmark(pool: &SqlitePool, id: i64) -> Result<(), String>
     Ok(())
 }
 
+/// Enregistre les limites (JSON `JobLimits`) d'un benchmark, `None` pour les retirer
+pub async fn set_benchmark_limits(
+    pool: &SqlitePool,
+    benchmark_id: i64,
+    limits: Option<&str>,
+) -> Result<(), String> {
+    let result = sqlx::query("UPDATE benchmarks SET limits = ? WHERE id = ?")
+        .bind(limits)
+        .bind(benchmark_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Erreur mise à jour limites benchmark: {e}"))?;
+
+    if result.rows_affected() == 0 {
+        return Err(format!("Benchmark {benchmark_id} not found"));
+    }
+
+    Ok(())
+}
+
+/// Charge les limites (JSON `JobLimits`) d'un benchmark
+pub async fn get_benchmark_limits(
+    pool: &SqlitePool,
+    benchmark_id: i64,
+) -> Result<Option<String>, String> {
+    let row = sqlx::query("SELECT limits FROM benchmarks WHERE id = ?")
+        .bind(benchmark_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Erreur lecture limites benchmark: {e}"))?
+        .ok_or_else(|| format!("Benchmark {benchmark_id} not found"))?;
+
+    Ok(row.get("limits"))
+}
+
+/// Limites du benchmark d'un job (recherche par projet et nom de benchmark)
+pub async fn get_benchmark_limits_by_name(
+    pool: &SqlitePool,
+    project_id: i64,
+    benchmark_name: &str,
+) -> Result<Option<String>, String> {
+    let row = sqlx::query("SELECT limits FROM benchmarks WHERE project_id = ? AND name = ?")
+        .bind(project_id)
+        .bind(benchmark_name)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Erreur lecture limites benchmark: {e}"))?;
+
+    Ok(row.and_then(|r| r.get("limits")))
+}
+
+/// Restreint un benchmark à certains serveurs (vide = tous les serveurs)
+pub async fn set_benchmark_servers(
+    pool: &SqlitePool,
+    benchmark_id: i64,
+    servers: &[String],
+) -> Result<(), String> {
+    let json = if servers.is_empty() {
+        None
+    } else {
+        Some(
+            serde_json::to_string(servers)
+                .map_err(|e| format!("Failed to serialize benchmark servers: {e}"))?,
+        )
+    };
+
+    let result = sqlx::query("UPDATE benchmarks SET servers = ? WHERE id = ?")
+        .bind(json)
+        .bind(benchmark_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Erreur mise à jour serveurs benchmark: {e}"))?;
+
+    if result.rows_affected() == 0 {
+        return Err(format!("Benchmark {benchmark_id} not found"));
+    }
+
+    Ok(())
+}
+
+fn parse_benchmark_servers(json: Option<String>) -> Result<Vec<String>, String> {
+    json.map_or_else(
+        || Ok(Vec::new()),
+        |json| serde_json::from_str(&json).map_err(|e| format!("Invalid benchmark servers: {e}")),
+    )
+}
+
+/// Serveurs autorisés pour un benchmark (vide = tous)
+pub async fn get_benchmark_servers(
+    pool: &SqlitePool,
+    benchmark_id: i64,
+) -> Result<Vec<String>, String> {
+    let row = sqlx::query("SELECT servers FROM benchmarks WHERE id = ?")
+        .bind(benchmark_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Erreur lecture serveurs benchmark: {e}"))?
+        .ok_or_else(|| format!("Benchmark {benchmark_id} not found"))?;
+
+    parse_benchmark_servers(row.get("servers"))
+}
+
+/// Serveurs autorisés pour le benchmark d'un job (recherche par projet et nom)
+pub async fn get_benchmark_servers_by_name(
+    pool: &SqlitePool,
+    project_id: i64,
+    benchmark_name: &str,
+) -> Result<Vec<String>, String> {
+    let row = sqlx::query("SELECT servers FROM benchmarks WHERE project_id = ? AND name = ?")
+        .bind(project_id)
+        .bind(benchmark_name)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Erreur lecture serveurs benchmark: {e}"))?;
+
+    parse_benchmark_servers(row.and_then(|r| r.get("servers")))
+}
+
 // =============================================================================
 // Queue Helper Functions (Story 1.2 - Beta 1)
 // =============================================================================
@@ -430,7 +933,8 @@ pub async fn get_queued_jobs(pool: &SqlitePool) -> Result<Vec<Job>, String> {
         r"
         SELECT id, project_id, benchmark_name, status, created_at, started_at, finished_at,
                progress_current, progress_total, results_path, error_message, log_content,
-               queue_position, queued_at
+               queue_position, queued_at, env_profile, peak_rss_kb, cpu_seconds, notes, server,
+               not_before
         FROM jobs
         WHERE queue_position IS NOT NULL
         ORDER BY
@@ -448,14 +952,16 @@ pub async fn get_queued_jobs(pool: &SqlitePool) -> Result<Vec<Job>, String> {
     .await
     .map_err(|e| format!("Failed to get queued jobs: {e}"))?;
 
-    Ok(rows_to_jobs_with_queue(rows))
+    let mut jobs = rows_to_jobs_with_queue(rows);
+    attach_tags(pool, &mut jobs).await?;
+    Ok(jobs)
 }
 
 // =============================================================================
 // Jobs (existant)
 // =============================================================================
 
-const fn status_to_str(status: &JobStatus) -> &'static str {
+pub(crate) const fn status_to_str(status: &JobStatus) -> &'static str {
     match status {
         JobStatus::Pending => "pending",
         JobStatus::Running => "running",
@@ -583,20 +1089,748 @@ pub async fn update_job_error(pool: &SqlitePool, job_id: i64, error: &str) -> Re
     Ok(())
 }
 
-/// Supprime un job en attente
-pub async fn delete_pending_job(pool: &SqlitePool, job_id: i64) -> Result<(), String> {
-    sqlx::query("DELETE FROM jobs WHERE id = ? AND status = 'pending'")
+/// Enregistre la mémoire maximale et le temps CPU d'un job terminé
+pub async fn update_job_resource_usage(
+    pool: &SqlitePool,
+    job_id: i64,
+    peak_rss_kb: u64,
+    cpu_seconds: f64,
+) -> Result<(), String> {
+    // Safe: a peak RSS in KiB never approaches i64::MAX
+    #[allow(clippy::cast_possible_wrap)]
+    let peak_rss_kb = peak_rss_kb as i64;
+
+    sqlx::query("UPDATE jobs SET peak_rss_kb = ?, cpu_seconds = ? WHERE id = ?")
+        .bind(peak_rss_kb)
+        .bind(cpu_seconds)
         .bind(job_id)
         .execute(pool)
         .await
-        .map_err(|e| format!("Erreur suppression job: {e}"))?;
+        .map_err(|e| format!("Erreur mise à jour ressources: {e}"))?;
 
     Ok(())
 }
 
-/// Charge les jobs en attente
-pub async fn load_pending_jobs(pool: &SqlitePool) -> Result<Vec<Job>, String> {
-    load_jobs_by_status(pool, "pending").await
+/// Enregistre la version du code (JSON `CodeVersion`) d'un job
+pub async fn set_job_code_version(
+    pool: &SqlitePool,
+    job_id: i64,
+    code_version: &str,
+) -> Result<(), String> {
+    sqlx::query("UPDATE jobs SET code_version = ? WHERE id = ?")
+        .bind(code_version)
+        .bind(job_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Erreur mise à jour code_version: {e}"))?;
+
+    Ok(())
+}
+
+/// Charge la version du code (JSON `CodeVersion`) d'un job
+pub async fn get_job_code_version(
+    pool: &SqlitePool,
+    job_id: i64,
+) -> Result<Option<String>, String> {
+    let row = sqlx::query("SELECT code_version FROM jobs WHERE id = ?")
+        .bind(job_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Erreur lecture code_version: {e}"))?
+        .ok_or_else(|| format!("Job {job_id} not found"))?;
+
+    Ok(row.get("code_version"))
+}
+
+/// Enregistre l'empreinte d'environnement (JSON `EnvFingerprint`) d'un job
+pub async fn set_job_env_fingerprint(
+    pool: &SqlitePool,
+    job_id: i64,
+    env_fingerprint: &str,
+) -> Result<(), String> {
+    sqlx::query("UPDATE jobs SET env_fingerprint = ? WHERE id = ?")
+        .bind(env_fingerprint)
+        .bind(job_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Erreur mise à jour env_fingerprint: {e}"))?;
+
+    Ok(())
+}
+
+/// Charge l'empreinte d'environnement (JSON `EnvFingerprint`) d'un job
+pub async fn get_job_env_fingerprint(
+    pool: &SqlitePool,
+    job_id: i64,
+) -> Result<Option<String>, String> {
+    let row = sqlx::query("SELECT env_fingerprint FROM jobs WHERE id = ?")
+        .bind(job_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Erreur lecture env_fingerprint: {e}"))?
+        .ok_or_else(|| format!("Job {job_id} not found"))?;
+
+    Ok(row.get("env_fingerprint"))
+}
+
+/// Serveur cible d'un job (`None` = serveur par défaut)
+pub async fn get_job_server(pool: &SqlitePool, job_id: i64) -> Result<Option<String>, String> {
+    let row = sqlx::query("SELECT server FROM jobs WHERE id = ?")
+        .bind(job_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Erreur lecture server: {e}"))?
+        .ok_or_else(|| format!("Job {job_id} not found"))?;
+
+    Ok(row.get("server"))
+}
+
+/// Enregistre l'identifiant du job dans l'ordonnanceur (job id Slurm, groupe de processus local)
+pub async fn set_job_backend_id(
+    pool: &SqlitePool,
+    job_id: i64,
+    backend_job_id: &str,
+) -> Result<(), String> {
+    sqlx::query("UPDATE jobs SET backend_job_id = ? WHERE id = ?")
+        .bind(backend_job_id)
+        .bind(job_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Erreur mise à jour backend_job_id: {e}"))?;
+    Ok(())
+}
+
+/// Enregistre l'emplacement du CSV de résultats propre au job sur le serveur
+pub async fn set_job_results_path(
+    pool: &SqlitePool,
+    job_id: i64,
+    results_path: &str,
+) -> Result<(), String> {
+    sqlx::query("UPDATE jobs SET results_path = ? WHERE id = ?")
+        .bind(results_path)
+        .bind(job_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Erreur mise à jour results_path: {e}"))?;
+    Ok(())
+}
+
+/// Identifiant du job dans l'ordonnanceur (`None` = job tmux ou pas encore soumis)
+pub async fn get_job_backend_id(pool: &SqlitePool, job_id: i64) -> Result<Option<String>, String> {
+    let row = sqlx::query("SELECT backend_job_id FROM jobs WHERE id = ?")
+        .bind(job_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Erreur lecture backend_job_id: {e}"))?
+        .ok_or_else(|| format!("Job {job_id} not found"))?;
+
+    Ok(row.get("backend_job_id"))
+}
+
+/// Profils de conteneur choisis pour un job et pour son projet
+pub async fn get_job_container_selection(
+    pool: &SqlitePool,
+    job_id: i64,
+) -> Result<(Option<String>, Option<String>), String> {
+    let row = sqlx::query(
+        r"
+        SELECT j.container, p.container AS project_container
+        FROM jobs j LEFT JOIN projects p ON p.id = j.project_id
+        WHERE j.id = ?
+        ",
+    )
+    .bind(job_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Erreur lecture conteneur: {e}"))?
+    .ok_or_else(|| format!("Job {job_id} not found"))?;
+
+    Ok((row.get("container"), row.get("project_container")))
+}
+
+/// Enregistre l'image dans laquelle un job a démarré
+pub async fn set_job_container_image(
+    pool: &SqlitePool,
+    job_id: i64,
+    image: &str,
+    digest: Option<&str>,
+) -> Result<(), String> {
+    sqlx::query("UPDATE jobs SET container_image = ?, container_digest = ? WHERE id = ?")
+        .bind(image)
+        .bind(digest)
+        .bind(job_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Erreur mise à jour image du conteneur: {e}"))?;
+    Ok(())
+}
+
+/// Conteneur d'un job (`None` = exécuté hors conteneur)
+pub async fn get_job_container(
+    pool: &SqlitePool,
+    job_id: i64,
+) -> Result<Option<JobContainer>, String> {
+    let row =
+        sqlx::query("SELECT container, container_image, container_digest FROM jobs WHERE id = ?")
+            .bind(job_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| format!("Erreur lecture conteneur: {e}"))?
+            .ok_or_else(|| format!("Job {job_id} not found"))?;
+
+    let container = JobContainer {
+        profile: row.get("container"),
+        image: row.get("container_image"),
+        digest: row.get("container_digest"),
+    };
+    Ok((container.profile.is_some() || container.image.is_some()).then_some(container))
+}
+
+/// Référence l'archive compressée du log d'un job (`size` = taille compressée en octets)
+pub async fn set_job_log_archive(
+    pool: &SqlitePool,
+    job_id: i64,
+    path: &str,
+    size: u64,
+) -> Result<(), String> {
+    // Safe: an archive file never approaches i64::MAX bytes
+    #[allow(clippy::cast_possible_wrap)]
+    let size = size as i64;
+
+    sqlx::query(
+        "UPDATE jobs SET log_archive_path = ?, log_archive_size = ?, log_archived_at = ? WHERE id = ?",
+    )
+    .bind(path)
+    .bind(size)
+    .bind(Utc::now().to_rfc3339())
+    .bind(job_id)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Erreur mise à jour archive log: {e}"))?;
+
+    Ok(())
+}
+
+/// Oublie l'archive du log d'un job (fichier supprimé par la rétention)
+pub async fn clear_job_log_archive(pool: &SqlitePool, job_id: i64) -> Result<(), String> {
+    sqlx::query(
+        "UPDATE jobs SET log_archive_path = NULL, log_archive_size = NULL, log_archived_at = NULL WHERE id = ?",
+    )
+    .bind(job_id)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Erreur suppression archive log: {e}"))?;
+
+    Ok(())
+}
+
+/// Charge le chemin de l'archive du log d'un job
+pub async fn get_job_log_archive(pool: &SqlitePool, job_id: i64) -> Result<Option<String>, String> {
+    let row = sqlx::query("SELECT log_archive_path FROM jobs WHERE id = ?")
+        .bind(job_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Erreur lecture archive log: {e}"))?
+        .ok_or_else(|| format!("Job {job_id} not found"))?;
+
+    Ok(row.get("log_archive_path"))
+}
+
+/// Liste les archives de logs, des plus anciennes aux plus récentes
+pub async fn list_log_archives(pool: &SqlitePool) -> Result<Vec<ArchivedLog>, String> {
+    let rows = sqlx::query(
+        "SELECT id, log_archive_path, log_archive_size, log_archived_at FROM jobs
+         WHERE log_archive_path IS NOT NULL
+         ORDER BY log_archived_at ASC, id ASC",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Erreur lecture archives logs: {e}"))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let size: Option<i64> = row.get("log_archive_size");
+            ArchivedLog {
+                job_id: row.get("id"),
+                path: row.get("log_archive_path"),
+                size: size.and_then(|s| u64::try_from(s).ok()).unwrap_or(0),
+                archived_at: row
+                    .get::<Option<String>, _>("log_archived_at")
+                    .unwrap_or_default(),
+            }
+        })
+        .collect())
+}
+
+// =============================================================================
+// Tags & notes
+// =============================================================================
+
+/// Nettoie une liste de tags : espaces retirés, vides ignorés, doublons (casse ignorée) fusionnés
+pub fn normalize_tags(tags: &[String]) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            continue;
+        }
+        if tag.chars().count() > 64 || tag.chars().any(char::is_control) {
+            return Err(format!("Invalid tag '{tag}'"));
+        }
+        if !normalized.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            normalized.push(tag.to_string());
+        }
+    }
+    Ok(normalized)
+}
+
+/// Ajoute des tags (déjà normalisés) à un job, en créant les tags inconnus
+///
+/// Prend une connexion pour s'exécuter dans la transaction de l'appelant.
+pub async fn add_job_tags(
+    conn: &mut SqliteConnection,
+    job_id: i64,
+    tags: &[String],
+) -> Result<(), String> {
+    for tag in tags {
+        sqlx::query("INSERT OR IGNORE INTO tags (name) VALUES (?)")
+            .bind(tag)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Erreur création tag: {e}"))?;
+
+        sqlx::query(
+            "INSERT OR IGNORE INTO job_tags (job_id, tag_id) SELECT ?, id FROM tags WHERE name = ?",
+        )
+        .bind(job_id)
+        .bind(tag)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Erreur ajout tag: {e}"))?;
+    }
+
+    Ok(())
+}
+
+/// Remplace les tags d'un job (les tags orphelins sont supprimés)
+pub async fn set_job_tags(pool: &SqlitePool, job_id: i64, tags: &[String]) -> Result<(), String> {
+    let tags = normalize_tags(tags)?;
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to begin transaction: {e}"))?;
+
+    sqlx::query("DELETE FROM job_tags WHERE job_id = ?")
+        .bind(job_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Erreur suppression tags: {e}"))?;
+
+    add_job_tags(&mut tx, job_id, &tags).await?;
+
+    sqlx::query("DELETE FROM tags WHERE id NOT IN (SELECT tag_id FROM job_tags)")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Erreur nettoyage tags: {e}"))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {e}"))?;
+
+    Ok(())
+}
+
+/// Liste les tags avec leur nombre de jobs (ordre alphabétique)
+pub async fn list_tags(pool: &SqlitePool) -> Result<Vec<Tag>, String> {
+    let rows = sqlx::query(
+        r"
+        SELECT t.name, COUNT(jt.job_id) AS job_count
+        FROM tags t
+        LEFT JOIN job_tags jt ON jt.tag_id = t.id
+        GROUP BY t.id
+        ORDER BY t.name COLLATE NOCASE ASC
+        ",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Erreur lecture tags: {e}"))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| Tag {
+            name: row.get("name"),
+            job_count: row.get("job_count"),
+        })
+        .collect())
+}
+
+/// Jobs terminés portant au moins un des tags (plus anciens d'abord)
+pub async fn job_ids_with_any_tag(pool: &SqlitePool, tags: &[String]) -> Result<Vec<i64>, String> {
+    if tags.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let placeholders = vec!["?"; tags.len()].join(", ");
+    let sql = format!(
+        r"
+        SELECT DISTINCT j.id
+        FROM jobs j
+        JOIN job_tags jt ON jt.job_id = j.id
+        JOIN tags t ON t.id = jt.tag_id
+        WHERE t.name IN ({placeholders}) AND j.status = 'completed'
+        ORDER BY j.id ASC
+        "
+    );
+
+    let mut query = sqlx::query_scalar(&sql);
+    for tag in tags {
+        query = query.bind(tag);
+    }
+
+    query
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Erreur lecture jobs par tag: {e}"))
+}
+
+/// Remplit `tags` des jobs chargés
+async fn attach_tags(pool: &SqlitePool, jobs: &mut [Job]) -> Result<(), String> {
+    if jobs.is_empty() {
+        return Ok(());
+    }
+
+    let placeholders = vec!["?"; jobs.len()].join(", ");
+    let sql = format!(
+        r"
+        SELECT jt.job_id, t.name
+        FROM job_tags jt
+        JOIN tags t ON t.id = jt.tag_id
+        WHERE jt.job_id IN ({placeholders})
+        ORDER BY t.name COLLATE NOCASE ASC
+        "
+    );
+
+    let mut query = sqlx::query(&sql);
+    for job in jobs.iter() {
+        query = query.bind(job.id);
+    }
+
+    let rows = query
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Erreur lecture tags: {e}"))?;
+
+    for row in rows {
+        let job_id: i64 = row.get("job_id");
+        if let Some(job) = jobs.iter_mut().find(|job| job.id == job_id) {
+            job.tags.push(row.get("name"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Enregistre les notes d'un job (`None` ou vide = effacées)
+pub async fn set_job_notes(
+    pool: &SqlitePool,
+    job_id: i64,
+    notes: Option<&str>,
+) -> Result<(), String> {
+    let notes = notes.map(str::trim).filter(|n| !n.is_empty());
+
+    let result = sqlx::query("UPDATE jobs SET notes = ? WHERE id = ?")
+        .bind(notes)
+        .bind(job_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Erreur mise à jour notes: {e}"))?;
+
+    if result.rows_affected() == 0 {
+        return Err(format!("Job {job_id} not found"));
+    }
+    Ok(())
+}
+
+/// Définit (ou efface) l'heure de départ au plus tôt d'un job en attente
+///
+/// `not_before` est un horodatage UTC normalisé (`job::parse_start_time`).
+pub async fn set_job_not_before(
+    pool: &SqlitePool,
+    job_id: i64,
+    not_before: Option<&str>,
+) -> Result<(), String> {
+    let result = sqlx::query("UPDATE jobs SET not_before = ? WHERE id = ? AND status = 'pending'")
+        .bind(not_before)
+        .bind(job_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Erreur mise à jour heure de départ: {e}"))?;
+
+    if result.rows_affected() == 0 {
+        return Err(format!("Job {job_id} is not pending"));
+    }
+    Ok(())
+}
+
+/// Prochaine heure de départ d'un job en attente pas encore démarrable
+pub async fn next_job_start(pool: &SqlitePool, now: &str) -> Result<Option<String>, String> {
+    sqlx::query_scalar(
+        "SELECT MIN(not_before) FROM jobs WHERE status = 'pending' AND not_before > ?",
+    )
+    .bind(now)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Erreur lecture heures de départ: {e}"))
+}
+
+// =============================================================================
+// Completion estimates
+// =============================================================================
+
+/// Jobs en attente et en cours pour les estimations (en cours d'abord, puis ordre de la file)
+pub async fn load_eta_jobs(pool: &SqlitePool) -> Result<Vec<EtaJob>, String> {
+    let rows = sqlx::query(
+        r"
+        SELECT id, project_id, benchmark_name, env_profile, container, server, status,
+               progress_current, progress_total,
+               (julianday('now') - julianday(started_at)) * 86400.0 AS elapsed,
+               (julianday(not_before) - julianday('now')) * 86400.0 AS starts_in
+        FROM jobs
+        WHERE status IN ('running', 'pending')
+        ORDER BY CASE status WHEN 'running' THEN 0 ELSE 1 END,
+                 queue_position ASC NULLS LAST, id ASC
+        ",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Erreur chargement jobs à estimer: {e}"))?;
+
+    Ok(rows
+        .iter()
+        .map(|r| {
+            let running = r.get::<String, _>("status") == "running";
+            let current: Option<i64> = r.get("progress_current");
+            let total: Option<i64> = r.get("progress_total");
+            EtaJob {
+                id: r.get("id"),
+                project_id: r.get("project_id"),
+                benchmark_name: r.get("benchmark_name"),
+                params: RunParams {
+                    env_profile: r.get("env_profile"),
+                    container: r.get("container"),
+                },
+                server: r.get("server"),
+                elapsed: running.then(|| r.get::<Option<f64>, _>("elapsed").unwrap_or(0.0)),
+                progress: current
+                    .zip(total)
+                    .and_then(|(c, t)| Some((u32::try_from(c).ok()?, u32::try_from(t).ok()?))),
+                starts_in: r
+                    .get::<Option<f64>, _>("starts_in")
+                    .filter(|seconds| *seconds > 0.0),
+            }
+        })
+        .collect())
+}
+
+/// Durées des exécutions réussies des benchmarks en file (plus récentes d'abord)
+pub async fn load_runtime_samples(pool: &SqlitePool) -> Result<Vec<RuntimeSample>, String> {
+    let rows = sqlx::query(&format!(
+        r"
+        SELECT j.project_id, j.benchmark_name, j.env_profile, j.container, j.server,
+               {duration} AS duration
+        FROM jobs j
+        WHERE j.status = 'completed'
+          AND j.started_at IS NOT NULL AND j.finished_at IS NOT NULL
+          AND {duration} > 0
+          AND j.benchmark_name IN (
+              SELECT benchmark_name FROM jobs WHERE status IN ('running', 'pending')
+          )
+        ORDER BY j.finished_at DESC
+        ",
+        duration = history::DURATION_SQL
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Erreur chargement durées: {e}"))?;
+
+    Ok(rows
+        .iter()
+        .map(|r| RuntimeSample {
+            project_id: r.get("project_id"),
+            benchmark_name: r.get("benchmark_name"),
+            params: RunParams {
+                env_profile: r.get("env_profile"),
+                container: r.get("container"),
+            },
+            server: r.get("server"),
+            seconds: r.get("duration"),
+        })
+        .collect())
+}
+
+// =============================================================================
+// Full-text search
+// =============================================================================
+
+/// (Ré)indexe un job pour la recherche plein texte (`log` = log complet si disponible)
+pub async fn index_job_search(
+    pool: &SqlitePool,
+    job_id: i64,
+    log: Option<&str>,
+) -> Result<(), String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to begin transaction: {e}"))?;
+
+    sqlx::query("DELETE FROM jobs_fts WHERE rowid = ?")
+        .bind(job_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Erreur indexation job: {e}"))?;
+
+    sqlx::query(&format!(
+        "INSERT INTO jobs_fts(rowid, benchmark_name, error_message, params, log)
+         {SEARCH_ROW_SELECT} WHERE j.id = ?"
+    ))
+    .bind(log)
+    .bind(job_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Erreur indexation job: {e}"))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {e}"))?;
+
+    Ok(())
+}
+
+/// Convertit une saisie libre en requête FTS5 : chaque mot ou `"phrase"` devient une
+/// phrase littérale (tous requis), un `*` final garde la recherche par préfixe
+///
+/// Retourne `None` si la saisie ne contient aucun terme.
+pub fn to_fts_query(input: &str) -> Option<String> {
+    let mut terms = Vec::new();
+    for (i, part) in input.split('"').enumerate() {
+        if i % 2 == 1 {
+            // Entre guillemets : phrase exacte
+            if !part.trim().is_empty() {
+                terms.push(format!("\"{}\"", part.trim()));
+            }
+            continue;
+        }
+        for word in part.split_whitespace() {
+            let (word, prefix) = word
+                .strip_suffix('*')
+                .map_or((word, false), |stem| (stem, true));
+            if word.is_empty() {
+                continue;
+            }
+            terms.push(format!("\"{word}\"{}", if prefix { "*" } else { "" }));
+        }
+    }
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Recherche plein texte dans les jobs terminés (nom, erreur, paramètres, log)
+///
+/// Résultats triés par pertinence, avec un extrait surligné.
+pub async fn search_jobs(
+    pool: &SqlitePool,
+    query: &str,
+    filters: &JobSearchFilters,
+) -> Result<Vec<JobSearchHit>, String> {
+    let Some(fts_query) = to_fts_query(query) else {
+        return Ok(Vec::new());
+    };
+    let status = filters.status.as_ref().map(status_to_str);
+    let limit = filters.limit.unwrap_or(50).clamp(1, 500);
+
+    // Nom du benchmark et erreur pèsent plus qu'une occurrence dans le log
+    let rows = sqlx::query(
+        r"
+        SELECT j.id, j.project_id, j.benchmark_name, j.status, j.created_at, j.started_at,
+               j.finished_at, j.progress_current, j.progress_total, j.results_path,
+               j.error_message, j.log_content, j.env_profile, j.peak_rss_kb, j.cpu_seconds, j.notes, j.server,
+               snippet(jobs_fts, -1, '<mark>', '</mark>', '…', 24) AS snippet,
+               bm25(jobs_fts, 10.0, 5.0, 5.0, 1.0) AS rank
+        FROM jobs_fts
+        JOIN jobs j ON j.id = jobs_fts.rowid
+        WHERE jobs_fts MATCH ?
+          AND (? IS NULL OR j.status = ?)
+          AND (? IS NULL OR j.project_id = ?)
+          AND (? IS NULL OR date(j.created_at) >= date(?))
+          AND (? IS NULL OR date(j.created_at) <= date(?))
+        ORDER BY rank
+        LIMIT ?
+        ",
+    )
+    .bind(&fts_query)
+    .bind(status)
+    .bind(status)
+    .bind(filters.project_id)
+    .bind(filters.project_id)
+    .bind(&filters.from)
+    .bind(&filters.from)
+    .bind(&filters.to)
+    .bind(&filters.to)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Erreur recherche: {e}"))?;
+
+    let matches: Vec<(String, f64)> = rows
+        .iter()
+        .map(|row| (row.get("snippet"), row.get("rank")))
+        .collect();
+
+    let mut jobs = rows_to_jobs(rows);
+    attach_tags(pool, &mut jobs).await?;
+    Ok(jobs
+        .into_iter()
+        .zip(matches)
+        .map(|(job, (snippet, rank))| JobSearchHit { job, snippet, rank })
+        .collect())
+}
+
+/// Supprime un job en attente
+pub async fn delete_pending_job(pool: &SqlitePool, job_id: i64) -> Result<(), String> {
+    sqlx::query("DELETE FROM jobs WHERE id = ? AND status = 'pending'")
+        .bind(job_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Erreur suppression job: {e}"))?;
+
+    Ok(())
+}
+
+/// Charge les jobs en attente dont l'heure de départ (`not_before`) est passée
+pub async fn load_pending_jobs(pool: &SqlitePool) -> Result<Vec<Job>, String> {
+    let rows = sqlx::query(
+        r"
+        SELECT id, project_id, benchmark_name, status, created_at, started_at, finished_at,
+               progress_current, progress_total, results_path, error_message, log_content,
+               env_profile, peak_rss_kb, cpu_seconds, notes, server
+        FROM jobs
+        WHERE status = 'pending' AND (not_before IS NULL OR not_before <= ?)
+        ORDER BY created_at ASC
+        ",
+    )
+    .bind(job::start_time_now())
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Erreur chargement jobs: {e}"))?;
+
+    let mut jobs = rows_to_jobs(rows);
+    attach_tags(pool, &mut jobs).await?;
+    Ok(jobs)
 }
 
 /// Charge le job en cours
@@ -610,7 +1844,8 @@ pub async fn load_history(pool: &SqlitePool, limit: i32) -> Result<Vec<Job>, Str
     let rows = sqlx::query(
         r"
         SELECT id, project_id, benchmark_name, status, created_at, started_at, finished_at,
-               progress_current, progress_total, results_path, error_message, log_content
+               progress_current, progress_total, results_path, error_message, log_content,
+               env_profile, peak_rss_kb, cpu_seconds, notes, server
         FROM jobs
         WHERE status IN ('completed', 'failed', 'killed')
         ORDER BY finished_at DESC
@@ -622,14 +1857,237 @@ pub async fn load_history(pool: &SqlitePool, limit: i32) -> Result<Vec<Job>, Str
     .await
     .map_err(|e| format!("Erreur chargement historique: {e}"))?;
 
-    Ok(rows_to_jobs(rows))
+    let mut jobs = rows_to_jobs(rows);
+    attach_tags(pool, &mut jobs).await?;
+    Ok(jobs)
+}
+
+type SqliteQuery<'q> = sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>>;
+
+fn bind_args<'q>(mut query: SqliteQuery<'q>, args: &'q [SqlArg]) -> SqliteQuery<'q> {
+    for arg in args {
+        query = match arg {
+            SqlArg::Int(v) => query.bind(*v),
+            SqlArg::Text(v) => query.bind(v.as_str()),
+        };
+    }
+    query
+}
+
+/// Historique filtré, trié et paginé par curseur (voir `history`)
+pub async fn query_history(pool: &SqlitePool, query: &HistoryQuery) -> Result<HistoryPage, String> {
+    let (where_clause, filter_args) = query.filters.where_clause()?;
+
+    let total: i64 = bind_args(
+        sqlx::query(&format!(
+            "SELECT COUNT(*) AS total FROM jobs j {where_clause}"
+        )),
+        &filter_args,
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Erreur comptage historique: {e}"))?
+    .get("total");
+
+    let mut conditions = where_clause;
+    let mut args = filter_args;
+    if let Some(ref cursor) = query.cursor {
+        let (condition, cursor_args) =
+            Cursor::decode(cursor, query.sort)?.condition(query.ascending);
+        conditions.push_str(" AND ");
+        conditions.push_str(&condition);
+        args.extend(cursor_args);
+    }
+
+    // Une ligne de plus que la page : indique s'il reste des jobs
+    let page_size = query.page_size();
+    args.push(SqlArg::Int(i64::from(page_size) + 1));
+    let direction = if query.ascending { "ASC" } else { "DESC" };
+    let sql = format!(
+        r"
+        SELECT j.id, j.project_id, j.benchmark_name, j.status, j.created_at, j.started_at,
+               j.finished_at, j.progress_current, j.progress_total, j.results_path,
+               j.error_message, j.log_content, j.env_profile, j.peak_rss_kb, j.cpu_seconds, j.notes, j.server,
+               {} AS sort_key
+        FROM jobs j
+        {conditions}
+        ORDER BY sort_key {direction}, j.id {direction}
+        LIMIT ?
+        ",
+        query.sort.sql()
+    );
+
+    let mut rows = bind_args(sqlx::query(&sql), &args)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Erreur chargement historique: {e}"))?;
+
+    let has_more = rows.len() > page_size as usize;
+    rows.truncate(page_size as usize);
+    let next_cursor = if has_more {
+        rows.last().map(|row| {
+            let key = if query.sort.is_numeric() {
+                CursorKey::Number(row.get("sort_key"))
+            } else {
+                CursorKey::Text(row.get("sort_key"))
+            };
+            Cursor {
+                sort: query.sort,
+                key,
+                id: row.get("id"),
+            }
+            .encode()
+        })
+    } else {
+        None
+    };
+
+    let mut jobs = rows_to_jobs(rows);
+    attach_tags(pool, &mut jobs).await?;
+    Ok(HistoryPage {
+        jobs,
+        next_cursor,
+        total,
+    })
+}
+
+/// Statistiques de l'historique filtré : taux de succès, durée moyenne par benchmark,
+/// tendance quotidienne des échecs
+pub async fn history_stats(
+    pool: &SqlitePool,
+    filters: &HistoryFilters,
+) -> Result<HistoryStats, String> {
+    let (where_clause, args) = filters.where_clause()?;
+
+    let totals = bind_args(
+        sqlx::query(&format!(
+            r"
+            SELECT COUNT(*) AS total,
+                   COALESCE(SUM(j.status = 'completed'), 0) AS completed,
+                   COALESCE(SUM(j.status = 'failed'), 0) AS failed,
+                   COALESCE(SUM(j.status = 'killed'), 0) AS killed
+            FROM jobs j
+            {where_clause}
+            "
+        )),
+        &args,
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Erreur statistiques historique: {e}"))?;
+
+    let per_benchmark = bind_args(
+        sqlx::query(&format!(
+            r"
+            SELECT j.benchmark_name,
+                   COUNT(*) AS runs,
+                   SUM(j.status = 'completed') AS completed,
+                   SUM(j.status = 'failed') AS failed,
+                   AVG(CASE WHEN j.status = 'completed' THEN {} END) AS mean_duration
+            FROM jobs j
+            {where_clause}
+            GROUP BY j.benchmark_name
+            ORDER BY runs DESC, j.benchmark_name ASC
+            ",
+            history::DURATION_SQL
+        )),
+        &args,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Erreur statistiques par benchmark: {e}"))?
+    .iter()
+    .map(|row| {
+        let runs: i64 = row.get("runs");
+        let completed: i64 = row.get("completed");
+        BenchmarkStats {
+            benchmark_name: row.get("benchmark_name"),
+            runs,
+            completed,
+            failed: row.get("failed"),
+            success_rate: history::rate(completed, runs),
+            mean_duration_seconds: row.get("mean_duration"),
+        }
+    })
+    .collect();
+
+    let failure_trend = bind_args(
+        sqlx::query(&format!(
+            r"
+            SELECT date(j.created_at) AS day,
+                   COUNT(*) AS runs,
+                   SUM(j.status = 'failed') AS failed
+            FROM jobs j
+            {where_clause}
+            GROUP BY day
+            ORDER BY day ASC
+            "
+        )),
+        &args,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Erreur tendance des échecs: {e}"))?
+    .iter()
+    .map(|row| DailyFailures {
+        day: row.get::<Option<String>, _>("day").unwrap_or_default(),
+        runs: row.get("runs"),
+        failed: row.get("failed"),
+    })
+    .collect();
+
+    let total: i64 = totals.get("total");
+    let completed: i64 = totals.get("completed");
+    Ok(HistoryStats {
+        total,
+        completed,
+        failed: totals.get("failed"),
+        killed: totals.get("killed"),
+        success_rate: history::rate(completed, total),
+        per_benchmark,
+        failure_trend,
+    })
+}
+
+/// Charge des jobs par identifiants (ordre des identifiants conservé, inconnus ignorés)
+pub async fn load_jobs_by_ids(pool: &SqlitePool, ids: &[i64]) -> Result<Vec<Job>, String> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let placeholders = vec!["?"; ids.len()].join(", ");
+    let sql = format!(
+        r"
+        SELECT id, project_id, benchmark_name, status, created_at, started_at, finished_at,
+               progress_current, progress_total, results_path, error_message, log_content,
+               env_profile, peak_rss_kb, cpu_seconds, notes, server
+        FROM jobs
+        WHERE id IN ({placeholders})
+        "
+    );
+
+    let mut query = sqlx::query(&sql);
+    for id in ids {
+        query = query.bind(id);
+    }
+
+    let rows = query
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Erreur chargement jobs: {e}"))?;
+
+    let mut jobs = rows_to_jobs(rows);
+    attach_tags(pool, &mut jobs).await?;
+    jobs.sort_by_key(|job| ids.iter().position(|id| *id == job.id));
+    Ok(jobs)
 }
 
 async fn load_jobs_by_status(pool: &SqlitePool, status: &str) -> Result<Vec<Job>, String> {
     let rows = sqlx::query(
         r"
         SELECT id, project_id, benchmark_name, status, created_at, started_at, finished_at,
-               progress_current, progress_total, results_path, error_message, log_content
+               progress_current, progress_total, results_path, error_message, log_content,
+               env_profile, peak_rss_kb, cpu_seconds, notes, server
         FROM jobs
         WHERE status = ?
         ORDER BY created_at ASC
@@ -640,7 +2098,9 @@ async fn load_jobs_by_status(pool: &SqlitePool, status: &str) -> Result<Vec<Job>
     .await
     .map_err(|e| format!("Erreur chargement jobs: {e}"))?;
 
-    Ok(rows_to_jobs(rows))
+    let mut jobs = rows_to_jobs(rows);
+    attach_tags(pool, &mut jobs).await?;
+    Ok(jobs)
 }
 
 fn rows_to_jobs(rows: Vec<sqlx::sqlite::SqliteRow>) -> Vec<Job> {
@@ -659,6 +2119,11 @@ fn rows_to_jobs(rows: Vec<sqlx::sqlite::SqliteRow>) -> Vec<Job> {
         let results_path: Option<String> = row.get("results_path");
         let error_message: Option<String> = row.get("error_message");
         let log_content: Option<String> = row.get("log_content");
+        let env_profile: Option<String> = row.get("env_profile");
+        let peak_rss_kb: Option<i64> = row.get("peak_rss_kb");
+        let cpu_seconds: Option<f64> = row.get("cpu_seconds");
+        let notes: Option<String> = row.get("notes");
+        let server: Option<String> = row.get("server");
 
         // Safe: progress values are always non-negative (stored as positive counts in DB)
         // These casts convert i32 (DB type) back to u32 (app type) for progress display
@@ -682,6 +2147,13 @@ fn rows_to_jobs(rows: Vec<sqlx::sqlite::SqliteRow>) -> Vec<Job> {
             log_content: log_content.unwrap_or_default(),
             queue_position: None,
             queued_at: None,
+            env_profile,
+            peak_rss_kb,
+            cpu_seconds,
+            notes,
+            tags: Vec::new(),
+            server,
+            not_before: None,
         });
     }
 
@@ -705,8 +2177,14 @@ fn rows_to_jobs_with_queue(rows: Vec<sqlx::sqlite::SqliteRow>) -> Vec<Job> {
         let results_path: Option<String> = row.get("results_path");
         let error_message: Option<String> = row.get("error_message");
         let log_content: Option<String> = row.get("log_content");
+        let env_profile: Option<String> = row.get("env_profile");
+        let peak_rss_kb: Option<i64> = row.get("peak_rss_kb");
+        let cpu_seconds: Option<f64> = row.get("cpu_seconds");
+        let notes: Option<String> = row.get("notes");
+        let server: Option<String> = row.get("server");
         let queue_position: Option<i64> = row.get("queue_position");
         let queued_at: Option<String> = row.get("queued_at");
+        let not_before: Option<String> = row.get("not_before");
 
         // Safe: progress values are always non-negative (stored as positive counts in DB)
         // These casts convert i32 (DB type) back to u32 (app type) for progress display
@@ -730,6 +2208,13 @@ fn rows_to_jobs_with_queue(rows: Vec<sqlx::sqlite::SqliteRow>) -> Vec<Job> {
             log_content: log_content.unwrap_or_default(),
             queue_position,
             queued_at,
+            env_profile,
+            peak_rss_kb,
+            cpu_seconds,
+            notes,
+            tags: Vec::new(),
+            server,
+            not_before,
         });
     }
 
@@ -998,6 +2483,167 @@ pub async fn cancel_all_pending_jobs(pool: &SqlitePool) -> Result<u32, String> {
     u32::try_from(result.rows_affected()).map_err(|_| "Row count exceeds u32::MAX".to_string())
 }
 
+// =============================================================================
+// Results
+// =============================================================================
+
+/// Remplace les résultats d'un job (schéma + lignes) et enregistre leur provenance
+pub async fn replace_job_results(
+    pool: &SqlitePool,
+    job_id: i64,
+    table: &ResultTable,
+    results_path: &str,
+) -> Result<(), String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Erreur transaction résultats: {e}"))?;
+
+    sqlx::query("DELETE FROM results WHERE job_id = ?")
+        .bind(job_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Erreur suppression résultats: {e}"))?;
+    sqlx::query("DELETE FROM result_columns WHERE job_id = ?")
+        .bind(job_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Erreur suppression colonnes: {e}"))?;
+
+    for (position, column) in (0_i64..).zip(&table.columns) {
+        sqlx::query(
+            "INSERT INTO result_columns (job_id, position, name, kind) VALUES (?, ?, ?, ?)",
+        )
+        .bind(job_id)
+        .bind(position)
+        .bind(&column.name)
+        .bind(column.kind.as_str())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Erreur insertion colonne: {e}"))?;
+    }
+
+    for ((row_index, row), known) in (0_i64..).zip(&table.rows).zip(table.instance_results()) {
+        let data = serde_json::Value::Object(table.row_object(row)).to_string();
+        sqlx::query(
+            r"
+            INSERT INTO results (job_id, row_index, instance, status, runtime, gap, data)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ",
+        )
+        .bind(job_id)
+        .bind(row_index)
+        .bind(known.as_ref().map(|r| r.instance.clone()))
+        .bind(known.as_ref().and_then(|r| r.status.clone()))
+        .bind(known.as_ref().and_then(|r| r.runtime))
+        .bind(known.as_ref().and_then(|r| r.gap))
+        .bind(data)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Erreur insertion résultat: {e}"))?;
+    }
+
+    sqlx::query("UPDATE jobs SET results_path = ? WHERE id = ?")
+        .bind(results_path)
+        .bind(job_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Erreur mise à jour results_path: {e}"))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Erreur commit résultats: {e}"))
+}
+
+/// Le job a-t-il des résultats ingérés ?
+pub async fn has_job_results(pool: &SqlitePool, job_id: i64) -> Result<bool, String> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM result_columns WHERE job_id = ?")
+        .bind(job_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Erreur lecture résultats: {e}"))?;
+
+    Ok(count > 0)
+}
+
+/// Charge les colonnes connues (instance, statut, temps, gap) des résultats d'un job
+pub async fn load_instance_results(
+    pool: &SqlitePool,
+    job_id: i64,
+) -> Result<Vec<InstanceResult>, String> {
+    let rows = sqlx::query(
+        r"
+        SELECT instance, status, runtime, gap
+        FROM results
+        WHERE job_id = ? AND instance IS NOT NULL
+        ORDER BY row_index
+        ",
+    )
+    .bind(job_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Erreur lecture résultats: {e}"))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| InstanceResult {
+            instance: row.get("instance"),
+            status: row.get("status"),
+            runtime: row.get("runtime"),
+            gap: row.get("gap"),
+        })
+        .collect())
+}
+
+/// Charge la table complète des résultats d'un job (`None` si non ingérés)
+pub async fn load_job_results(
+    pool: &SqlitePool,
+    job_id: i64,
+) -> Result<Option<ResultTable>, String> {
+    let columns: Vec<ResultColumn> =
+        sqlx::query("SELECT name, kind FROM result_columns WHERE job_id = ? ORDER BY position")
+            .bind(job_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Erreur lecture colonnes: {e}"))?
+            .into_iter()
+            .map(|row| ResultColumn {
+                name: row.get("name"),
+                kind: ColumnKind::parse(row.get("kind")),
+            })
+            .collect();
+
+    if columns.is_empty() {
+        return Ok(None);
+    }
+
+    let data_rows = sqlx::query("SELECT data FROM results WHERE job_id = ? ORDER BY row_index")
+        .bind(job_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Erreur lecture résultats: {e}"))?;
+
+    let mut rows = Vec::with_capacity(data_rows.len());
+    for row in data_rows {
+        let data: String = row.get("data");
+        let object: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(&data).map_err(|e| format!("Résultat invalide: {e}"))?;
+        rows.push(
+            columns
+                .iter()
+                .map(|col| {
+                    object
+                        .get(&col.name)
+                        .cloned()
+                        .unwrap_or(serde_json::Value::Null)
+                })
+                .collect(),
+        );
+    }
+
+    Ok(Some(ResultTable { columns, rows }))
+}
+
 // =============================================================================
 // Tests (Story 1.3)
 // =============================================================================
@@ -1042,6 +2688,18 @@ mod tests {
                 log_content TEXT,
                 queue_position INTEGER,
                 queued_at TEXT,
+                env_profile TEXT,
+                code_version TEXT,
+                env_fingerprint TEXT,
+                peak_rss_kb INTEGER,
+                cpu_seconds REAL,
+                log_archive_path TEXT,
+                log_archive_size INTEGER,
+                log_archived_at TEXT,
+                notes TEXT,
+                server TEXT,
+                not_before TEXT,
+                container TEXT,
                 FOREIGN KEY (project_id) REFERENCES projects(id)
             )
             ",
@@ -1049,6 +2707,11 @@ mod tests {
         .execute(&pool)
         .await?;
 
+        let mut conn = pool.acquire().await?;
+        create_results_tables(&mut conn).await?;
+        migrate_tags_and_notes(&mut conn).await?;
+        drop(conn);
+
         // Create a test project
         sqlx::query(
             "INSERT INTO projects (name, python_version, created_at, updated_at) VALUES ('test', '3.12', '2026-01-11', '2026-01-11')"
@@ -1395,6 +3058,87 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_job_start_times() -> Result<(), Box<dyn std::error::Error>> {
+        let pool = init_test_db().await?;
+
+        let now_job = insert_job_with_queue(&pool, 1, "now.py", 1, "2026-01-11T10:00:00Z").await?;
+        let later = insert_job_with_queue(&pool, 1, "later.py", 2, "2026-01-11T10:01:00Z").await?;
+        let past = insert_job_with_queue(&pool, 1, "past.py", 3, "2026-01-11T10:02:00Z").await?;
+        set_job_not_before(&pool, later, Some("2999-01-01T02:00:00Z")).await?;
+        set_job_not_before(&pool, past, Some("2020-01-01T02:00:00Z")).await?;
+
+        // Jobs whose start time has not come are not started
+        let due: Vec<i64> = load_pending_jobs(&pool)
+            .await?
+            .iter()
+            .map(|j| j.id)
+            .collect();
+        assert_eq!(due, vec![now_job, past]);
+        assert_eq!(
+            next_job_start(&pool, &job::start_time_now())
+                .await?
+                .as_deref(),
+            Some("2999-01-01T02:00:00Z")
+        );
+
+        // Persisted and shown with the queue
+        let jobs = get_queued_jobs(&pool).await?;
+        assert_eq!(jobs[1].not_before.as_deref(), Some("2999-01-01T02:00:00Z"));
+
+        set_job_not_before(&pool, later, None).await?;
+        assert_eq!(load_pending_jobs(&pool).await?.len(), 3);
+        assert_eq!(next_job_start(&pool, &job::start_time_now()).await?, None);
+
+        // Only pending jobs can be delayed
+        update_job_status(&pool, now_job, &JobStatus::Running).await?;
+        assert!(
+            set_job_not_before(&pool, now_job, Some("2999-01-01T02:00:00Z"))
+                .await
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_eta_queries() -> Result<(), Box<dyn std::error::Error>> {
+        let pool = init_test_db().await?;
+
+        let done = insert_job_with_queue(&pool, 1, "bench.py", 1, "2026-01-11T10:00:00Z").await?;
+        let other = insert_job_with_queue(&pool, 1, "other.py", 2, "2026-01-11T10:00:00Z").await?;
+        let pending =
+            insert_job_with_queue(&pool, 1, "bench.py", 3, "2026-01-11T10:01:00Z").await?;
+        let running =
+            insert_job_with_queue(&pool, 1, "bench.py", 4, "2026-01-11T10:02:00Z").await?;
+        sqlx::query(
+            "UPDATE jobs SET status = 'completed', started_at = '2026-01-11T10:00:00+00:00',
+                 finished_at = '2026-01-11T10:02:30.5+00:00' WHERE id IN (?, ?)",
+        )
+        .bind(done)
+        .bind(other)
+        .execute(&pool)
+        .await?;
+        update_job_status(&pool, running, &JobStatus::Running).await?;
+        update_job_progress(&pool, running, 2, 8).await?;
+        set_job_not_before(&pool, pending, Some("2999-01-01T00:00:00Z")).await?;
+
+        // Only runs of benchmarks still queued
+        let samples = load_runtime_samples(&pool).await?;
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].benchmark_name, "bench.py");
+        assert!((samples[0].seconds - 150.5).abs() < 0.01);
+
+        // Running first, then pending in queue order
+        let jobs = load_eta_jobs(&pool).await?;
+        let ids: Vec<i64> = jobs.iter().map(|j| j.id).collect();
+        assert_eq!(ids, vec![running, pending]);
+        assert_eq!(jobs[0].progress, Some((2, 8)));
+        assert!(jobs[0].elapsed.is_some_and(|elapsed| elapsed < 60.0));
+        assert_eq!(jobs[1].elapsed, None);
+        assert!(jobs[1].starts_in.is_some_and(|seconds| seconds > 0.0));
+        Ok(())
+    }
+
     // =============================================================================
     // Story 1.5 - Duplicate Detection Tests
     // =============================================================================
@@ -1533,4 +3277,372 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_replace_and_load_job_results() -> Result<(), Box<dyn std::error::Error>> {
+        let pool = init_test_db().await?;
+        let job_id =
+            insert_job_with_queue(&pool, 1, "benchmark_01.py", 1, "2026-01-11T10:00:00Z").await?;
+
+        assert!(!has_job_results(&pool, job_id).await?);
+        assert!(load_job_results(&pool, job_id).await?.is_none());
+
+        let table = crate::results::parse_csv(
+            "instance,status,runtime,nodes\na,optimal,1.5,10\nb,time_limit,60,\n",
+        )?;
+        replace_job_results(&pool, job_id, &table, "~/results.csv").await?;
+        // Re-ingesting replaces previous rows
+        replace_job_results(&pool, job_id, &table, "~/results.csv").await?;
+
+        assert!(has_job_results(&pool, job_id).await?);
+        assert_eq!(load_job_results(&pool, job_id).await?, Some(table));
+
+        let results = load_instance_results(&pool, job_id).await?;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1].status.as_deref(), Some("time_limit"));
+
+        // Full rows remain queryable through JSON
+        let nodes: Option<i64> = sqlx::query_scalar(
+            "SELECT json_extract(data, '$.nodes') FROM results WHERE job_id = ? AND instance = 'a'",
+        )
+        .bind(job_id)
+        .fetch_one(&pool)
+        .await?;
+        assert_eq!(nodes, Some(10));
+
+        let path: Option<String> = sqlx::query_scalar("SELECT results_path FROM jobs WHERE id = ?")
+            .bind(job_id)
+            .fetch_one(&pool)
+            .await?;
+        assert_eq!(path.as_deref(), Some("~/results.csv"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_fts_query() {
+        assert_eq!(
+            to_fts_query("Model is infeasible").as_deref(),
+            Some(r#""Model" "is" "infeasible""#)
+        );
+        assert_eq!(
+            to_fts_query(r#""Model is infeasible" N12 bench*"#).as_deref(),
+            Some(r#""Model is infeasible" "N12" "bench"*"#)
+        );
+        // FTS5 operators are taken literally
+        assert_eq!(
+            to_fts_query("gap:0.5 NOT (a)").as_deref(),
+            Some(r#""gap:0.5" "NOT" "(a)""#)
+        );
+        assert_eq!(to_fts_query("  \"\" * "), None);
+    }
+
+    #[tokio::test]
+    async fn test_search_jobs() -> Result<(), Box<dyn std::error::Error>> {
+        let pool = init_test_db().await?;
+
+        // Finished before the index existed: indexed from its stored log when created
+        let old = insert_job(&pool, 1, "knapsack.py").await?;
+        update_job_logs(&pool, old, "Solving N12\nModel is infeasible\n").await?;
+        update_job_error(&pool, old, "Model is infeasible").await?;
+        update_job_status(&pool, old, &JobStatus::Failed).await?;
+        let pending = insert_job(&pool, 1, "infeasible_pending.py").await?;
+        create_search_index(&mut *pool.acquire().await?).await?;
+
+        let new = insert_job(&pool, 1, "tsp.py").await?;
+        update_job_logs(&pool, new, "tail only").await?;
+        update_job_status(&pool, new, &JobStatus::Completed).await?;
+        let table = crate::results::parse_csv("instance,status\nN12,optimal\nN13,optimal\n")?;
+        replace_job_results(&pool, new, &table, "~/results.csv").await?;
+        index_job_search(&pool, new, Some("Optimal solution found")).await?;
+        // Re-indexing replaces the previous row
+        index_job_search(&pool, new, Some("Optimal solution found\nDone")).await?;
+
+        let hits = search_jobs(&pool, "infeasible", &JobSearchFilters::default()).await?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].job.id, old);
+        assert!(hits[0].snippet.contains("<mark>infeasible</mark>"));
+        assert!(!hits.iter().any(|hit| hit.job.id == pending));
+
+        // Instance names are searchable as parameters, the full log replaces the tail
+        let hits = search_jobs(&pool, "N12", &JobSearchFilters::default()).await?;
+        assert_eq!(hits.len(), 2);
+        assert!(search_jobs(&pool, "tail", &JobSearchFilters::default())
+            .await?
+            .is_empty());
+        assert_eq!(
+            search_jobs(&pool, "optim*", &JobSearchFilters::default())
+                .await?
+                .len(),
+            1
+        );
+
+        let completed = JobSearchFilters {
+            status: Some(JobStatus::Completed),
+            ..JobSearchFilters::default()
+        };
+        let hits = search_jobs(&pool, "N12", &completed).await?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].job.id, new);
+
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        let in_range = JobSearchFilters {
+            from: Some(today.clone()),
+            to: Some(today),
+            project_id: Some(1),
+            ..JobSearchFilters::default()
+        };
+        assert_eq!(search_jobs(&pool, "N12", &in_range).await?.len(), 2);
+        let before = JobSearchFilters {
+            to: Some("2020-01-01".to_string()),
+            ..JobSearchFilters::default()
+        };
+        assert!(search_jobs(&pool, "N12", &before).await?.is_empty());
+        assert!(search_jobs(&pool, "  ", &JobSearchFilters::default())
+            .await?
+            .is_empty());
+
+        Ok(())
+    }
+
+    /// Inserts a finished job with explicit timestamps (duration = `seconds`)
+    async fn insert_finished_job(
+        pool: &SqlitePool,
+        benchmark: &str,
+        status: &str,
+        day: &str,
+        seconds: i64,
+    ) -> Result<i64, Box<dyn std::error::Error>> {
+        let id = sqlx::query(
+            "INSERT INTO jobs (project_id, benchmark_name, status, created_at, started_at, finished_at)
+             VALUES (1, ?, ?, ?, ?, datetime(?, '+' || ? || ' seconds'))",
+        )
+        .bind(benchmark)
+        .bind(status)
+        .bind(format!("{day}T10:00:00+00:00"))
+        .bind(format!("{day} 10:00:00"))
+        .bind(format!("{day} 10:00:00"))
+        .bind(seconds)
+        .execute(pool)
+        .await?
+        .last_insert_rowid();
+        Ok(id)
+    }
+
+    #[tokio::test]
+    async fn test_query_history_pagination() -> Result<(), Box<dyn std::error::Error>> {
+        let pool = init_test_db().await?;
+        create_jobs_indexes(&mut *pool.acquire().await?).await?;
+        for (i, seconds) in [30, 10, 50, 10, 40].into_iter().enumerate() {
+            insert_finished_job(
+                &pool,
+                "a.py",
+                "completed",
+                &format!("2026-01-0{}", i + 1),
+                seconds,
+            )
+            .await?;
+        }
+        insert_job(&pool, 1, "pending.py").await?;
+
+        // Longest first, ties broken by id, 2 per page
+        let mut query = HistoryQuery {
+            sort: history::HistorySort::Duration,
+            limit: Some(2),
+            ..HistoryQuery::default()
+        };
+        let mut durations = Vec::new();
+        let mut pages = 0;
+        loop {
+            let page = query_history(&pool, &query).await?;
+            assert_eq!(page.total, 5);
+            durations.extend(page.jobs.iter().map(|job| job.id));
+            pages += 1;
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        assert_eq!(pages, 3);
+        assert_eq!(durations, vec![3, 5, 1, 4, 2]);
+
+        // Filters: date range and ascending creation order
+        let page = query_history(
+            &pool,
+            &HistoryQuery {
+                filters: HistoryFilters {
+                    from: Some("2026-01-02".to_string()),
+                    to: Some("2026-01-03".to_string()),
+                    ..HistoryFilters::default()
+                },
+                sort: history::HistorySort::CreatedAt,
+                ascending: true,
+                ..HistoryQuery::default()
+            },
+        )
+        .await?;
+        assert_eq!(
+            page.jobs.iter().map(|j| j.id).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(page.next_cursor, None);
+
+        // A cursor from another sort order is rejected
+        query.sort = history::HistorySort::Benchmark;
+        assert!(query_history(&pool, &query).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_history_param_filter_and_stats() -> Result<(), Box<dyn std::error::Error>> {
+        let pool = init_test_db().await?;
+        let a1 = insert_finished_job(&pool, "a.py", "completed", "2026-01-01", 10).await?;
+        insert_finished_job(&pool, "a.py", "completed", "2026-01-01", 30).await?;
+        insert_finished_job(&pool, "a.py", "failed", "2026-01-02", 5).await?;
+        insert_finished_job(&pool, "b.py", "killed", "2026-01-02", 5).await?;
+
+        let table = crate::results::parse_csv("instance,seed\nN12,3\nN13,4\n")?;
+        replace_job_results(&pool, a1, &table, "~/results.csv").await?;
+        let filters = HistoryFilters {
+            params: vec![history::ParamFilter {
+                name: "seed".to_string(),
+                value: "3".to_string(),
+            }],
+            ..HistoryFilters::default()
+        };
+        let page = query_history(
+            &pool,
+            &HistoryQuery {
+                filters,
+                ..HistoryQuery::default()
+            },
+        )
+        .await?;
+        assert_eq!(page.jobs.iter().map(|j| j.id).collect::<Vec<_>>(), vec![a1]);
+
+        let stats = history_stats(&pool, &HistoryFilters::default()).await?;
+        assert_eq!(
+            (stats.total, stats.completed, stats.failed, stats.killed),
+            (4, 2, 1, 1)
+        );
+        assert!((stats.success_rate - 0.5).abs() < 1e-9);
+
+        let a = &stats.per_benchmark[0];
+        assert_eq!(
+            (a.benchmark_name.as_str(), a.runs, a.failed),
+            ("a.py", 3, 1)
+        );
+        let mean = a.mean_duration_seconds.ok_or("missing mean duration")?;
+        assert!((mean - 20.0).abs() < 0.01, "mean duration {mean}");
+        assert_eq!(stats.per_benchmark[1].mean_duration_seconds, None);
+
+        let trend: Vec<(String, i64, i64)> = stats
+            .failure_trend
+            .into_iter()
+            .map(|d| (d.day, d.runs, d.failed))
+            .collect();
+        assert_eq!(
+            trend,
+            vec![
+                ("2026-01-01".to_string(), 2, 0),
+                ("2026-01-02".to_string(), 2, 1)
+            ]
+        );
+
+        let empty = history_stats(
+            &pool,
+            &HistoryFilters {
+                benchmark: Some("missing.py".to_string()),
+                ..HistoryFilters::default()
+            },
+        )
+        .await?;
+        assert_eq!(empty.total, 0);
+        assert!(empty.per_benchmark.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_tags() -> Result<(), String> {
+        let tags = normalize_tags(&[
+            " baseline ".to_string(),
+            String::new(),
+            "Baseline".to_string(),
+            "new-cuts".to_string(),
+        ])?;
+        assert_eq!(tags, vec!["baseline", "new-cuts"]);
+        assert!(normalize_tags(&["a\nb".to_string()]).is_err());
+        assert!(normalize_tags(&["x".repeat(65)]).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_job_tags_and_notes() -> Result<(), Box<dyn std::error::Error>> {
+        let pool = init_test_db().await?;
+        // Idempotent
+        migrate_tags_and_notes(&mut *pool.acquire().await?).await?;
+
+        let a = insert_finished_job(&pool, "a.py", "completed", "2026-01-01", 10).await?;
+        let b = insert_finished_job(&pool, "b.py", "completed", "2026-01-02", 10).await?;
+        let c = insert_finished_job(&pool, "c.py", "failed", "2026-01-03", 10).await?;
+
+        set_job_tags(&pool, a, &["baseline".to_string(), "paper-v2".to_string()]).await?;
+        let mut tx = pool.begin().await?;
+        add_job_tags(
+            &mut tx,
+            b,
+            &["new-cuts".to_string(), "PAPER-V2".to_string()],
+        )
+        .await?;
+        tx.commit().await?;
+        set_job_tags(&pool, c, &["baseline".to_string()]).await?;
+        set_job_notes(&pool, a, Some("  reference run  ")).await?;
+
+        let jobs = load_jobs_by_ids(&pool, &[a, b]).await?;
+        assert_eq!(jobs[0].tags, vec!["baseline", "paper-v2"]);
+        assert_eq!(jobs[0].notes.as_deref(), Some("reference run"));
+        assert_eq!(jobs[1].tags, vec!["new-cuts", "paper-v2"]);
+
+        let tags: Vec<(String, i64)> = list_tags(&pool)
+            .await?
+            .into_iter()
+            .map(|t| (t.name, t.job_count))
+            .collect();
+        assert_eq!(
+            tags,
+            vec![
+                ("baseline".to_string(), 2),
+                ("new-cuts".to_string(), 1),
+                ("paper-v2".to_string(), 2)
+            ]
+        );
+
+        // History filter: all tags required
+        let filters = HistoryFilters {
+            tags: vec!["paper-v2".to_string(), "baseline".to_string()],
+            ..HistoryFilters::default()
+        };
+        let page = query_history(
+            &pool,
+            &HistoryQuery {
+                filters,
+                ..HistoryQuery::default()
+            },
+        )
+        .await?;
+        assert_eq!(page.jobs.iter().map(|j| j.id).collect::<Vec<_>>(), vec![a]);
+
+        // Reports: completed jobs with any of the tags
+        let ids =
+            job_ids_with_any_tag(&pool, &["baseline".to_string(), "new-cuts".to_string()]).await?;
+        assert_eq!(ids, vec![a, b]);
+
+        // Replacing tags drops the orphaned ones, clearing notes
+        set_job_tags(&pool, b, &[]).await?;
+        set_job_notes(&pool, a, Some("  ")).await?;
+        assert!(!list_tags(&pool).await?.iter().any(|t| t.name == "new-cuts"));
+        assert_eq!(load_jobs_by_ids(&pool, &[a]).await?[0].notes, None);
+        assert!(set_job_notes(&pool, 999, None).await.is_err());
+        Ok(())
+    }
 }
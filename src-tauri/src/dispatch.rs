@@ -0,0 +1,180 @@
+//! Automatic placement of queued jobs across servers
+//!
+//! A job queued without a server (with `[dispatch] enabled`) is placed when it
+//! starts: servers that are reachable, allowed by the job's benchmark, below
+//! their `max_concurrent` and below `max_load_per_core` are ranked by how busy
+//! they are (queue slots used, then load average per core), and the queue tries
+//! them in that order until one passes the license preflight.
+
+use std::collections::BTreeMap;
+
+use crate::config::AppConfig;
+use crate::ssh::SshExecutor;
+
+/// Load of a server, sampled over SSH
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ServerLoad {
+    /// Online cores (`nproc`)
+    pub cores: u32,
+    /// 1-minute load average
+    pub load_1m: f64,
+}
+
+impl ServerLoad {
+    /// Load average per core (1.0 = every core busy)
+    pub fn per_core(&self) -> f64 {
+        self.load_1m / f64::from(self.cores.max(1))
+    }
+}
+
+/// State of a server when placing a job
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub name: String,
+    /// Jobs the queue is running on it
+    pub running: u32,
+    pub max_concurrent: u32,
+    /// `None` when the server is unreachable (not connected or sampling failed)
+    pub load: Option<ServerLoad>,
+}
+
+impl Candidate {
+    /// Whether the server has a free queue slot
+    pub const fn has_slot(&self) -> bool {
+        self.running < self.max_concurrent
+    }
+}
+
+/// Parse `nproc; cat /proc/loadavg` output (`"8\n0.52 0.40 0.33 1/123 4567"`)
+pub fn parse_load(output: &str) -> Result<ServerLoad, String> {
+    let mut lines = output.lines().map(str::trim).filter(|l| !l.is_empty());
+
+    let cores = lines
+        .next()
+        .and_then(|l| l.parse().ok())
+        .ok_or_else(|| format!("Unexpected nproc output: '{}'", output.trim()))?;
+    let load_1m = lines
+        .next()
+        .and_then(|l| l.split_whitespace().next())
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| format!("Unexpected /proc/loadavg output: '{}'", output.trim()))?;
+
+    Ok(ServerLoad { cores, load_1m })
+}
+
+/// Sample the load of a server
+///
+/// # Errors
+/// Returns error if the command fails (server unreachable) or its output is unexpected
+pub async fn sample_load(executor: &SshExecutor) -> Result<ServerLoad, String> {
+    let output = executor
+        .execute("nproc; cat /proc/loadavg")
+        .await
+        .map_err(|e| e.to_string())?;
+    parse_load(&output)
+}
+
+/// Servers a job may be placed on, best first
+///
+/// `allowed` restricts the servers (empty = any). Unreachable, full and
+/// overloaded servers are left out.
+pub fn rank(candidates: &[Candidate], allowed: &[String], max_load_per_core: f64) -> Vec<String> {
+    let mut ranked: Vec<(&Candidate, ServerLoad)> = candidates
+        .iter()
+        .filter(|c| allowed.is_empty() || allowed.contains(&c.name))
+        .filter(|c| c.has_slot())
+        .filter_map(|c| c.load.map(|load| (c, load)))
+        .filter(|(_, load)| load.per_core() <= max_load_per_core)
+        .collect();
+
+    ranked.sort_by(|(a, a_load), (b, b_load)| {
+        let used = |c: &Candidate| f64::from(c.running) / f64::from(c.max_concurrent.max(1));
+        used(a)
+            .total_cmp(&used(b))
+            .then(a_load.per_core().total_cmp(&b_load.per_core()))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    ranked.into_iter().map(|(c, _)| c.name.clone()).collect()
+}
+
+/// Queue slots used per server, from the jobs the queue is running
+pub fn running_per_server(running: &BTreeMap<i64, String>) -> BTreeMap<&str, u32> {
+    let mut counts = BTreeMap::new();
+    for server in running.values() {
+        *counts.entry(server.as_str()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Check a benchmark's server constraint against the configured servers
+///
+/// # Errors
+/// Returns error naming the first unknown server
+pub fn validate_servers(config: &AppConfig, servers: &[String]) -> Result<(), String> {
+    let known = config.server_names();
+    servers
+        .iter()
+        .find(|s| !known.contains(s))
+        .map_or(Ok(()), |s| Err(format!("Unknown server: {s}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(name: &str, running: u32, max: u32, load: Option<(u32, f64)>) -> Candidate {
+        Candidate {
+            name: name.to_string(),
+            running,
+            max_concurrent: max,
+            load: load.map(|(cores, load_1m)| ServerLoad { cores, load_1m }),
+        }
+    }
+
+    #[test]
+    fn test_parse_load() -> Result<(), String> {
+        let load = parse_load("16\n4.00 3.10 2.50 3/612 88211\n")?;
+        assert_eq!(load.cores, 16);
+        assert!((load.per_core() - 0.25).abs() < f64::EPSILON);
+
+        assert!(parse_load("").is_err());
+        assert!(parse_load("bash: nproc: command not found\n").is_err());
+        assert!(parse_load("8\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rank_prefers_free_and_idle_servers() {
+        let candidates = vec![
+            candidate("default", 1, 1, Some((8, 0.5))), // full
+            candidate("gpu1", 1, 4, Some((32, 8.0))),   // 1/4 used
+            candidate("gpu2", 0, 2, Some((32, 16.0))),  // idle slots, load 0.5/core
+            candidate("cpu1", 0, 2, Some((8, 2.0))),    // idle slots, load 0.25/core
+            candidate("down", 0, 2, None),              // unreachable
+            candidate("hot", 0, 2, Some((4, 6.0))),     // overloaded
+        ];
+
+        assert_eq!(rank(&candidates, &[], 1.0), vec!["cpu1", "gpu2", "gpu1"]);
+        // Benchmark constraint
+        assert_eq!(
+            rank(&candidates, &["gpu1".to_string(), "down".to_string()], 1.0),
+            vec!["gpu1"]
+        );
+        // Stricter load threshold
+        assert_eq!(rank(&candidates, &[], 0.3), vec!["cpu1", "gpu1"]);
+    }
+
+    #[test]
+    fn test_running_per_server() {
+        let running = BTreeMap::from([
+            (1, "default".to_string()),
+            (2, "gpu1".to_string()),
+            (3, "gpu1".to_string()),
+        ]);
+        let counts = running_per_server(&running);
+        assert_eq!(counts.get("gpu1"), Some(&2));
+        assert_eq!(counts.get("default"), Some(&1));
+        assert_eq!(counts.get("cpu1"), None);
+    }
+}
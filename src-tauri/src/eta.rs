@@ -0,0 +1,386 @@
+//! Completion time estimates for the queue
+//!
+//! A job's expected runtime is the median of the latest successful runs of its
+//! benchmark: runs with the same parameters (environment profile and container)
+//! on the same server if there are at least `MIN_SAMPLES` of them, else with the
+//! same parameters on any server, else any run of the benchmark. When no level
+//! has enough runs, the most specific one with a run is used.
+//!
+//! A running job's remaining time blends the rate of its `[x/y]` progress with
+//! its expected runtime minus the time already elapsed, trusting the progress
+//! rate more as the job advances.
+//!
+//! The queue is then played forward: each server runs `max_concurrent` jobs at a
+//! time, and pending jobs take, in queue order, the first slot freed on their
+//! server (on any server when the dispatcher places them), not before their start
+//! time. Execution windows and pauses are not accounted for.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+use crate::config::{AppConfig, DEFAULT_SERVER};
+use crate::db;
+
+/// Runs needed at a level before falling back to a less specific one
+const MIN_SAMPLES: usize = 3;
+/// Latest runs considered per level
+const MAX_SAMPLES: usize = 20;
+
+/// Parameters a job's runtime depends on besides its benchmark
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunParams {
+    pub env_profile: Option<String>,
+    pub container: Option<String>,
+}
+
+/// Duration of a completed run
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeSample {
+    pub project_id: Option<i64>,
+    pub benchmark_name: String,
+    pub params: RunParams,
+    pub server: Option<String>,
+    pub seconds: f64,
+}
+
+/// A pending or running job to estimate
+#[derive(Debug, Clone, PartialEq)]
+pub struct EtaJob {
+    pub id: i64,
+    pub project_id: Option<i64>,
+    pub benchmark_name: String,
+    pub params: RunParams,
+    pub server: Option<String>,
+    /// Seconds since the job started (`None` while pending)
+    pub elapsed: Option<f64>,
+    pub progress: Option<(u32, u32)>,
+    /// Seconds until the job's start time, if it is in the future
+    pub starts_in: Option<f64>,
+}
+
+/// Estimated completion of one job
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobEta {
+    pub job_id: i64,
+    /// Median runtime of similar runs, in seconds
+    pub expected_runtime: Option<f64>,
+    pub remaining_seconds: Option<f64>,
+    pub finishes_at: Option<String>,
+}
+
+/// Estimated completion of the whole queue
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueEta {
+    pub jobs: Vec<JobEta>,
+    /// Until the last job finishes (`None` if any job cannot be estimated)
+    pub remaining_seconds: Option<f64>,
+    pub finishes_at: Option<String>,
+    /// Jobs without an estimate (no history and no progress)
+    pub unestimated: usize,
+}
+
+/// Estimate the completion of the pending and running jobs
+pub async fn queue_eta(pool: &SqlitePool, config: &AppConfig) -> Result<QueueEta, String> {
+    let servers = config
+        .server_names()
+        .into_iter()
+        .filter_map(|name| {
+            let slots = config.for_server(&name).ok()?.ssh.max_concurrent;
+            Some((name, slots))
+        })
+        .collect();
+    let jobs = db::load_eta_jobs(pool).await?;
+    let samples = db::load_runtime_samples(pool).await?;
+
+    Ok(estimate(
+        &jobs,
+        &samples,
+        &servers,
+        config.dispatch.enabled,
+        Utc::now(),
+    ))
+}
+
+/// Play the queue forward (`jobs`: running first, then pending in queue order;
+/// `samples`: latest first; `servers`: slots per server)
+pub fn estimate(
+    jobs: &[EtaJob],
+    samples: &[RuntimeSample],
+    servers: &BTreeMap<String, u32>,
+    dispatch: bool,
+    now: DateTime<Utc>,
+) -> QueueEta {
+    // When each slot of each server frees up (`None` = unknown)
+    let mut slots: BTreeMap<String, Vec<Option<f64>>> = BTreeMap::new();
+    let mut etas = Vec::with_capacity(jobs.len());
+
+    for job in jobs.iter().filter(|job| job.elapsed.is_some()) {
+        let expected = expected_runtime(job, samples);
+        let remaining = job
+            .elapsed
+            .and_then(|elapsed| remaining_time(elapsed, job.progress, expected));
+        let server = job.server.as_deref().unwrap_or(DEFAULT_SERVER);
+        slots.entry(server.to_string()).or_default().push(remaining);
+        etas.push(job_eta(job.id, expected, remaining, now));
+    }
+    for (server, &max) in servers {
+        let free = slots.entry(server.clone()).or_default();
+        let max = usize::try_from(max.max(1)).unwrap_or(1);
+        if free.len() < max {
+            free.resize(max, Some(0.0));
+        }
+    }
+
+    for job in jobs.iter().filter(|job| job.elapsed.is_none()) {
+        let pinned = match job.server {
+            Some(ref server) => Some(server.as_str()),
+            None if !dispatch => Some(DEFAULT_SERVER),
+            None => None,
+        };
+        let expected = expected_runtime(job, samples);
+
+        // Earliest known free slot among the job's servers
+        let mut best: Option<(&str, usize, f64)> = None;
+        for (server, free) in &slots {
+            if pinned.is_some_and(|pinned| pinned != server) {
+                continue;
+            }
+            for (index, at) in free.iter().enumerate() {
+                if let Some(at) = *at {
+                    if best.is_none_or(|(_, _, best)| at < best) {
+                        best = Some((server, index, at));
+                    }
+                }
+            }
+        }
+
+        let remaining = best.and_then(|(_, _, free)| {
+            let start = free.max(job.starts_in.unwrap_or(0.0));
+            expected.map(|expected| start + expected)
+        });
+        if let Some((server, index, _)) = best {
+            let server = server.to_string();
+            if let Some(slot) = slots.get_mut(&server).and_then(|free| free.get_mut(index)) {
+                *slot = remaining;
+            }
+        }
+        etas.push(job_eta(job.id, expected, remaining, now));
+    }
+
+    let unestimated = etas
+        .iter()
+        .filter(|eta| eta.remaining_seconds.is_none())
+        .count();
+    let remaining_seconds = if unestimated == 0 {
+        Some(
+            etas.iter()
+                .filter_map(|eta| eta.remaining_seconds)
+                .fold(0.0, f64::max),
+        )
+    } else {
+        None
+    };
+
+    QueueEta {
+        jobs: etas,
+        remaining_seconds,
+        finishes_at: remaining_seconds.and_then(|seconds| finish_time(now, seconds)),
+        unestimated,
+    }
+}
+
+/// Median runtime of the runs most similar to `job`
+pub fn expected_runtime(job: &EtaJob, samples: &[RuntimeSample]) -> Option<f64> {
+    let same_server = |s: &RuntimeSample| job.server.is_some() && s.server == job.server;
+    let levels: [&dyn Fn(&RuntimeSample) -> bool; 3] = [
+        &|s| s.params == job.params && same_server(s),
+        &|s| s.params == job.params,
+        &|_| true,
+    ];
+
+    let mut fallback = None;
+    for level in levels {
+        let mut durations: Vec<f64> = samples
+            .iter()
+            .filter(|s| s.project_id == job.project_id && s.benchmark_name == job.benchmark_name)
+            .filter(|s| level(s))
+            .take(MAX_SAMPLES)
+            .map(|s| s.seconds)
+            .collect();
+        if durations.len() >= MIN_SAMPLES {
+            return median(&mut durations);
+        }
+        fallback = fallback.or_else(|| median(&mut durations));
+    }
+    fallback
+}
+
+/// Remaining time of a running job, from its progress and expected runtime
+pub fn remaining_time(
+    elapsed: f64,
+    progress: Option<(u32, u32)>,
+    expected: Option<f64>,
+) -> Option<f64> {
+    // An overrun job gives no hint from its history
+    let from_history = expected
+        .map(|expected| expected - elapsed)
+        .filter(|remaining| *remaining >= 0.0);
+    let Some((current, total)) =
+        progress.filter(|&(current, total)| current > 0 && current <= total)
+    else {
+        return from_history;
+    };
+
+    let done = f64::from(current) / f64::from(total);
+    let from_rate = elapsed * (1.0 - done) / done;
+    Some(from_history.map_or(from_rate, |from_history| {
+        done.mul_add(from_rate, (1.0 - done) * from_history)
+    }))
+}
+
+fn median(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        Some(f64::midpoint(*values.get(mid - 1)?, *values.get(mid)?))
+    } else {
+        values.get(mid).copied()
+    }
+}
+
+fn job_eta(
+    job_id: i64,
+    expected: Option<f64>,
+    remaining: Option<f64>,
+    now: DateTime<Utc>,
+) -> JobEta {
+    JobEta {
+        job_id,
+        expected_runtime: expected.map(f64::round),
+        remaining_seconds: remaining.map(f64::round),
+        finishes_at: remaining.and_then(|seconds| finish_time(now, seconds)),
+    }
+}
+
+fn finish_time(now: DateTime<Utc>, seconds: f64) -> Option<String> {
+    let duration = std::time::Duration::try_from_secs_f64(seconds).ok()?;
+    let at = now + chrono::Duration::from_std(duration).ok()?;
+    Some(at.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(server: &str, env_profile: Option<&str>, seconds: f64) -> RuntimeSample {
+        RuntimeSample {
+            project_id: Some(1),
+            benchmark_name: "bench.py".to_string(),
+            params: RunParams {
+                env_profile: env_profile.map(str::to_string),
+                container: None,
+            },
+            server: Some(server.to_string()),
+            seconds,
+        }
+    }
+
+    fn job(id: i64, server: Option<&str>, elapsed: Option<f64>) -> EtaJob {
+        EtaJob {
+            id,
+            project_id: Some(1),
+            benchmark_name: "bench.py".to_string(),
+            params: RunParams::default(),
+            server: server.map(str::to_string),
+            elapsed,
+            progress: None,
+            starts_in: None,
+        }
+    }
+
+    #[test]
+    fn test_expected_runtime_levels() {
+        let samples = vec![
+            sample("gpu1", None, 100.0),
+            sample("gpu1", None, 300.0),
+            sample("default", None, 200.0),
+            sample("default", Some("cplex"), 1000.0),
+            sample("gpu1", Some("cplex"), 2000.0),
+        ];
+
+        // Only 2 runs on gpu1: falls back to any server with the same parameters
+        assert_eq!(
+            expected_runtime(&job(1, Some("gpu1"), None), &samples),
+            Some(200.0)
+        );
+        // Few cplex runs: any run of the benchmark
+        let mut cplex = job(2, Some("gpu1"), None);
+        cplex.params.env_profile = Some("cplex".to_string());
+        assert_eq!(expected_runtime(&cplex, &samples), Some(300.0));
+        // Not enough runs at any level: the most specific one with a run
+        assert_eq!(expected_runtime(&cplex, &samples[3..]), Some(2000.0));
+
+        let mut other = job(3, None, None);
+        other.benchmark_name = "other.py".to_string();
+        assert_eq!(expected_runtime(&other, &samples), None);
+    }
+
+    #[test]
+    fn test_remaining_time() {
+        // History only, then overrun
+        assert_eq!(remaining_time(60.0, None, Some(100.0)), Some(40.0));
+        assert_eq!(remaining_time(160.0, None, Some(100.0)), None);
+        // Progress only: 1/4 done in 60 s
+        assert_eq!(remaining_time(60.0, Some((1, 4)), None), Some(180.0));
+        assert_eq!(remaining_time(60.0, Some((0, 4)), None), None);
+        // Both, weighted by progress: 0.25 * 180 + 0.75 * 40
+        assert_eq!(remaining_time(60.0, Some((1, 4)), Some(100.0)), Some(75.0));
+    }
+
+    #[test]
+    fn test_estimate_with_concurrency() -> Result<(), String> {
+        let samples = vec![
+            sample("default", None, 100.0),
+            sample("default", None, 100.0),
+            sample("default", None, 100.0),
+        ];
+        let servers = BTreeMap::from([("default".to_string(), 2)]);
+        let now = DateTime::parse_from_rfc3339("2026-10-19T12:00:00Z")
+            .map_err(|e| e.to_string())?
+            .with_timezone(&Utc);
+
+        let mut delayed = job(4, None, None);
+        delayed.starts_in = Some(500.0);
+        let jobs = vec![
+            job(1, Some("default"), Some(40.0)),
+            job(2, None, None),
+            job(3, None, None),
+            delayed,
+        ];
+        let eta = estimate(&jobs, &samples, &servers, false, now);
+
+        let remaining: Vec<Option<f64>> = eta.jobs.iter().map(|j| j.remaining_seconds).collect();
+        // Job 2 takes the free slot, job 3 the slot of job 1, job 4 waits its start time
+        assert_eq!(
+            remaining,
+            vec![Some(60.0), Some(100.0), Some(160.0), Some(600.0)]
+        );
+        assert_eq!(eta.remaining_seconds, Some(600.0));
+        assert_eq!(eta.finishes_at.as_deref(), Some("2026-10-19T12:10:00Z"));
+        assert_eq!(eta.unestimated, 0);
+
+        // Without history nor progress, later jobs on the server are unknown too
+        let eta = estimate(&jobs, &[], &servers, false, now);
+        assert_eq!(eta.unestimated, 4);
+        assert_eq!(eta.remaining_seconds, None);
+        Ok(())
+    }
+}
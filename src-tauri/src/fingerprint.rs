@@ -0,0 +1,329 @@
+//! Environment fingerprint captured at job start
+//!
+//! Results depend on more than the code: a single SSH round-trip records the
+//! server environment the job runs in, stored as JSON on the job:
+//! - Python version, `uv pip freeze` and the `uv.lock` hash
+//! - Solver version (`gurobi_cl --version` by default)
+//! - CPU model, core count, kernel and hostname
+//!
+//! Two fingerprints can be diffed field by field (packages individually).
+
+use crate::config::AppConfig;
+use crate::db;
+use crate::ssh::{SshExecutor, SshManager};
+use crate::state::Job;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+
+/// Section marker in the capture script output
+const SECTION_PREFIX: &str = "##fp:";
+
+/// Environment of the server when a job started
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvFingerprint {
+    pub captured_at: String,
+    pub hostname: Option<String>,
+    pub kernel: Option<String>,
+    pub cpu_model: Option<String>,
+    pub cpu_cores: Option<u32>,
+    pub python_version: Option<String>,
+    /// SHA-256 of the project's `uv.lock` on the server
+    pub uv_lock_sha256: Option<String>,
+    /// First line of the solver version command output
+    pub solver_version: Option<String>,
+    /// `uv pip freeze` as package → version (editable/URL installs keep the raw line)
+    pub packages: BTreeMap<String, String>,
+}
+
+/// One difference between two fingerprints
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FingerprintChange {
+    /// Field name, or `packages.<name>` for a package
+    pub field: String,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+/// Shell script printing each fingerprint section after a `##fp:<name>` marker
+pub fn capture_script(
+    env_prelude: &str,
+    project_dir: &str,
+    uv_path: &str,
+    solver_command: &str,
+) -> String {
+    format!(
+        "{env_prelude}cd {project_dir} 2>/dev/null; \
+         echo '{SECTION_PREFIX}hostname'; hostname; \
+         echo '{SECTION_PREFIX}kernel'; uname -sr; \
+         echo '{SECTION_PREFIX}cpu_model'; grep -m1 'model name' /proc/cpuinfo | cut -d: -f2-; \
+         echo '{SECTION_PREFIX}cpu_cores'; nproc; \
+         echo '{SECTION_PREFIX}python'; {uv_path} run --no-sync python --version 2>&1 || python3 --version 2>&1; \
+         echo '{SECTION_PREFIX}uv_lock'; sha256sum uv.lock 2>/dev/null | cut -d' ' -f1; \
+         echo '{SECTION_PREFIX}packages'; {uv_path} pip freeze 2>/dev/null; \
+         echo '{SECTION_PREFIX}solver'; {solver_command} 2>&1 | head -n 5"
+    )
+}
+
+/// Parse the output of `capture_script`
+pub fn parse_capture_output(output: &str) -> EnvFingerprint {
+    let mut sections: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    let mut current = None;
+
+    for line in output.lines() {
+        if let Some(name) = line.trim().strip_prefix(SECTION_PREFIX) {
+            current = Some(name);
+            sections.entry(name).or_default();
+        } else if let Some(name) = current {
+            let line = line.trim();
+            if !line.is_empty() {
+                sections.entry(name).or_default().push(line);
+            }
+        }
+    }
+
+    let first = |name: &str| {
+        sections
+            .get(name)
+            .and_then(|lines| lines.first())
+            .map(|line| (*line).to_string())
+    };
+
+    let packages = sections
+        .get("packages")
+        .into_iter()
+        .flatten()
+        .map(|line| {
+            line.split_once("==").map_or_else(
+                || ((*line).to_string(), String::new()),
+                |(name, version)| (name.to_lowercase(), version.to_string()),
+            )
+        })
+        .collect();
+
+    EnvFingerprint {
+        captured_at: chrono::Utc::now().to_rfc3339(),
+        hostname: first("hostname"),
+        kernel: first("kernel"),
+        cpu_model: first("cpu_model"),
+        cpu_cores: first("cpu_cores").and_then(|n| n.parse().ok()),
+        python_version: first("python").map(|v| v.trim_start_matches("Python ").to_string()),
+        uv_lock_sha256: first("uv_lock"),
+        solver_version: sections
+            .get("solver")
+            .and_then(|lines| {
+                lines
+                    .iter()
+                    .find(|l| l.to_lowercase().contains("version"))
+                    .or_else(|| lines.first())
+            })
+            .map(|line| (*line).to_string()),
+        packages,
+    }
+}
+
+/// Run the capture script on the server
+///
+/// # Errors
+/// Returns error if the SSH command cannot be executed
+pub async fn capture(
+    executor: &SshExecutor,
+    env_prelude: &str,
+    project_dir: &str,
+    uv_path: &str,
+    solver_command: &str,
+) -> Result<EnvFingerprint, String> {
+    let result = executor
+        .execute_raw(&capture_script(
+            env_prelude,
+            project_dir,
+            uv_path,
+            solver_command,
+        ))
+        .await
+        .map_err(|e| format!("Failed to capture environment fingerprint: {e}"))?;
+
+    Ok(parse_capture_output(&result.stdout))
+}
+
+/// Capture the fingerprint of a job's environment and store it on the job
+///
+/// Uses the job's environment profile (job > project > default) and project directory.
+pub async fn record_job_fingerprint(
+    pool: &SqlitePool,
+    ssh: &SshManager,
+    config: &AppConfig,
+    job: &Job,
+) -> Result<EnvFingerprint, String> {
+    let project_id = job
+        .project_id
+        .ok_or_else(|| format!("Job {} has no project", job.id))?;
+    let proj = db::get_project(pool, project_id)
+        .await?
+        .ok_or("Projet non trouvé")?;
+
+    let env_prelude = config
+        .resolve_env_profile(job.env_profile.as_deref(), proj.env_profile.as_deref())?
+        .map(|profile| profile.to_shell_prelude())
+        .unwrap_or_default();
+    let project_dir = format!("{}/projects/{}", config.remote.remote_base, proj.name);
+
+    let fingerprint = capture(
+        ssh.executor(),
+        &env_prelude,
+        &project_dir,
+        &config.tools.uv_path,
+        &config.fingerprint.solver_command,
+    )
+    .await?;
+
+    let json = serde_json::to_string(&fingerprint)
+        .map_err(|e| format!("Failed to serialize environment fingerprint: {e}"))?;
+    db::set_job_env_fingerprint(pool, job.id, &json).await?;
+
+    Ok(fingerprint)
+}
+
+/// Field-by-field differences between two fingerprints (capture time ignored)
+pub fn diff(left: &EnvFingerprint, right: &EnvFingerprint) -> Vec<FingerprintChange> {
+    let fields = [
+        ("hostname", &left.hostname, &right.hostname),
+        ("kernel", &left.kernel, &right.kernel),
+        ("cpu_model", &left.cpu_model, &right.cpu_model),
+        (
+            "python_version",
+            &left.python_version,
+            &right.python_version,
+        ),
+        (
+            "uv_lock_sha256",
+            &left.uv_lock_sha256,
+            &right.uv_lock_sha256,
+        ),
+        (
+            "solver_version",
+            &left.solver_version,
+            &right.solver_version,
+        ),
+    ];
+
+    let mut changes: Vec<FingerprintChange> = fields
+        .into_iter()
+        .filter(|(_, l, r)| l != r)
+        .map(|(field, l, r)| FingerprintChange {
+            field: field.to_string(),
+            left: l.clone(),
+            right: r.clone(),
+        })
+        .collect();
+
+    if left.cpu_cores != right.cpu_cores {
+        changes.push(FingerprintChange {
+            field: "cpu_cores".to_string(),
+            left: left.cpu_cores.map(|n| n.to_string()),
+            right: right.cpu_cores.map(|n| n.to_string()),
+        });
+    }
+
+    let names: std::collections::BTreeSet<&String> =
+        left.packages.keys().chain(right.packages.keys()).collect();
+    for name in names {
+        let (l, r) = (left.packages.get(name), right.packages.get(name));
+        if l != r {
+            changes.push(FingerprintChange {
+                field: format!("packages.{name}"),
+                left: l.cloned(),
+                right: r.cloned(),
+            });
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = "##fp:hostname\n\
+        calc01\n\
+        ##fp:kernel\n\
+        Linux 6.1.0-18-amd64\n\
+        ##fp:cpu_model\n\
+        \x20Intel(R) Xeon(R) Gold 6248R CPU @ 3.00GHz\n\
+        ##fp:cpu_cores\n\
+        48\n\
+        ##fp:python\n\
+        Python 3.12.3\n\
+        ##fp:uv_lock\n\
+        9f2c1e\n\
+        ##fp:packages\n\
+        gurobipy==11.0.3\n\
+        NumPy==2.1.0\n\
+        -e file:///home/user/lib\n\
+        ##fp:solver\n\
+        \n\
+        Gurobi Optimizer version 11.0.3 build v11.0.3rc0 (linux64 - \"Debian GNU/Linux 12\")\n";
+
+    #[test]
+    fn test_parse_capture_output() {
+        let fp = parse_capture_output(OUTPUT);
+
+        assert_eq!(fp.hostname.as_deref(), Some("calc01"));
+        assert_eq!(fp.kernel.as_deref(), Some("Linux 6.1.0-18-amd64"));
+        assert_eq!(
+            fp.cpu_model.as_deref(),
+            Some("Intel(R) Xeon(R) Gold 6248R CPU @ 3.00GHz")
+        );
+        assert_eq!(fp.cpu_cores, Some(48));
+        assert_eq!(fp.python_version.as_deref(), Some("3.12.3"));
+        assert_eq!(fp.uv_lock_sha256.as_deref(), Some("9f2c1e"));
+        assert_eq!(fp.packages.get("numpy").map(String::as_str), Some("2.1.0"));
+        assert!(fp.packages.contains_key("-e file:///home/user/lib"));
+        assert!(fp
+            .solver_version
+            .is_some_and(|v| v.starts_with("Gurobi Optimizer version 11.0.3")));
+    }
+
+    #[test]
+    fn test_parse_missing_sections() {
+        let fp = parse_capture_output("##fp:hostname\ncalc01\n##fp:uv_lock\n##fp:cpu_cores\n?\n");
+        assert_eq!(fp.hostname.as_deref(), Some("calc01"));
+        assert_eq!(fp.uv_lock_sha256, None);
+        assert_eq!(fp.cpu_cores, None);
+        assert!(fp.packages.is_empty());
+    }
+
+    #[test]
+    fn test_diff_fingerprints() {
+        let left = parse_capture_output(OUTPUT);
+        let mut right = left.clone();
+        right.captured_at = "later".to_string();
+        assert!(diff(&left, &right).is_empty());
+
+        right.hostname = Some("calc02".to_string());
+        right.cpu_cores = Some(64);
+        right
+            .packages
+            .insert("numpy".to_string(), "2.2.0".to_string());
+        right.packages.remove("gurobipy");
+        right
+            .packages
+            .insert("scipy".to_string(), "1.14.0".to_string());
+
+        let changes = diff(&left, &right);
+        let fields: Vec<&str> = changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "hostname",
+                "cpu_cores",
+                "packages.gurobipy",
+                "packages.numpy",
+                "packages.scipy"
+            ]
+        );
+        assert_eq!(changes[2].right, None);
+        assert_eq!(changes[4].left, None);
+    }
+}
@@ -0,0 +1,342 @@
+//! History queries over finished jobs
+//!
+//! `HistoryQuery` combines filters (project, benchmark, status, creation date,
+//! result parameter values, tags), a sort order and keyset pagination: each page
+//! returns an opaque `next_cursor` holding the sort value and id of its last
+//! job, so pages stay stable while new jobs finish. `HistoryStats` aggregates
+//! the same filtered set (success rate, mean duration per benchmark, daily
+//! failure trend). The SQL runs in `db::query_history` / `db::history_stats`.
+
+use crate::db;
+use crate::state::{Job, JobStatus};
+use serde::{Deserialize, Serialize};
+
+/// Page size when none is given, and the largest accepted
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+pub const MAX_PAGE_SIZE: u32 = 500;
+
+/// Job duration in seconds (NULL while a timestamp is missing)
+pub const DURATION_SQL: &str = "(julianday(j.finished_at) - julianday(j.started_at)) * 86400.0";
+
+/// Value of a result column required on at least one result row of the job
+///
+/// `name` is a column of `benchmark_results.csv` (e.g. `instance`, `seed`);
+/// values are compared as text (`true`/`false` are stored as 1/0).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParamFilter {
+    pub name: String,
+    pub value: String,
+}
+
+/// History filters (all optional; no status = every finished job)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistoryFilters {
+    #[serde(default)]
+    pub project_id: Option<i64>,
+    /// Exact benchmark name
+    #[serde(default)]
+    pub benchmark: Option<String>,
+    #[serde(default)]
+    pub statuses: Vec<JobStatus>,
+    /// Creation date lower bound (YYYY-MM-DD, inclusive)
+    #[serde(default)]
+    pub from: Option<String>,
+    /// Creation date upper bound (YYYY-MM-DD, inclusive)
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub params: Vec<ParamFilter>,
+    /// Tags the job must all carry
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Bound value of a generated SQL fragment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SqlArg {
+    Int(i64),
+    Text(String),
+}
+
+impl HistoryFilters {
+    /// `WHERE` clause over `jobs j` and its arguments, in bind order
+    pub fn where_clause(&self) -> Result<(String, Vec<SqlArg>), String> {
+        let mut conditions = Vec::new();
+        let mut args = Vec::new();
+
+        if self.statuses.is_empty() {
+            conditions.push("j.status IN ('completed', 'failed', 'killed')".to_string());
+        } else {
+            conditions.push(format!(
+                "j.status IN ({})",
+                vec!["?"; self.statuses.len()].join(", ")
+            ));
+            args.extend(
+                self.statuses
+                    .iter()
+                    .map(|status| SqlArg::Text(db::status_to_str(status).to_string())),
+            );
+        }
+        if let Some(project_id) = self.project_id {
+            conditions.push("j.project_id = ?".to_string());
+            args.push(SqlArg::Int(project_id));
+        }
+        if let Some(ref benchmark) = self.benchmark {
+            conditions.push("j.benchmark_name = ?".to_string());
+            args.push(SqlArg::Text(benchmark.clone()));
+        }
+        if let Some(ref from) = self.from {
+            conditions.push("date(j.created_at) >= date(?)".to_string());
+            args.push(SqlArg::Text(from.clone()));
+        }
+        if let Some(ref to) = self.to {
+            conditions.push("date(j.created_at) <= date(?)".to_string());
+            args.push(SqlArg::Text(to.clone()));
+        }
+        for param in &self.params {
+            if param.name.is_empty() || param.name.contains('"') {
+                return Err(format!("Invalid parameter name '{}'", param.name));
+            }
+            conditions.push(
+                "EXISTS (SELECT 1 FROM results r WHERE r.job_id = j.id \
+                 AND CAST(json_extract(r.data, ?) AS TEXT) = ?)"
+                    .to_string(),
+            );
+            args.push(SqlArg::Text(format!("$.\"{}\"", param.name)));
+            args.push(SqlArg::Text(param.value.clone()));
+        }
+
+        for tag in &self.tags {
+            conditions.push(
+                "EXISTS (SELECT 1 FROM job_tags jt JOIN tags t ON t.id = jt.tag_id \
+                 WHERE jt.job_id = j.id AND t.name = ?)"
+                    .to_string(),
+            );
+            args.push(SqlArg::Text(tag.trim().to_string()));
+        }
+
+        Ok((format!("WHERE {}", conditions.join(" AND ")), args))
+    }
+}
+
+/// Sort key of a history query
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistorySort {
+    #[default]
+    FinishedAt,
+    CreatedAt,
+    Duration,
+    Benchmark,
+    PeakMemory,
+}
+
+impl HistorySort {
+    /// SQL expression of the key (never NULL, so keyset comparisons hold)
+    pub const fn sql(self) -> &'static str {
+        match self {
+            Self::FinishedAt => "COALESCE(j.finished_at, '')",
+            Self::CreatedAt => "j.created_at",
+            // Milliseconds: integer keys survive the cursor round trip exactly
+            Self::Duration => {
+                "COALESCE(CAST(ROUND((julianday(j.finished_at) - julianday(j.started_at)) * 86400000.0) AS INTEGER), -1)"
+            }
+            Self::Benchmark => "j.benchmark_name",
+            Self::PeakMemory => "COALESCE(j.peak_rss_kb, -1)",
+        }
+    }
+
+    pub const fn is_numeric(self) -> bool {
+        matches!(self, Self::Duration | Self::PeakMemory)
+    }
+}
+
+/// Paginated history request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistoryQuery {
+    #[serde(default)]
+    pub filters: HistoryFilters,
+    #[serde(default)]
+    pub sort: HistorySort,
+    /// Ascending order (newest / largest first by default)
+    #[serde(default)]
+    pub ascending: bool,
+    #[serde(default)]
+    pub limit: Option<u32>,
+    /// `next_cursor` of the previous page
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+impl HistoryQuery {
+    pub fn page_size(&self) -> u32 {
+        self.limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE)
+    }
+}
+
+/// One page of history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryPage {
+    pub jobs: Vec<Job>,
+    /// Cursor of the next page (`None` on the last page)
+    pub next_cursor: Option<String>,
+    /// Jobs matching the filters, all pages included
+    pub total: i64,
+}
+
+/// Position after the last job of a page: its sort value and id
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cursor {
+    pub sort: HistorySort,
+    pub key: CursorKey,
+    pub id: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CursorKey {
+    Number(i64),
+    Text(String),
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Decode a cursor, rejecting one produced for another sort key
+    pub fn decode(cursor: &str, sort: HistorySort) -> Result<Self, String> {
+        let cursor: Self =
+            serde_json::from_str(cursor).map_err(|e| format!("Invalid history cursor: {e}"))?;
+        if cursor.sort != sort {
+            return Err("History cursor belongs to another sort order".to_string());
+        }
+        Ok(cursor)
+    }
+
+    /// Keyset condition selecting the jobs after this cursor, and its arguments
+    pub fn condition(&self, ascending: bool) -> (String, Vec<SqlArg>) {
+        let key = self.sort.sql();
+        let op = if ascending { ">" } else { "<" };
+        let value = match self.key {
+            CursorKey::Number(n) => SqlArg::Int(n),
+            CursorKey::Text(ref s) => SqlArg::Text(s.clone()),
+        };
+        (
+            format!("({key} {op} ? OR ({key} = ? AND j.id {op} ?))"),
+            vec![value.clone(), value, SqlArg::Int(self.id)],
+        )
+    }
+}
+
+/// Aggregates of one benchmark
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkStats {
+    pub benchmark_name: String,
+    pub runs: i64,
+    pub completed: i64,
+    pub failed: i64,
+    /// Completed / runs
+    pub success_rate: f64,
+    /// Mean duration of completed runs, in seconds
+    pub mean_duration_seconds: Option<f64>,
+}
+
+/// Runs and failures of one day (creation date)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyFailures {
+    pub day: String,
+    pub runs: i64,
+    pub failed: i64,
+}
+
+/// Aggregates over the filtered history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryStats {
+    pub total: i64,
+    pub completed: i64,
+    pub failed: i64,
+    pub killed: i64,
+    pub success_rate: f64,
+    /// Most run benchmarks first
+    pub per_benchmark: Vec<BenchmarkStats>,
+    /// Oldest day first
+    pub failure_trend: Vec<DailyFailures>,
+}
+
+/// `part / total`, 0 for an empty set
+#[allow(clippy::cast_precision_loss)] // Job counts stay far below 2^52
+pub fn rate(part: i64, total: i64) -> f64 {
+    if total > 0 {
+        part as f64 / total as f64
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_where_clause() -> Result<(), String> {
+        let (clause, args) = HistoryFilters::default().where_clause()?;
+        assert_eq!(
+            clause,
+            "WHERE j.status IN ('completed', 'failed', 'killed')"
+        );
+        assert!(args.is_empty());
+
+        let filters = HistoryFilters {
+            project_id: Some(3),
+            statuses: vec![JobStatus::Failed, JobStatus::Killed],
+            params: vec![ParamFilter {
+                name: "instance".to_string(),
+                value: "N12".to_string(),
+            }],
+            ..HistoryFilters::default()
+        };
+        let (clause, args) = filters.where_clause()?;
+        assert!(clause.starts_with("WHERE j.status IN (?, ?) AND j.project_id = ? AND EXISTS"));
+        assert_eq!(
+            args,
+            vec![
+                SqlArg::Text("failed".to_string()),
+                SqlArg::Text("killed".to_string()),
+                SqlArg::Int(3),
+                SqlArg::Text("$.\"instance\"".to_string()),
+                SqlArg::Text("N12".to_string()),
+            ]
+        );
+
+        let injected = HistoryFilters {
+            params: vec![ParamFilter {
+                name: "a\"] OR 1".to_string(),
+                value: String::new(),
+            }],
+            ..HistoryFilters::default()
+        };
+        assert!(injected.where_clause().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cursor_roundtrip() -> Result<(), String> {
+        let cursor = Cursor {
+            sort: HistorySort::Duration,
+            key: CursorKey::Number(12_500),
+            id: 7,
+        };
+        let decoded = Cursor::decode(&cursor.encode(), HistorySort::Duration)?;
+        assert_eq!(decoded, cursor);
+        assert!(Cursor::decode(&cursor.encode(), HistorySort::Benchmark).is_err());
+        assert!(Cursor::decode("garbage", HistorySort::Duration).is_err());
+
+        let (condition, args) = decoded.condition(false);
+        assert!(condition.contains("< ? OR"));
+        assert_eq!(args.len(), 3);
+        Ok(())
+    }
+}
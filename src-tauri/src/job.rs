@@ -1,5 +1,7 @@
 //! Fonctions utilitaires pour la gestion des jobs
 
+use chrono::{DateTime, SecondsFormat, Utc};
+
 /// Parse la progression depuis les logs
 /// Format attendu: [12/22] Config: ...
 pub fn parse_progress(logs: &str) -> Option<(u32, u32)> {
@@ -55,6 +57,19 @@ pub fn detect_job_error(logs: &str) -> Option<String> {
     }
 }
 
+/// Échappe une chaîne destinée à être placée entre quotes simples dans une commande shell
+pub fn escape_single_quotes(s: &str) -> String {
+    s.replace('\'', r"'\''")
+}
+
+/// Mot shell entre quotes simples pour un chemin distant, un `~/` initial devenant `$HOME/`
+pub fn shell_path(path: &str) -> String {
+    path.strip_prefix("~/").map_or_else(
+        || format!("'{}'", escape_single_quotes(path)),
+        |rest| format!("\"$HOME\"/'{}'", escape_single_quotes(rest)),
+    )
+}
+
 /// Formate le temps écoulé
 pub fn format_duration(secs: u64) -> String {
     if secs < 60 {
@@ -70,6 +85,23 @@ pub fn format_duration(secs: u64) -> String {
     }
 }
 
+/// Normalise une heure de départ (RFC 3339, tout fuseau) en horodatage UTC
+///
+/// Les horodatages UTC à la seconde se comparent comme des chaînes (en SQL).
+pub fn parse_start_time(value: &str) -> Result<String, String> {
+    DateTime::parse_from_rfc3339(value.trim())
+        .map(|at| {
+            at.with_timezone(&Utc)
+                .to_rfc3339_opts(SecondsFormat::Secs, true)
+        })
+        .map_err(|e| format!("Invalid start time '{value}': {e}"))
+}
+
+/// Heure courante au format des heures de départ
+pub fn start_time_now() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,10 +127,42 @@ mod tests {
         assert!(!detect_job_finished(logs2));
     }
 
+    #[test]
+    fn test_escape_single_quotes() {
+        assert_eq!(escape_single_quotes("echo ok"), "echo ok");
+        assert_eq!(escape_single_quotes("echo 'hi'"), r"echo '\''hi'\''");
+    }
+
+    #[test]
+    fn test_shell_path() {
+        assert_eq!(shell_path("~/jobs/1.log"), r#""$HOME"/'jobs/1.log'"#);
+        assert_eq!(shell_path("/data/it's.csv"), r"'/data/it'\''s.csv'");
+        assert_eq!(
+            shell_path("~/a $(b) `c` \"d\\e\""),
+            r#""$HOME"/'a $(b) `c` "d\e"'"#
+        );
+        assert_eq!(shell_path("~user/x"), "'~user/x'");
+    }
+
     #[test]
     fn test_format_duration() {
         assert_eq!(format_duration(45), "45s");
         assert_eq!(format_duration(125), "2m 5s");
         assert_eq!(format_duration(3725), "1h 2m");
     }
+
+    #[test]
+    fn test_parse_start_time() -> Result<(), String> {
+        assert_eq!(
+            parse_start_time("2026-10-20T02:00:00+02:00")?,
+            "2026-10-20T00:00:00Z"
+        );
+        assert_eq!(
+            parse_start_time("2026-10-20T18:00:30.500Z")?,
+            "2026-10-20T18:00:30Z"
+        );
+        assert!(parse_start_time("demain 2h").is_err());
+        assert!(start_time_now() > parse_start_time("2020-01-01T00:00:00Z")?);
+        Ok(())
+    }
 }
@@ -1,14 +1,36 @@
+pub mod batch;
+pub mod code_version;
 pub mod commands;
 pub mod config;
+pub mod container;
 pub mod db;
+pub mod dispatch;
+pub mod eta;
+pub mod fingerprint;
+pub mod history;
 pub mod job;
+pub mod license;
+pub mod limits;
+pub mod load_guard;
+pub mod local;
+pub mod log_archive;
+pub mod log_stream;
+pub mod migrations;
 pub mod paths;
+pub mod pbs;
 pub mod project;
 pub mod python_deps;
 pub mod queue_service;
+pub mod report;
+pub mod results;
+pub mod schedule;
 pub mod server_db;
+pub mod servers;
+pub mod sge;
+pub mod slurm;
 pub mod ssh;
 pub mod state;
+pub mod usage;
 pub mod wrapper;
 
 use tauri::Manager;
@@ -48,10 +70,12 @@ pub fn run() -> Result<(), tauri::Error> {
             commands::test_ssh_direct,
             commands::check_ssh_key_status,
             commands::add_ssh_key,
+            commands::list_servers,
             // Server Database
             commands::init_server_db,
             // Wrapper Deployment
             commands::check_wrapper_installed,
+            commands::check_license,
             commands::deploy_wrapper,
             // Sync
             commands::check_sync_status,
@@ -63,6 +87,12 @@ pub fn run() -> Result<(), tauri::Error> {
             commands::delete_project,
             commands::set_active_project,
             commands::get_active_project,
+            // Environment Profiles
+            commands::list_env_profiles,
+            commands::set_project_env_profile,
+            commands::list_container_profiles,
+            commands::set_project_container,
+            commands::get_job_container,
             // Python Versions
             commands::list_python_versions,
             commands::set_project_python_version,
@@ -71,6 +101,10 @@ pub fn run() -> Result<(), tauri::Error> {
             commands::remove_benchmark_from_project,
             commands::list_project_benchmarks,
             commands::get_benchmark_dependencies,
+            commands::get_benchmark_limits,
+            commands::set_benchmark_limits,
+            commands::get_benchmark_servers,
+            commands::set_benchmark_servers,
             // Jobs
             commands::queue_jobs,
             commands::queue_benchmarks, // Story 1.2 - Queue with position
@@ -80,12 +114,23 @@ pub fn run() -> Result<(), tauri::Error> {
             commands::move_job_to_front,
             commands::move_job_to_end,
             commands::reorder_queue_job,
+            commands::set_job_not_before,
             commands::cancel_all_pending_jobs,
             commands::start_next_job,
             commands::stop_job,
             commands::kill_job,
             commands::get_job_logs,
+            commands::subscribe_job_logs,
+            commands::unsubscribe_job_logs,
             commands::get_job_status,
+            // Tags & Notes
+            commands::set_job_tags,
+            commands::set_job_notes,
+            commands::list_tags,
+            // Log Archive
+            commands::get_archived_job_log,
+            commands::archive_job_log,
+            commands::prune_log_archive,
             // Story 2.4 - Queue Processing
             commands::start_queue_processing,
             commands::stop_queue_processing,
@@ -95,7 +140,21 @@ pub fn run() -> Result<(), tauri::Error> {
             commands::resume_queue_processing,
             // History
             commands::load_history,
+            commands::query_history,
+            commands::get_history_stats,
+            commands::search_jobs,
             commands::delete_job,
+            commands::ingest_job_results,
+            commands::get_job_results,
+            commands::get_result_summaries,
+            // Code Versions
+            commands::get_job_code_version,
+            commands::rerun_job_version,
+            // Environment Fingerprints
+            commands::get_job_env_fingerprint,
+            commands::diff_job_env_fingerprints,
+            // Reports
+            commands::generate_comparison_report,
             // Project Dependencies
             commands::add_project_dependency,
             commands::remove_project_dependency,
@@ -0,0 +1,242 @@
+//! License preflight check before job launch
+//!
+//! Runs a tiny license query (`gurobi_cl --license` or `gurobi_cl --tokens`) on the
+//! server through `SshExecutor` and decides whether the next job may start:
+//! - Missing or expired license → job is blocked with the solver's error
+//! - All tokens in use / token server unreachable → job is delayed and retried
+//! - License close to expiry → job starts, a warning is surfaced
+
+use crate::ssh::SshExecutor;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Parsed output of the license query
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LicenseStatus {
+    /// Expiry date reported by the solver (`None` for permanent licenses)
+    pub expires_on: Option<NaiveDate>,
+    /// Tokens currently checked out (token server only)
+    pub tokens_in_use: Option<u32>,
+    /// Maximum concurrent tokens (token server only)
+    pub tokens_max: Option<u32>,
+    /// First error line reported by the solver, if any
+    pub error: Option<String>,
+}
+
+/// Decision taken before launching a job
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "outcome", content = "reason", rename_all = "lowercase")]
+pub enum PreflightOutcome {
+    /// License available; optional warning (e.g. expiry approaching)
+    Ready(Option<String>),
+    /// License temporarily unavailable, retry later
+    Delay(String),
+    /// License unusable, the job must not start
+    Block(String),
+}
+
+/// Result of an on-demand license check (status + decision)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LicenseReport {
+    pub status: LicenseStatus,
+    pub outcome: PreflightOutcome,
+}
+
+/// Run the license query on the server and parse its output
+///
+/// `env_prelude` is the job's environment profile so the solver binary is on `PATH`.
+///
+/// # Errors
+/// Returns error if the SSH command cannot be executed
+pub async fn query_license(
+    executor: &SshExecutor,
+    env_prelude: &str,
+    command: &str,
+) -> Result<LicenseStatus, String> {
+    let result = executor
+        .execute_raw(&format!("{env_prelude}{command} 2>&1"))
+        .await
+        .map_err(|e| format!("Failed to query license: {e}"))?;
+
+    let mut status = parse_license_output(&format!("{}\n{}", result.stdout, result.stderr));
+    if status.error.is_none() && !result.success() {
+        status.error = Some(format!(
+            "License command exited with code {}",
+            result.exit_code
+        ));
+    }
+    Ok(status)
+}
+
+/// Parse `gurobi_cl --license` / `gurobi_cl --tokens` output
+///
+/// Recognized lines:
+/// - `... license - for non-commercial use only - expires 2026-06-12`
+/// - `Maximum allowed uses: 4, current: 2`
+/// - `ERROR 10009: License expired 2025-01-01`
+pub fn parse_license_output(output: &str) -> LicenseStatus {
+    let mut status = LicenseStatus::default();
+
+    for line in output.lines().map(str::trim) {
+        let lower = line.to_lowercase();
+
+        if status.error.is_none() && lower.starts_with("error") {
+            status.error = Some(line.to_string());
+        }
+
+        if lower.contains("expire") && status.expires_on.is_none() {
+            status.expires_on = line.split_whitespace().find_map(|word| {
+                let word = word.trim_matches(|c: char| !c.is_ascii_digit() && c != '-');
+                NaiveDate::parse_from_str(word, "%Y-%m-%d").ok()
+            });
+        }
+
+        if let Some(rest) = lower.strip_prefix("maximum allowed uses:") {
+            let mut numbers = rest
+                .split(|c: char| !c.is_ascii_digit())
+                .filter_map(|n| n.parse::<u32>().ok());
+            status.tokens_max = numbers.next();
+            status.tokens_in_use = numbers.next();
+        }
+    }
+
+    status
+}
+
+/// Decide whether a job may start given the license status
+pub fn evaluate(status: &LicenseStatus, today: NaiveDate, warn_days: i64) -> PreflightOutcome {
+    if let Some(ref error) = status.error {
+        let lower = error.to_lowercase();
+        let transient = lower.contains("token")
+            || lower.contains("in use")
+            || lower.contains("unable to contact")
+            || lower.contains("timed out");
+
+        return if transient && !lower.contains("expired") {
+            PreflightOutcome::Delay(format!("Waiting for a license: {error}"))
+        } else {
+            PreflightOutcome::Block(format!("License unavailable: {error}"))
+        };
+    }
+
+    if let (Some(in_use), Some(max)) = (status.tokens_in_use, status.tokens_max) {
+        if in_use >= max {
+            return PreflightOutcome::Delay(format!(
+                "Waiting for a license token ({in_use}/{max} in use)"
+            ));
+        }
+    }
+
+    match status.expires_on {
+        Some(date) if date < today => PreflightOutcome::Block(format!("License expired on {date}")),
+        Some(date) if (date - today).num_days() <= warn_days => {
+            PreflightOutcome::Ready(Some(format!(
+                "License expires on {date} ({} days left)",
+                (date - today).num_days()
+            )))
+        }
+        _ => PreflightOutcome::Ready(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> Result<NaiveDate, chrono::ParseError> {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d")
+    }
+
+    #[test]
+    fn test_parse_named_user_license() -> Result<(), Box<dyn std::error::Error>> {
+        let output = "Set parameter Username\n\
+            Academic license - for non-commercial use only - expires 2026-11-02\n\
+            Using license file /home/user/gurobi.lic\n";
+
+        let status = parse_license_output(output);
+        assert_eq!(status.expires_on, Some(date("2026-11-02")?));
+        assert_eq!(status.error, None);
+        assert_eq!(status.tokens_max, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_token_server() {
+        let output = "Checking status of Gurobi token server 'lic01'...\n\n\
+            Token server functioning normally.\n\
+            Maximum allowed uses: 4, current: 4\n";
+
+        let status = parse_license_output(output);
+        assert_eq!(status.tokens_max, Some(4));
+        assert_eq!(status.tokens_in_use, Some(4));
+    }
+
+    #[test]
+    fn test_parse_error_line() -> Result<(), Box<dyn std::error::Error>> {
+        let status = parse_license_output("ERROR 10009: License expired 2025-01-01\n");
+        assert_eq!(
+            status.error.as_deref(),
+            Some("ERROR 10009: License expired 2025-01-01")
+        );
+        assert_eq!(status.expires_on, Some(date("2025-01-01")?));
+        Ok(())
+    }
+
+    #[test]
+    fn test_evaluate_outcomes() -> Result<(), Box<dyn std::error::Error>> {
+        let today = date("2026-10-19")?;
+
+        let ok = LicenseStatus {
+            expires_on: Some(date("2027-10-19")?),
+            ..LicenseStatus::default()
+        };
+        assert_eq!(evaluate(&ok, today, 14), PreflightOutcome::Ready(None));
+
+        let soon = LicenseStatus {
+            expires_on: Some(date("2026-10-26")?),
+            ..LicenseStatus::default()
+        };
+        assert!(matches!(
+            evaluate(&soon, today, 14),
+            PreflightOutcome::Ready(Some(ref w)) if w.contains("7 days left")
+        ));
+
+        let expired = LicenseStatus {
+            expires_on: Some(date("2026-10-01")?),
+            ..LicenseStatus::default()
+        };
+        assert!(matches!(
+            evaluate(&expired, today, 14),
+            PreflightOutcome::Block(_)
+        ));
+
+        let exhausted = LicenseStatus {
+            tokens_in_use: Some(2),
+            tokens_max: Some(2),
+            ..LicenseStatus::default()
+        };
+        assert!(matches!(
+            evaluate(&exhausted, today, 14),
+            PreflightOutcome::Delay(_)
+        ));
+
+        let missing = LicenseStatus {
+            error: Some("ERROR 10009: No Gurobi license found".to_string()),
+            ..LicenseStatus::default()
+        };
+        assert!(matches!(
+            evaluate(&missing, today, 14),
+            PreflightOutcome::Block(_)
+        ));
+
+        let busy = LicenseStatus {
+            error: Some("ERROR 10009: Failed to obtain token: all tokens in use".to_string()),
+            ..LicenseStatus::default()
+        };
+        assert!(matches!(
+            evaluate(&busy, today, 14),
+            PreflightOutcome::Delay(_)
+        ));
+        Ok(())
+    }
+}
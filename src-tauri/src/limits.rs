@@ -0,0 +1,228 @@
+//! Memory and CPU limits applied to jobs
+//!
+//! Limits come from the `[limits]` config section, overridden field by field by
+//! the limits stored on a benchmark. They are passed to `job_wrapper.sh` as
+//! `SOLVERPILOT_*` variables; the wrapper applies them with a `systemd-run --user`
+//! scope (cgroup v2) when available, `ulimit`/`taskset` otherwise, and reports a
+//! limit hit as a distinct `"reason"` in the job's state file.
+
+use crate::config::AppConfig;
+use crate::db;
+use crate::ssh::SshExecutor;
+use crate::state::Job;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+/// Resource limits of a job (`None` = unlimited)
+///
+/// ```toml
+/// [limits]
+/// memory_mb = 16384
+/// cpu_quota_percent = 400   # 4 cores
+/// cpu_affinity = "0-3"
+/// cpu_time_limit_s = 7200
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobLimits {
+    /// Memory cap in MiB
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_mb: Option<u64>,
+    /// CPU quota in percent of one core (cgroup scope only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_quota_percent: Option<u32>,
+    /// CPU list for `taskset -c` (e.g. "0-3,8")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_affinity: Option<String>,
+    /// CPU time limit in seconds (SIGXCPU, then SIGKILL 5 s later)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_time_limit_s: Option<u64>,
+}
+
+impl JobLimits {
+    /// Check values before they reach the wrapper command line
+    pub fn validate(&self) -> Result<(), String> {
+        if self.memory_mb == Some(0) {
+            return Err("Memory limit must be greater than 0 MiB".to_string());
+        }
+        if self.cpu_quota_percent == Some(0) {
+            return Err("CPU quota must be greater than 0%".to_string());
+        }
+        if self.cpu_time_limit_s == Some(0) {
+            return Err("CPU time limit must be greater than 0 s".to_string());
+        }
+        if let Some(ref affinity) = self.cpu_affinity {
+            let valid = !affinity.is_empty()
+                && affinity
+                    .split(',')
+                    .flat_map(|range| range.split('-'))
+                    .all(|cpu| !cpu.is_empty() && cpu.chars().all(|c| c.is_ascii_digit()));
+            if !valid {
+                return Err(format!(
+                    "Invalid CPU affinity '{affinity}' (expected a list like 0-3,8)"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Fields set in `overrides` replace those of `self`
+    #[must_use]
+    pub fn merge(&self, overrides: &Self) -> Self {
+        Self {
+            memory_mb: overrides.memory_mb.or(self.memory_mb),
+            cpu_quota_percent: overrides.cpu_quota_percent.or(self.cpu_quota_percent),
+            cpu_affinity: overrides
+                .cpu_affinity
+                .clone()
+                .or_else(|| self.cpu_affinity.clone()),
+            cpu_time_limit_s: overrides.cpu_time_limit_s.or(self.cpu_time_limit_s),
+        }
+    }
+
+    /// Variable assignments prefixed to the wrapper command (empty or ending with a space)
+    pub fn to_env_assignments(&self) -> String {
+        let vars = [
+            (
+                "SOLVERPILOT_MEM_LIMIT_MB",
+                self.memory_mb.map(|v| v.to_string()),
+            ),
+            (
+                "SOLVERPILOT_CPU_QUOTA",
+                self.cpu_quota_percent.map(|v| v.to_string()),
+            ),
+            ("SOLVERPILOT_CPU_AFFINITY", self.cpu_affinity.clone()),
+            (
+                "SOLVERPILOT_CPU_TIME_LIMIT",
+                self.cpu_time_limit_s.map(|v| v.to_string()),
+            ),
+        ];
+
+        vars.into_iter()
+            .filter_map(|(key, value)| value.map(|v| format!("{key}={v} ")))
+            .collect()
+    }
+}
+
+/// Parse a JSON `JobLimits` stored on a benchmark
+pub fn parse_limits(json: &str) -> Result<JobLimits, String> {
+    serde_json::from_str(json).map_err(|e| format!("Invalid job limits: {e}"))
+}
+
+/// Limits of a job: config defaults overridden by its benchmark's limits
+pub async fn resolve_job_limits(
+    pool: &SqlitePool,
+    config: &AppConfig,
+    job: &Job,
+) -> Result<JobLimits, String> {
+    let Some(project_id) = job.project_id else {
+        return Ok(config.limits.clone());
+    };
+
+    let limits =
+        match db::get_benchmark_limits_by_name(pool, project_id, &job.benchmark_name).await? {
+            Some(json) => config.limits.merge(&parse_limits(&json)?),
+            None => config.limits.clone(),
+        };
+    limits.validate()?;
+
+    Ok(limits)
+}
+
+/// Extract the limit `"reason"` from the wrapper's state file (`None` when empty)
+pub fn parse_limit_reason(state_json: &str) -> Option<String> {
+    serde_json::from_str::<serde_json::Value>(state_json.trim())
+        .ok()?
+        .get("reason")?
+        .as_str()
+        .filter(|reason| !reason.is_empty())
+        .map(str::to_string)
+}
+
+/// Read why the wrapper stopped a job (out of memory, limit exceeded), if it did
+///
+/// # Errors
+/// Returns error if the SSH command cannot be executed
+pub async fn read_limit_reason(
+    executor: &SshExecutor,
+    job_id: i64,
+) -> Result<Option<String>, String> {
+    let result = executor
+        .execute_raw(&format!(
+            "cat ~/.solverpilot-server/jobs/{job_id}.status 2>/dev/null"
+        ))
+        .await
+        .map_err(|e| format!("Failed to read job state file: {e}"))?;
+
+    Ok(parse_limit_reason(&result.stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_limits() {
+        let mut limits = JobLimits {
+            memory_mb: Some(4096),
+            cpu_affinity: Some("0-3,8".to_string()),
+            ..JobLimits::default()
+        };
+        assert!(limits.validate().is_ok());
+
+        limits.cpu_affinity = Some("0-3; rm -rf ~".to_string());
+        assert!(limits.validate().is_err());
+        limits.cpu_affinity = Some("1,,2".to_string());
+        assert!(limits.validate().is_err());
+        limits.cpu_affinity = None;
+        limits.memory_mb = Some(0);
+        assert!(limits.validate().is_err());
+    }
+
+    #[test]
+    fn test_merge_and_env_assignments() {
+        let defaults = JobLimits {
+            memory_mb: Some(8192),
+            cpu_time_limit_s: Some(3600),
+            ..JobLimits::default()
+        };
+        let overrides = JobLimits {
+            memory_mb: Some(2048),
+            cpu_affinity: Some("0-1".to_string()),
+            ..JobLimits::default()
+        };
+
+        let limits = defaults.merge(&overrides);
+        assert_eq!(
+            limits.to_env_assignments(),
+            "SOLVERPILOT_MEM_LIMIT_MB=2048 SOLVERPILOT_CPU_AFFINITY=0-1 SOLVERPILOT_CPU_TIME_LIMIT=3600 "
+        );
+        assert_eq!(JobLimits::default().to_env_assignments(), "");
+    }
+
+    #[test]
+    fn test_parse_limits_json() -> Result<(), Box<dyn std::error::Error>> {
+        let limits = parse_limits(r#"{"memory_mb": 1024, "cpu_quota_percent": 200}"#)?;
+        assert_eq!(limits.memory_mb, Some(1024));
+        assert_eq!(limits.cpu_quota_percent, Some(200));
+        assert_eq!(serde_json::to_string(&JobLimits::default())?, "{}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_limit_reason() {
+        let state = r#"{
+  "id": "42",
+  "status": "failed",
+  "exit_code": 1,
+  "reason": "out of memory (limit 50 MiB)",
+  "user": "alice"
+}"#;
+        assert_eq!(
+            parse_limit_reason(state).as_deref(),
+            Some("out of memory (limit 50 MiB)")
+        );
+        assert_eq!(parse_limit_reason(r#"{"reason": ""}"#), None);
+        assert_eq!(parse_limit_reason(r#"{"status": "running"}"#), None);
+        assert_eq!(parse_limit_reason(""), None);
+    }
+}
@@ -0,0 +1,222 @@
+//! Server load guard checked before each job starts
+//!
+//! Servers are shared with other users: before starting a job on a tmux or
+//! local server, the queue samples the server over SSH and holds the job
+//! ("waiting for resources") while:
+//! - the 1-minute load average per core is above `max_load_per_core`,
+//! - less than `min_free_memory_mb` of memory is available (`MemAvailable`),
+//! - more than `max_heavy_processes` processes of other users use at least
+//!   `heavy_process_cpu` % CPU (as reported by `ps`, averaged over their lifetime).
+//!
+//! The load average includes the queue's own running jobs. Batch scheduler
+//! servers are not checked: their scheduler allocates the resources.
+
+use crate::config::{ExecutionBackend, LoadGuardConfig};
+use crate::dispatch::{self, ServerLoad};
+use crate::ssh::SshExecutor;
+
+/// Load, memory, user and top processes, separated by `---` lines
+const SAMPLE_COMMAND: &str = "nproc; cat /proc/loadavg; echo ---; \
+     grep MemAvailable /proc/meminfo; echo ---; id -un; echo ---; \
+     ps -eo user:32=,pcpu=,comm= --sort=-pcpu | head -n 50";
+
+/// Heavy processes named in a reason
+const NAMED_PROCESSES: usize = 3;
+
+/// A process and its CPU usage
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessUsage {
+    pub user: String,
+    pub cpu_percent: f64,
+    pub command: String,
+}
+
+/// Resources of a server, sampled over SSH
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerResources {
+    pub load: ServerLoad,
+    /// Available memory in MiB
+    pub available_mb: u64,
+    /// User the queue runs jobs as
+    pub user: String,
+    /// Busiest processes, most CPU first
+    pub processes: Vec<ProcessUsage>,
+}
+
+/// Whether servers with this backend are checked
+pub const fn applies_to(backend: ExecutionBackend) -> bool {
+    matches!(backend, ExecutionBackend::Tmux | ExecutionBackend::Local)
+}
+
+/// Parse the output of `SAMPLE_COMMAND`
+pub fn parse_resources(output: &str) -> Result<ServerResources, String> {
+    let mut sections = vec![String::new()];
+    for line in output.lines() {
+        if line.trim() == "---" {
+            sections.push(String::new());
+        } else if let Some(section) = sections.last_mut() {
+            section.push_str(line);
+            section.push('\n');
+        }
+    }
+    let [load, memory, user, processes] = sections.as_slice() else {
+        return Err(format!("Unexpected resources output: '{}'", output.trim()));
+    };
+
+    let available_kb: u64 = memory
+        .trim()
+        .strip_prefix("MemAvailable:")
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|kb| kb.parse().ok())
+        .ok_or_else(|| format!("Unexpected /proc/meminfo output: '{}'", memory.trim()))?;
+    let user = user.trim();
+    if user.is_empty() {
+        return Err("Unexpected id output: no user".to_string());
+    }
+
+    Ok(ServerResources {
+        load: dispatch::parse_load(load)?,
+        available_mb: available_kb / 1024,
+        user: user.to_string(),
+        processes: processes.lines().filter_map(parse_process).collect(),
+    })
+}
+
+/// Parse a `ps -eo user,pcpu,comm` line
+fn parse_process(line: &str) -> Option<ProcessUsage> {
+    let mut fields = line.split_whitespace();
+    let user = fields.next()?.to_string();
+    let cpu_percent = fields.next()?.parse().ok()?;
+    let command = fields.collect::<Vec<_>>().join(" ");
+
+    Some(ProcessUsage {
+        user,
+        cpu_percent,
+        command,
+    })
+}
+
+/// Why the server is too busy for a new job (`None` = it may start)
+pub fn evaluate(resources: &ServerResources, config: &LoadGuardConfig) -> Option<String> {
+    let mut reasons = Vec::new();
+
+    if resources.load.per_core() > config.max_load_per_core {
+        reasons.push(format!(
+            "load {:.1} on {} cores (max {:.2} per core)",
+            resources.load.load_1m, resources.load.cores, config.max_load_per_core
+        ));
+    }
+
+    if resources.available_mb < config.min_free_memory_mb {
+        reasons.push(format!(
+            "{} MiB of memory available (min {} MiB)",
+            resources.available_mb, config.min_free_memory_mb
+        ));
+    }
+
+    let heavy: Vec<&ProcessUsage> = resources
+        .processes
+        .iter()
+        .filter(|p| p.user != resources.user)
+        .filter(|p| p.cpu_percent >= config.heavy_process_cpu)
+        .collect();
+    if heavy.len() > usize::try_from(config.max_heavy_processes).unwrap_or(usize::MAX) {
+        let named: Vec<String> = heavy
+            .iter()
+            .take(NAMED_PROCESSES)
+            .map(|p| format!("{} {} {:.0}%", p.user, p.command, p.cpu_percent))
+            .collect();
+        let more = heavy.len().saturating_sub(NAMED_PROCESSES);
+        reasons.push(format!(
+            "{} CPU-heavy processes of other users ({}{})",
+            heavy.len(),
+            named.join(", "),
+            if more > 0 {
+                format!(", +{more}")
+            } else {
+                String::new()
+            }
+        ));
+    }
+
+    (!reasons.is_empty()).then(|| reasons.join("; "))
+}
+
+/// Sample the server and tell why it is too busy, if it is
+///
+/// # Errors
+/// Returns error if the command fails (server unreachable) or its output is unexpected
+pub async fn check(
+    executor: &SshExecutor,
+    config: &LoadGuardConfig,
+) -> Result<Option<String>, String> {
+    let output = executor
+        .execute(SAMPLE_COMMAND)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(evaluate(&parse_resources(&output)?, config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = "32\n61.20 58.00 40.10 40/900 1234\n---\n\
+        MemAvailable:    4194304 kB\n---\nalice\n---\n\
+        bob       3150.2 gurobi_cl\n\
+        alice     1590.0 python\n\
+        carol       95.5 matlab\n\
+        root         0.3 systemd\n";
+
+    #[test]
+    fn test_parse_resources() -> Result<(), String> {
+        let resources = parse_resources(OUTPUT)?;
+        assert_eq!(resources.load.cores, 32);
+        assert_eq!(resources.available_mb, 4096);
+        assert_eq!(resources.user, "alice");
+        assert_eq!(resources.processes.len(), 4);
+        assert_eq!(resources.processes[0].command, "gurobi_cl");
+        assert!((resources.processes[2].cpu_percent - 95.5).abs() < f64::EPSILON);
+
+        assert!(parse_resources("32\n1.0 1.0 1.0 1/2 3\n").is_err());
+        assert!(parse_resources("32\n1.0 1.0 1.0 1/2 3\n---\n\n---\nalice\n---\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_evaluate_resources() -> Result<(), String> {
+        let resources = parse_resources(OUTPUT)?;
+        let mut config = LoadGuardConfig {
+            enabled: true,
+            max_load_per_core: 2.0,
+            ..LoadGuardConfig::default()
+        };
+
+        // Other users' heavy processes only (not alice's own job, nor idle ones)
+        assert_eq!(
+            evaluate(&resources, &config).as_deref(),
+            Some("2 CPU-heavy processes of other users (bob gurobi_cl 3150%, carol matlab 96%)")
+        );
+        config.max_heavy_processes = 2;
+        assert_eq!(evaluate(&resources, &config), None);
+
+        config.max_load_per_core = 0.8;
+        config.min_free_memory_mb = 8192;
+        assert_eq!(
+            evaluate(&resources, &config).as_deref(),
+            Some(
+                "load 61.2 on 32 cores (max 0.80 per core); \
+                 4096 MiB of memory available (min 8192 MiB)"
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_applies_to_interactive_backends() {
+        assert!(applies_to(ExecutionBackend::Tmux));
+        assert!(applies_to(ExecutionBackend::Local));
+        assert!(!applies_to(ExecutionBackend::Slurm));
+        assert!(!applies_to(ExecutionBackend::Sge));
+    }
+}
@@ -0,0 +1,236 @@
+//! Local execution backend
+//!
+//! Runs jobs on this machine without SSH: the wrapper, server database and
+//! job directories keep their remote layout under a local home directory, and
+//! each job runs in its own process group, signalled to stop or kill it.
+//! Queue, progress parsing and history work exactly as with a remote server.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ssh::SshExecutor;
+
+/// Options of a local server
+///
+/// ```toml
+/// [servers.local]
+/// host = "localhost"
+/// user = "alice"
+/// backend = "local"
+///
+/// [servers.local.local]
+/// home = "~/.solverpilot-local"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalConfig {
+    /// Directory used as `$HOME` by local jobs (default: the user's home)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub home: Option<String>,
+}
+
+/// Home directory of the local server (`~/` expanded)
+///
+/// # Errors
+/// Returns error if `$HOME` is not set
+pub fn home_dir(config: Option<&LocalConfig>) -> Result<PathBuf, String> {
+    let home = PathBuf::from(std::env::var("HOME").map_err(|_| "HOME is not set")?);
+    Ok(match config.and_then(|c| c.home.as_deref()) {
+        None => home,
+        Some(path) => path
+            .strip_prefix("~/")
+            .map_or_else(|| PathBuf::from(path), |rest| home.join(rest)),
+    })
+}
+
+/// Start a job command in a new process group and return its id
+///
+/// # Errors
+/// Returns error if the process cannot be spawned
+pub fn start_job(executor: &SshExecutor, command: &str) -> Result<u32, String> {
+    executor
+        .spawn_process_group(command)
+        .map_err(|e| format!("Failed to start local job: {e}"))
+}
+
+/// Parse a process group id stored as the job's backend id
+///
+/// # Errors
+/// Returns error if the id is not a process group id
+pub fn parse_pgid(backend_id: &str) -> Result<u32, String> {
+    backend_id
+        .parse()
+        .ok()
+        .filter(|&pgid| pgid > 1)
+        .ok_or_else(|| format!("Invalid process group id '{backend_id}'"))
+}
+
+/// Signal every process of a job
+///
+/// Graceful: SIGINT (the wrapper exits with 130). Otherwise SIGTERM, then
+/// SIGKILL 5 seconds later for processes that ignore it.
+///
+/// # Errors
+/// Returns error if the signal cannot be sent
+pub async fn signal(executor: &SshExecutor, pgid: u32, graceful: bool) -> Result<(), String> {
+    let cmd = if graceful {
+        format!("kill -INT -- -{pgid} 2>/dev/null || true")
+    } else {
+        format!(
+            "kill -TERM -- -{pgid} 2>/dev/null; (sleep 5; kill -KILL -- -{pgid} 2>/dev/null) >/dev/null 2>&1 &"
+        )
+    };
+    executor
+        .execute_ignore_status(&cmd)
+        .await
+        .map_err(|e| format!("Failed to signal local job: {e}"))?;
+    Ok(())
+}
+
+/// Suspend (`SIGSTOP`) or continue (`SIGCONT`) every process of a job
+///
+/// # Errors
+/// Returns error if the signal cannot be sent
+pub async fn suspend(executor: &SshExecutor, pgid: u32, suspend: bool) -> Result<(), String> {
+    let signal = if suspend { "STOP" } else { "CONT" };
+    executor
+        .execute(&format!("kill -{signal} -- -{pgid}"))
+        .await
+        .map_err(|e| format!("Failed to signal local job: {e}"))?;
+    Ok(())
+}
+
+/// Whether any process of a job is still running
+///
+/// # Errors
+/// Returns error if the check cannot be run
+pub async fn is_running(executor: &SshExecutor, pgid: u32) -> Result<bool, String> {
+    let output = executor
+        .execute_ignore_status(&format!(
+            "kill -0 -- -{pgid} 2>/dev/null && echo yes || echo no"
+        ))
+        .await
+        .map_err(|e| format!("Failed to check local job: {e}"))?;
+    Ok(output.trim() == "yes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{test_config, AppConfig, ExecutionBackend};
+    use crate::ssh::{LocalShell, SshManager};
+    use crate::wrapper::WrapperManager;
+    use std::time::Duration;
+
+    fn config() -> Result<AppConfig, toml::de::Error> {
+        let mut config = test_config("")?;
+        config.ssh.backend = ExecutionBackend::Local;
+        Ok(config)
+    }
+
+    /// Insert a queued job in the server database, as the queue does remotely
+    async fn queue_job(ssh: &SshManager, id: i64) -> Result<(), String> {
+        ssh.execute(&format!(
+            "sqlite3 ~/.solverpilot-server/server.db \"INSERT INTO jobs (id, user, benchmark_path, status, queued_at) VALUES ('{id}', 'test', 'bench.py', 'queued', datetime('now'))\""
+        ))
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Poll the server database until the job leaves `queued`/`running`
+    async fn wait_for_status(ssh: &SshManager, id: i64) -> Result<String, String> {
+        for _ in 0..100 {
+            let status = ssh
+                .execute(&format!(
+                    "sqlite3 ~/.solverpilot-server/server.db \"SELECT status FROM jobs WHERE id = '{id}'\""
+                ))
+                .await
+                .map_err(|e| e.to_string())?;
+            let status = status.trim();
+            if status == "completed" || status == "failed" {
+                return Ok(status.to_string());
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        Err(format!("Job {id} did not finish"))
+    }
+
+    #[test]
+    fn test_home_dir_and_pgid() -> Result<(), String> {
+        let home = PathBuf::from(std::env::var("HOME").map_err(|e| e.to_string())?);
+        assert_eq!(home_dir(None)?, home);
+        let config = LocalConfig {
+            home: Some("~/.solverpilot-local".to_string()),
+        };
+        assert_eq!(home_dir(Some(&config))?, home.join(".solverpilot-local"));
+
+        assert_eq!(parse_pgid("4242")?, 4242);
+        assert!(parse_pgid("1").is_err());
+        assert!(parse_pgid("solverpilot_1").is_err());
+        Ok(())
+    }
+
+    /// The whole server side (wrapper, server DB, logs, signals) without a remote host
+    #[tokio::test]
+    async fn test_run_and_stop_local_jobs() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let ssh = SshManager::local(config()?, LocalShell::new(dir.path().join("home"))?);
+        let executor = ssh.executor();
+
+        let upgrade = WrapperManager::new().ensure_server_ready(executor).await?;
+        assert!(upgrade.wrapper_deployed);
+
+        // A job running to completion, output captured in its log
+        std::fs::write(
+            dir.path().join("home/bench.py"),
+            "print('solved in 3 iterations')\n",
+        )?;
+        queue_job(&ssh, 1).await?;
+        start_job(
+            executor,
+            "mkdir -p ~/jobs; exec > ~/jobs/1.log 2>&1; ~/.solverpilot/bin/job_wrapper.sh 1 python3 ~/bench.py",
+        )?;
+        assert_eq!(wait_for_status(&ssh, 1).await?, "completed");
+        let log = std::fs::read_to_string(dir.path().join("home/jobs/1.log"))?;
+        assert!(log.contains("solved in 3 iterations"));
+
+        // A long job stopped with SIGINT: the wrapper records the failure
+        queue_job(&ssh, 2).await?;
+        let pgid = start_job(
+            executor,
+            "exec > ~/jobs/2.log 2>&1; ~/.solverpilot/bin/job_wrapper.sh 2 sleep 60",
+        )?;
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert!(is_running(executor, pgid).await?);
+
+        // Suspended at the end of an execution window, continued when the next one opens
+        suspend(executor, pgid, true).await?;
+        let mut stopped = false;
+        for _ in 0..50 {
+            let stat = ssh.execute(&format!("ps -o stat= -p {pgid}")).await?;
+            stopped = stat.trim().starts_with('T');
+            if stopped {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        assert!(stopped);
+        suspend(executor, pgid, false).await?;
+
+        signal(executor, pgid, true).await?;
+        assert_eq!(wait_for_status(&ssh, 2).await?, "failed");
+        let exit_code = ssh
+            .execute("sqlite3 ~/.solverpilot-server/server.db \"SELECT exit_code FROM jobs WHERE id = '2'\"")
+            .await?;
+        assert_eq!(exit_code.trim(), "130");
+        for _ in 0..50 {
+            if !is_running(executor, pgid).await? {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        assert!(!is_running(executor, pgid).await?);
+        Ok(())
+    }
+}
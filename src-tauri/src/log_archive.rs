@@ -0,0 +1,363 @@
+//! Local archive of finished jobs' logs
+//!
+//! When a job finishes, its full remote log (`{remote_base}/jobs/{id}.log`) is
+//! downloaded, gzip-compressed into `logs_dir()/job_{id}.log.gz` and referenced
+//! from the job (`log_archive_path`) so it stays viewable offline. The `[logs]`
+//! config section sets the retention (age and total size) and whether the
+//! remote copy is deleted once archived.
+
+use crate::config::{AppConfig, LogsConfig};
+use crate::db;
+use crate::paths;
+use crate::ssh::SshManager;
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::borrow::Cow;
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Largest part of a log kept in the search index (its start and its end)
+const MAX_INDEXED_LOG_BYTES: usize = 4 * 1024 * 1024;
+
+/// Archived log of a job
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchivedLog {
+    pub job_id: i64,
+    pub path: String,
+    /// Compressed size in bytes
+    pub size: u64,
+    /// RFC 3339 timestamp
+    pub archived_at: String,
+}
+
+/// Outcome of a retention pass
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RetentionReport {
+    pub deleted: usize,
+    pub freed_bytes: u64,
+    pub remaining_bytes: u64,
+}
+
+/// Gzip `content` into `dest` (written to a temp file, then renamed); returns the compressed size
+pub fn compress_to(content: &[u8], dest: &Path) -> Result<u64, String> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+
+    let tmp = dest.with_extension("gz.tmp");
+    let file = std::fs::File::create(&tmp)
+        .map_err(|e| format!("Failed to create {}: {e}", tmp.display()))?;
+    let mut encoder = GzEncoder::new(file, Compression::default());
+    encoder
+        .write_all(content)
+        .and_then(|()| encoder.finish())
+        .map_err(|e| format!("Failed to compress log: {e}"))?;
+
+    std::fs::rename(&tmp, dest)
+        .map_err(|e| format!("Failed to move log archive to {}: {e}", dest.display()))?;
+
+    std::fs::metadata(dest)
+        .map(|m| m.len())
+        .map_err(|e| format!("Failed to stat {}: {e}", dest.display()))
+}
+
+/// Decompress an archived log (invalid UTF-8 replaced)
+pub fn read_archive(path: &Path) -> Result<String, String> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| format!("Log archive {} unavailable: {e}", path.display()))?;
+    let mut content = Vec::new();
+    GzDecoder::new(file)
+        .read_to_end(&mut content)
+        .map_err(|e| format!("Corrupted log archive {}: {e}", path.display()))?;
+
+    Ok(String::from_utf8_lossy(&content).into_owned())
+}
+
+/// Log text to index: whole when small, otherwise its first and last 2 MiB
+pub fn search_excerpt(log: &str) -> Cow<'_, str> {
+    if log.len() <= MAX_INDEXED_LOG_BYTES {
+        return Cow::Borrowed(log);
+    }
+
+    let half = MAX_INDEXED_LOG_BYTES / 2;
+    let mut head_end = half;
+    while !log.is_char_boundary(head_end) {
+        head_end -= 1;
+    }
+    let mut tail_start = log.len() - half;
+    while !log.is_char_boundary(tail_start) {
+        tail_start += 1;
+    }
+
+    Cow::Owned(format!(
+        "{}\n[...]\n{}",
+        &log[..head_end],
+        &log[tail_start..]
+    ))
+}
+
+/// Archives to delete: older than `retention_days`, then the oldest ones until
+/// the total fits in `max_total_bytes` (0 disables either rule)
+///
+/// `archives` must be ordered oldest first.
+pub fn select_expired(
+    archives: &[ArchivedLog],
+    now: DateTime<Utc>,
+    retention_days: u32,
+    max_total_bytes: u64,
+) -> Vec<&ArchivedLog> {
+    let too_old = |archive: &ArchivedLog| {
+        retention_days > 0
+            && DateTime::parse_from_rfc3339(&archive.archived_at).is_ok_and(|at| {
+                now.signed_duration_since(at) > chrono::Duration::days(i64::from(retention_days))
+            })
+    };
+
+    let mut total: u64 = archives.iter().map(|a| a.size).sum();
+    let mut expired = Vec::new();
+    for archive in archives {
+        let over_size = max_total_bytes > 0 && total > max_total_bytes;
+        if !over_size && !too_old(archive) {
+            continue;
+        }
+        total = total.saturating_sub(archive.size);
+        expired.push(archive);
+    }
+
+    expired
+}
+
+/// Delete archives beyond the configured retention and unlink them from their jobs
+pub async fn enforce_retention(
+    pool: &SqlitePool,
+    config: &LogsConfig,
+) -> Result<RetentionReport, String> {
+    let archives = db::list_log_archives(pool).await?;
+    let expired = select_expired(
+        &archives,
+        Utc::now(),
+        config.retention_days,
+        config.max_total_mb.saturating_mul(1024 * 1024),
+    );
+
+    let mut report = RetentionReport {
+        remaining_bytes: archives.iter().map(|a| a.size).sum(),
+        ..RetentionReport::default()
+    };
+    for archive in expired {
+        match std::fs::remove_file(&archive.path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                tracing::warn!("Failed to delete log archive {}: {}", archive.path, e);
+                continue;
+            }
+        }
+        db::clear_job_log_archive(pool, archive.job_id).await?;
+        report.deleted += 1;
+        report.freed_bytes += archive.size;
+        report.remaining_bytes = report.remaining_bytes.saturating_sub(archive.size);
+    }
+
+    if report.deleted > 0 {
+        tracing::info!(
+            "Log retention: deleted {} archives ({} bytes freed)",
+            report.deleted,
+            report.freed_bytes
+        );
+    }
+    Ok(report)
+}
+
+/// Download a job's full log, compress it locally and record it on the job
+///
+/// With `prune_remote`, the server copy is deleted once its size matches the
+/// downloaded one.
+pub async fn archive_job_log(
+    pool: &SqlitePool,
+    ssh: &SshManager,
+    config: &AppConfig,
+    job_id: i64,
+) -> Result<ArchivedLog, String> {
+    let remote_path = format!("{}/{job_id}.log", config.remote_jobs_path());
+    let logs_dir = paths::logs_dir()?;
+    std::fs::create_dir_all(&logs_dir)
+        .map_err(|e| format!("Failed to create {}: {e}", logs_dir.display()))?;
+
+    let download = logs_dir.join(format!("job_{job_id}.log.part"));
+    ssh.transfer()
+        .rsync_from_server(&remote_path, &download.display().to_string())
+        .await
+        .map_err(|e| format!("Failed to download log of job {job_id}: {e}"))?;
+    let content = std::fs::read(&download);
+    let _ = std::fs::remove_file(&download);
+    let content = content.map_err(|e| format!("Failed to read downloaded log: {e}"))?;
+
+    let dest = logs_dir.join(format!("job_{job_id}.log.gz"));
+    let size = compress_to(&content, &dest)?;
+    let path = dest.display().to_string();
+    db::set_job_log_archive(pool, job_id, &path, size).await?;
+    let text = String::from_utf8_lossy(&content);
+    if let Err(e) = db::index_job_search(pool, job_id, Some(&search_excerpt(&text))).await {
+        tracing::warn!("Log of job {} not indexed for search: {}", job_id, e);
+    }
+    tracing::info!(
+        "Archived log of job {} ({} bytes, {} compressed)",
+        job_id,
+        content.len(),
+        size
+    );
+
+    if config.logs.prune_remote {
+        let remote_size = ssh
+            .execute(&format!("stat -c %s {remote_path}"))
+            .await
+            .ok()
+            .and_then(|out| out.trim().parse::<usize>().ok());
+        if remote_size == Some(content.len()) {
+            if let Err(e) = ssh.execute(&format!("rm -f {remote_path}")).await {
+                tracing::warn!("Failed to prune remote log of job {}: {}", job_id, e);
+            }
+        } else {
+            tracing::warn!(
+                "Remote log of job {} changed since download, not pruned",
+                job_id
+            );
+        }
+    }
+
+    Ok(ArchivedLog {
+        job_id,
+        path,
+        size,
+        archived_at: Utc::now().to_rfc3339(),
+    })
+}
+
+/// Archive a finished job's log and apply retention when enabled, then index the
+/// job for search (failures only logged)
+pub async fn archive_finished_job(
+    pool: &SqlitePool,
+    ssh: &SshManager,
+    config: &AppConfig,
+    job_id: i64,
+) {
+    let archived = if config.logs.archive {
+        archive_job_log(pool, ssh, config, job_id)
+            .await
+            .map_err(|e| tracing::warn!("Log of job {} not archived: {}", job_id, e))
+            .is_ok()
+    } else {
+        false
+    };
+
+    // Without an archive, the stored log tail is indexed instead
+    if !archived {
+        if let Err(e) = db::index_job_search(pool, job_id, None).await {
+            tracing::warn!("Job {} not indexed for search: {}", job_id, e);
+        }
+        return;
+    }
+    if let Err(e) = enforce_retention(pool, &config.logs).await {
+        tracing::warn!("Log retention failed: {}", e);
+    }
+}
+
+/// Archived log of a job (`None` if it was never archived or was pruned)
+pub async fn load_archived_log(pool: &SqlitePool, job_id: i64) -> Result<Option<String>, String> {
+    db::get_job_log_archive(pool, job_id)
+        .await?
+        .map(|path| read_archive(Path::new(&path)))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn archive(job_id: i64, size: u64, archived_at: &str) -> ArchivedLog {
+        ArchivedLog {
+            job_id,
+            path: format!("job_{job_id}.log.gz"),
+            size,
+            archived_at: archived_at.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_compress_roundtrip() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let dest = dir.path().join("nested").join("job_1.log.gz");
+        let log = "[1/3] instance_a\n".repeat(1000);
+
+        let size = compress_to(log.as_bytes(), &dest)?;
+        assert!(size > 0 && size < log.len() as u64);
+        assert!(!dest.with_extension("gz.tmp").exists());
+        assert_eq!(read_archive(&dest)?, log);
+        assert!(read_archive(&dir.path().join("missing.gz")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_excerpt_keeps_head_and_tail() {
+        let small = "Model is infeasible\n";
+        assert!(matches!(search_excerpt(small), Cow::Borrowed(_)));
+
+        let log = format!(
+            "instance N12\n{}Model is infeasible\n",
+            "é".repeat(MAX_INDEXED_LOG_BYTES)
+        );
+        let excerpt = search_excerpt(&log);
+        assert!(excerpt.len() <= MAX_INDEXED_LOG_BYTES + 8);
+        assert!(excerpt.starts_with("instance N12"));
+        assert!(excerpt.ends_with("Model is infeasible\n"));
+        assert!(excerpt.contains("[...]"));
+    }
+
+    #[test]
+    fn test_select_expired_by_age() -> Result<(), Box<dyn std::error::Error>> {
+        let now = DateTime::parse_from_rfc3339("2026-03-01T00:00:00Z")?.with_timezone(&Utc);
+        let archives = vec![
+            archive(1, 10, "2025-11-01T00:00:00Z"),
+            archive(2, 10, "2026-02-20T00:00:00Z"),
+            archive(3, 10, "not a date"),
+        ];
+
+        let expired: Vec<i64> = select_expired(&archives, now, 30, 0)
+            .iter()
+            .map(|a| a.job_id)
+            .collect();
+        assert_eq!(expired, vec![1]);
+        assert!(select_expired(&archives, now, 0, 0).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_select_expired_by_total_size() -> Result<(), Box<dyn std::error::Error>> {
+        let now = DateTime::parse_from_rfc3339("2026-03-01T00:00:00Z")?.with_timezone(&Utc);
+        let archives = vec![
+            archive(1, 400, "2026-02-01T00:00:00Z"),
+            archive(2, 300, "2026-02-02T00:00:00Z"),
+            archive(3, 500, "2026-02-03T00:00:00Z"),
+        ];
+
+        // 1200 bytes over a 900 cap: the oldest archive goes first
+        let expired: Vec<i64> = select_expired(&archives, now, 0, 900)
+            .iter()
+            .map(|a| a.job_id)
+            .collect();
+        assert_eq!(expired, vec![1]);
+
+        let expired: Vec<i64> = select_expired(&archives, now, 0, 500)
+            .iter()
+            .map(|a| a.job_id)
+            .collect();
+        assert_eq!(expired, vec![1, 2]);
+        Ok(())
+    }
+}
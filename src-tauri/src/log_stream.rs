@@ -0,0 +1,690 @@
+//! Live job logs over a persistent SSH channel
+//!
+//! Instead of running `tail -n` on every poll, one `tail -F` per job runs on a
+//! dedicated SSH connection (`SshPool::dedicated`) and its output is shared by
+//! every subscriber of that job:
+//! - each subscriber receives incremental chunks (`job-log-chunk` events) tagged
+//!   with their byte offset in the log file, batched every 100 ms
+//! - a subscriber that falls behind catches up from the last 64 KiB kept in
+//!   memory; older bytes are skipped and the chunk is flagged `reset`
+//! - a dropped connection is reopened with backoff and resumes at the last offset
+//! - the stream stops when its last subscriber leaves
+//!
+//! With the local backend, `tail -F` runs as a child process instead.
+
+use crate::ssh::{LocalShell, SshManager, SshPool};
+use russh::ChannelMsg;
+use serde::Serialize;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError};
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::sync::{broadcast, Mutex};
+use tokio::task::JoinHandle;
+
+/// Tauri event carrying a `LogChunk`
+pub const LOG_CHUNK_EVENT: &str = "job-log-chunk";
+
+/// Bytes kept per stream for late or lagging subscribers and status polls
+const RECENT_BYTES: usize = 64 * 1024;
+/// Largest chunk sent to a subscriber at once
+const MAX_CHUNK_BYTES: usize = 64 * 1024;
+/// Output is batched for this long before being sent
+const FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+/// Reads buffered per subscriber before it lags
+const CHANNEL_CAPACITY: usize = 256;
+const RECONNECT_MIN: Duration = Duration::from_secs(1);
+const RECONNECT_MAX: Duration = Duration::from_secs(30);
+
+/// Incremental log output sent to one subscriber
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LogChunk {
+    pub subscription_id: u64,
+    pub job_id: i64,
+    /// Byte offset of `data` in the log file
+    pub offset: u64,
+    /// Offset following `data` (pass it to resume a later subscription)
+    pub next_offset: u64,
+    pub data: String,
+    /// Output before `offset` was skipped (lagging subscriber or truncated file)
+    pub reset: bool,
+}
+
+/// Output read by the `tail -F` task
+#[derive(Debug, Clone)]
+enum StreamEvent {
+    Data { offset: u64, data: Arc<[u8]> },
+    Truncated,
+}
+
+/// Last bytes of a log, with the offset of the first one
+#[derive(Debug)]
+struct RecentLog {
+    start: u64,
+    bytes: VecDeque<u8>,
+}
+
+impl RecentLog {
+    const fn new(start: u64) -> Self {
+        Self {
+            start,
+            bytes: VecDeque::new(),
+        }
+    }
+
+    fn end(&self) -> u64 {
+        self.start + self.bytes.len() as u64
+    }
+
+    fn reset(&mut self, offset: u64) {
+        self.start = offset;
+        self.bytes.clear();
+    }
+
+    fn append(&mut self, offset: u64, data: &[u8]) {
+        if offset != self.end() {
+            self.reset(offset);
+        }
+        self.bytes.extend(data);
+
+        let excess = self.bytes.len().saturating_sub(RECENT_BYTES);
+        self.bytes.drain(..excess);
+        self.start += excess as u64;
+    }
+
+    /// Bytes from `offset` (clamped to the kept range) with their actual offset
+    fn slice_from(&self, offset: u64) -> (u64, Vec<u8>) {
+        let from = offset.clamp(self.start, self.end());
+        let skip = usize::try_from(from - self.start).unwrap_or(usize::MAX);
+        (from, self.bytes.iter().skip(skip).copied().collect())
+    }
+}
+
+type SharedRecent = Arc<std::sync::Mutex<RecentLog>>;
+
+fn lock_recent(recent: &SharedRecent) -> std::sync::MutexGuard<'_, RecentLog> {
+    recent.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Bytes waiting to be sent to one subscriber
+#[derive(Debug)]
+struct Outbox {
+    /// Offset of the next expected byte
+    next: u64,
+    buf: Vec<u8>,
+    reset: bool,
+}
+
+impl Outbox {
+    const fn new(next: u64) -> Self {
+        Self {
+            next,
+            buf: Vec::new(),
+            reset: false,
+        }
+    }
+
+    /// Bytes starting at `offset` would leave a hole
+    const fn is_gap(&self, offset: u64) -> bool {
+        offset > self.next
+    }
+
+    /// Queue bytes read at `offset`, ignoring those already queued
+    fn push(&mut self, offset: u64, data: &[u8]) {
+        if offset > self.next {
+            self.next = offset;
+            self.reset = true;
+        }
+        let end = offset + data.len() as u64;
+        if end <= self.next {
+            return;
+        }
+        let skip = usize::try_from(self.next - offset).unwrap_or(usize::MAX);
+        self.buf.extend_from_slice(&data[skip..]);
+        self.next = end;
+    }
+
+    /// Continue from `offset` after a truncation (earlier output no longer exists)
+    fn restart_at(&mut self, offset: u64) {
+        self.buf.clear();
+        self.next = offset;
+        self.reset = true;
+    }
+
+    /// Queued text as `(offset, data, reset)`; an incomplete UTF-8 sequence at the
+    /// end is kept for the next read unless `force`
+    fn take(&mut self, force: bool) -> Option<(u64, String, bool)> {
+        let len = if force {
+            self.buf.len()
+        } else {
+            match std::str::from_utf8(&self.buf) {
+                Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                _ => self.buf.len(),
+            }
+        };
+        if len == 0 {
+            return None;
+        }
+
+        let offset = self.next - self.buf.len() as u64;
+        let data = String::from_utf8_lossy(&self.buf[..len]).into_owned();
+        self.buf.drain(..len);
+        let reset = std::mem::take(&mut self.reset);
+        Some((offset, data, reset))
+    }
+}
+
+/// Remote command streaming a log from `offset` (follows rotation and truncation)
+fn tail_command(log_path: &str, offset: u64) -> String {
+    format!("tail -c +{} -F {log_path}", offset + 1)
+}
+
+/// Where `tail -F` runs
+enum TailSource {
+    Ssh(SshPool),
+    Local(LocalShell),
+}
+
+/// Run `tail -F` on a dedicated connection (or locally), reconnecting until aborted
+async fn run_stream(
+    source: TailSource,
+    log_path: String,
+    start: u64,
+    sender: broadcast::Sender<StreamEvent>,
+    recent: SharedRecent,
+) {
+    let mut offset = start;
+    let mut backoff = RECONNECT_MIN;
+
+    loop {
+        let before = offset;
+        let result = match source {
+            TailSource::Ssh(ref pool) => {
+                tail_once(pool, &log_path, &mut offset, &sender, &recent).await
+            }
+            TailSource::Local(ref shell) => {
+                tail_local(shell, &log_path, &mut offset, &sender, &recent).await
+            }
+        };
+        if let Err(e) = result {
+            tracing::warn!("Log stream for {} interrupted: {}", log_path, e);
+        }
+        if offset != before {
+            backoff = RECONNECT_MIN;
+        }
+
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(RECONNECT_MAX);
+        tracing::debug!("Resuming log stream for {} at byte {}", log_path, offset);
+    }
+}
+
+/// One `tail -F` session; returns when the channel closes
+async fn tail_once(
+    pool: &SshPool,
+    log_path: &str,
+    offset: &mut u64,
+    sender: &broadcast::Sender<StreamEvent>,
+    recent: &SharedRecent,
+) -> Result<(), String> {
+    let conn = pool.dedicated().await.map_err(|e| e.to_string())?;
+    let mut channel = conn
+        .channel_open_session()
+        .await
+        .map_err(|e| format!("Failed to open log channel: {e}"))?;
+    channel
+        .exec(true, tail_command(log_path, *offset))
+        .await
+        .map_err(|e| format!("Failed to start tail: {e}"))?;
+
+    while let Some(msg) = channel.wait().await {
+        match msg {
+            ChannelMsg::Data { data } => on_output(&data, offset, sender, recent),
+            ChannelMsg::ExtendedData { data, ext: 1 } => {
+                on_error(&data, log_path, offset, sender, recent);
+            }
+            ChannelMsg::Close | ChannelMsg::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// One local `tail -F` process; returns when it exits
+async fn tail_local(
+    shell: &LocalShell,
+    log_path: &str,
+    offset: &mut u64,
+    sender: &broadcast::Sender<StreamEvent>,
+    recent: &SharedRecent,
+) -> Result<(), String> {
+    let mut child = shell
+        .command(&tail_command(log_path, *offset))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to start tail: {e}"))?;
+    let (Some(mut stdout), Some(mut stderr)) = (child.stdout.take(), child.stderr.take()) else {
+        return Err("tail output not captured".to_string());
+    };
+
+    let mut out = vec![0u8; MAX_CHUNK_BYTES];
+    let mut err = vec![0u8; 4096];
+    let mut stderr_open = true;
+    loop {
+        tokio::select! {
+            read = stdout.read(&mut out) => match read {
+                Ok(0) => break,
+                Ok(n) => on_output(&out[..n], offset, sender, recent),
+                Err(e) => return Err(format!("Failed to read tail output: {e}")),
+            },
+            read = stderr.read(&mut err), if stderr_open => match read {
+                Ok(n) if n > 0 => on_error(&err[..n], log_path, offset, sender, recent),
+                _ => stderr_open = false,
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Log bytes read by `tail`
+fn on_output(
+    data: &[u8],
+    offset: &mut u64,
+    sender: &broadcast::Sender<StreamEvent>,
+    recent: &SharedRecent,
+) {
+    lock_recent(recent).append(*offset, data);
+    // No receiver is fine: subscribers catch up from `recent`
+    let _ = sender.send(StreamEvent::Data {
+        offset: *offset,
+        data: Arc::from(data),
+    });
+    *offset += data.len() as u64;
+}
+
+/// `tail` diagnostics: a truncated file restarts the stream at 0
+fn on_error(
+    data: &[u8],
+    log_path: &str,
+    offset: &mut u64,
+    sender: &broadcast::Sender<StreamEvent>,
+    recent: &SharedRecent,
+) {
+    let message = String::from_utf8_lossy(data);
+    if message.contains("file truncated") {
+        *offset = 0;
+        lock_recent(recent).reset(0);
+        let _ = sender.send(StreamEvent::Truncated);
+    } else {
+        tracing::debug!("tail {}: {}", log_path, message.trim());
+    }
+}
+
+/// First offset of a new stream: `from_offset`, or the last 64 KiB of the log
+async fn stream_start(
+    ssh: &SshManager,
+    log_path: &str,
+    from_offset: Option<u64>,
+) -> Result<u64, String> {
+    if let Some(offset) = from_offset {
+        return Ok(offset);
+    }
+
+    let size = ssh
+        .executor()
+        .execute_ignore_status(&format!("stat -c %s {log_path} 2>/dev/null"))
+        .await
+        .map_err(|e| format!("Failed to read log size: {e}"))?;
+
+    Ok(size
+        .trim()
+        .parse::<u64>()
+        .unwrap_or(0)
+        .saturating_sub(RECENT_BYTES as u64))
+}
+
+/// Send a subscriber its share of the stream, batched, until aborted
+async fn forward<F>(
+    subscription_id: u64,
+    job_id: i64,
+    from_offset: Option<u64>,
+    mut receiver: broadcast::Receiver<StreamEvent>,
+    recent: SharedRecent,
+    emit: F,
+) where
+    F: Fn(LogChunk) + Send + Sync + 'static,
+{
+    let flush = |outbox: &mut Outbox, force: bool| {
+        while let Some((offset, data, reset)) = outbox.take(force) {
+            emit(LogChunk {
+                subscription_id,
+                job_id,
+                offset,
+                next_offset: outbox.next - outbox.buf.len() as u64,
+                data,
+                reset,
+            });
+        }
+    };
+    let replay = |outbox: &mut Outbox| {
+        let (offset, data) = lock_recent(&recent).slice_from(outbox.next);
+        if offset > outbox.next || !data.is_empty() {
+            outbox.push(offset, &data);
+        }
+    };
+
+    let (start, end) = {
+        let recent = lock_recent(&recent);
+        (recent.start, recent.end())
+    };
+    let mut outbox = Outbox::new(from_offset.unwrap_or(start).min(end));
+    outbox.reset = from_offset.is_some_and(|o| o > end);
+    replay(&mut outbox);
+
+    let mut ticker = tokio::time::interval(FLUSH_INTERVAL);
+    loop {
+        tokio::select! {
+            event = receiver.recv() => match event {
+                Ok(StreamEvent::Data { offset, data }) => {
+                    if outbox.is_gap(offset) {
+                        flush(&mut outbox, true);
+                    }
+                    outbox.push(offset, &data);
+                    if outbox.buf.len() >= MAX_CHUNK_BYTES {
+                        flush(&mut outbox, false);
+                    }
+                }
+                Ok(StreamEvent::Truncated) => {
+                    flush(&mut outbox, true);
+                    outbox.restart_at(0);
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::debug!("Log subscriber {subscription_id} lagged by {skipped} reads");
+                    flush(&mut outbox, true);
+                    replay(&mut outbox);
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    flush(&mut outbox, true);
+                    break;
+                }
+            },
+            _ = ticker.tick() => flush(&mut outbox, false),
+        }
+    }
+}
+
+/// Active `tail -F` of one job
+struct JobStream {
+    sender: broadcast::Sender<StreamEvent>,
+    recent: SharedRecent,
+    subscribers: usize,
+    task: JoinHandle<()>,
+}
+
+struct Subscription {
+    job_id: i64,
+    forwarder: JoinHandle<()>,
+}
+
+#[derive(Default)]
+struct Streams {
+    jobs: HashMap<i64, JobStream>,
+    subscriptions: HashMap<u64, Subscription>,
+}
+
+/// Log streams shared by all subscribers (one per job)
+#[derive(Default)]
+pub struct LogStreams {
+    inner: Mutex<Streams>,
+    next_id: AtomicU64,
+}
+
+impl LogStreams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Subscribe to a job's log, starting at `from_offset` (default: the last 64 KiB)
+    ///
+    /// Chunks are passed to `emit` until `unsubscribe` is called with the returned id.
+    pub async fn subscribe<F>(
+        &self,
+        ssh: &SshManager,
+        job_id: i64,
+        log_path: String,
+        from_offset: Option<u64>,
+        emit: F,
+    ) -> Result<u64, String>
+    where
+        F: Fn(LogChunk) + Send + Sync + 'static,
+    {
+        let mut streams = self.inner.lock().await;
+
+        let stream = match streams.jobs.entry(job_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let start = stream_start(ssh, &log_path, from_offset).await?;
+                let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+                let recent = Arc::new(std::sync::Mutex::new(RecentLog::new(start)));
+                let source = match (ssh.pool(), ssh.executor().local_shell()) {
+                    (Some(pool), _) => TailSource::Ssh(pool.clone()),
+                    (None, Some(shell)) => TailSource::Local(shell.clone()),
+                    (None, None) => return Err("No connection to stream logs from".to_string()),
+                };
+                let task = tokio::spawn(run_stream(
+                    source,
+                    log_path,
+                    start,
+                    sender.clone(),
+                    Arc::clone(&recent),
+                ));
+                entry.insert(JobStream {
+                    sender,
+                    recent,
+                    subscribers: 0,
+                    task,
+                })
+            }
+        };
+        stream.subscribers += 1;
+        let receiver = stream.sender.subscribe();
+        let recent = Arc::clone(&stream.recent);
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let forwarder = tokio::spawn(forward(id, job_id, from_offset, receiver, recent, emit));
+        streams
+            .subscriptions
+            .insert(id, Subscription { job_id, forwarder });
+        drop(streams);
+
+        tracing::debug!("Log subscriber {id} attached to job {job_id}");
+        Ok(id)
+    }
+
+    /// Stop a subscription (the job's stream stops with its last subscriber)
+    pub async fn unsubscribe(&self, subscription_id: u64) -> Result<(), String> {
+        let mut streams = self.inner.lock().await;
+        let subscription = streams
+            .subscriptions
+            .remove(&subscription_id)
+            .ok_or_else(|| format!("Log subscription {subscription_id} not found"))?;
+        subscription.forwarder.abort();
+
+        if let Some(stream) = streams.jobs.get_mut(&subscription.job_id) {
+            stream.subscribers = stream.subscribers.saturating_sub(1);
+            if stream.subscribers == 0 {
+                stream.task.abort();
+                streams.jobs.remove(&subscription.job_id);
+                tracing::debug!("Log stream of job {} stopped", subscription.job_id);
+            }
+        }
+        drop(streams);
+
+        Ok(())
+    }
+
+    /// Stop every stream (SSH connection closed)
+    pub async fn stop_all(&self) {
+        let mut streams = self.inner.lock().await;
+        for (_, subscription) in streams.subscriptions.drain() {
+            subscription.forwarder.abort();
+        }
+        for (_, stream) in streams.jobs.drain() {
+            stream.task.abort();
+        }
+    }
+
+    /// Last `lines` lines of a streamed job's log (`None` when not streamed)
+    pub async fn recent_lines(&self, job_id: i64, lines: usize) -> Option<String> {
+        let streams = self.inner.lock().await;
+        let stream = streams.jobs.get(&job_id)?;
+        let bytes: Vec<u8> = lock_recent(&stream.recent).bytes.iter().copied().collect();
+        drop(streams);
+
+        let text = String::from_utf8_lossy(&bytes);
+        let all: Vec<&str> = text.lines().collect();
+        Some(all[all.len().saturating_sub(lines)..].join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_log_keeps_tail() {
+        let mut recent = RecentLog::new(10);
+        recent.append(10, b"hello ");
+        recent.append(16, b"world");
+        assert_eq!(recent.end(), 21);
+        assert_eq!(recent.slice_from(16), (16, b"world".to_vec()));
+        assert_eq!(recent.slice_from(0), (10, b"hello world".to_vec()));
+
+        recent.append(21, &vec![b'x'; RECENT_BYTES]);
+        assert_eq!(recent.start, 21);
+        assert_eq!(recent.bytes.len(), RECENT_BYTES);
+
+        // Non-contiguous append (reconnect at another offset) restarts the buffer
+        recent.append(5, b"abc");
+        assert_eq!((recent.start, recent.end()), (5, 8));
+    }
+
+    #[test]
+    fn test_outbox_dedup_gap_and_utf8() {
+        let mut outbox = Outbox::new(4);
+        outbox.push(0, b"abcdef");
+        outbox.push(4, b"ef");
+        assert_eq!(outbox.take(false), Some((4, "ef".to_string(), false)));
+
+        // "é" split across two reads
+        outbox.push(6, &[b'x', 0xC3]);
+        assert_eq!(outbox.take(false), Some((6, "x".to_string(), false)));
+        outbox.push(8, &[0xA9]);
+        assert_eq!(outbox.take(false), Some((7, "é".to_string(), false)));
+
+        outbox.push(20, b"later");
+        assert_eq!(outbox.take(false), Some((20, "later".to_string(), true)));
+        assert_eq!(outbox.take(true), None);
+    }
+
+    #[test]
+    fn test_tail_command() {
+        assert_eq!(
+            tail_command("~/jobs/7.log", 0),
+            "tail -c +1 -F ~/jobs/7.log"
+        );
+        assert_eq!(
+            tail_command("~/jobs/7.log", 1024),
+            "tail -c +1025 -F ~/jobs/7.log"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_forward_replays_and_batches() -> Result<(), Box<dyn std::error::Error>> {
+        let recent = Arc::new(std::sync::Mutex::new(RecentLog::new(0)));
+        lock_recent(&recent).append(0, b"line 1\n");
+        let (sender, receiver) = broadcast::channel(CHANNEL_CAPACITY);
+        let (chunks_tx, mut chunks_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let forwarder = tokio::spawn(forward(
+            1,
+            42,
+            None,
+            receiver,
+            Arc::clone(&recent),
+            move |c| {
+                let _ = chunks_tx.send(c);
+            },
+        ));
+
+        // Already replayed from the buffer: ignored
+        sender.send(StreamEvent::Data {
+            offset: 0,
+            data: Arc::from(&b"line 1\n"[..]),
+        })?;
+        sender.send(StreamEvent::Data {
+            offset: 7,
+            data: Arc::from(&b"line 2\n"[..]),
+        })?;
+        drop(sender);
+        forwarder.await?;
+
+        let mut text = String::new();
+        while let Ok(chunk) = chunks_rx.try_recv() {
+            assert_eq!(chunk.offset, text.len() as u64);
+            assert!(!chunk.reset);
+            text.push_str(&chunk.data);
+            assert_eq!(chunk.next_offset, text.len() as u64);
+        }
+        assert_eq!(text, "line 1\nline 2\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_forward_lagging_subscriber_resets() -> Result<(), Box<dyn std::error::Error>> {
+        let recent = Arc::new(std::sync::Mutex::new(RecentLog::new(0)));
+        let (sender, receiver) = broadcast::channel(2);
+        let (chunks_tx, mut chunks_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        // More reads than the channel holds, and more bytes than the buffer keeps
+        let read = vec![b'a'; RECENT_BYTES / 2];
+        for i in 0..4u64 {
+            let offset = i * read.len() as u64;
+            lock_recent(&recent).append(offset, &read);
+            sender.send(StreamEvent::Data {
+                offset,
+                data: Arc::from(&read[..]),
+            })?;
+        }
+
+        let forwarder = tokio::spawn(forward(
+            2,
+            42,
+            Some(0),
+            receiver,
+            Arc::clone(&recent),
+            move |c| {
+                let _ = chunks_tx.send(c);
+            },
+        ));
+        drop(sender);
+        forwarder.await?;
+
+        let first = chunks_rx.try_recv()?;
+        assert!(first.reset);
+        assert_eq!(first.offset, RECENT_BYTES as u64);
+        let total: usize = std::iter::once(first)
+            .chain(std::iter::from_fn(|| chunks_rx.try_recv().ok()))
+            .map(|c| c.data.len())
+            .sum();
+        assert_eq!(total, RECENT_BYTES);
+        Ok(())
+    }
+}
@@ -0,0 +1,360 @@
+//! Versioned migrations of the local database (`solver-pilot.db`)
+//!
+//! The schema version is stored as `schema_version` in the `metadata` table.
+//! On startup, every migration above it runs in order, each in its own
+//! transaction together with the version bump, so an interrupted upgrade
+//! resumes from the last completed step. Before migrating an existing database
+//! a copy is written next to it (`VACUUM INTO`), and a database written by a
+//! newer release is refused rather than opened.
+//!
+//! Databases created before versioning have no `schema_version`: they start at
+//! 0 and replay every migration, which is why the steps stay idempotent.
+
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use sqlx::{sqlite::SqlitePool, Row, SqliteConnection};
+
+use crate::db;
+
+/// Schema version written by this build (the last migration)
+pub const SCHEMA_VERSION: i64 = 17;
+
+/// One forward migration
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+}
+
+/// All migrations, oldest first (append only: never renumber or edit a shipped one)
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "projects, benchmarks and jobs tables",
+    },
+    Migration {
+        version: 2,
+        description: "queue columns on jobs",
+    },
+    Migration {
+        version: 3,
+        description: "environment profile columns",
+    },
+    Migration {
+        version: 4,
+        description: "job code version",
+    },
+    Migration {
+        version: 5,
+        description: "job environment fingerprint",
+    },
+    Migration {
+        version: 6,
+        description: "job resource usage",
+    },
+    Migration {
+        version: 7,
+        description: "benchmark limits",
+    },
+    Migration {
+        version: 8,
+        description: "job log archive columns",
+    },
+    Migration {
+        version: 9,
+        description: "job tags and notes",
+    },
+    Migration {
+        version: 10,
+        description: "ingested results tables",
+    },
+    Migration {
+        version: 11,
+        description: "history indexes",
+    },
+    Migration {
+        version: 12,
+        description: "full-text search index",
+    },
+    Migration {
+        version: 13,
+        description: "job target server",
+    },
+    Migration {
+        version: 14,
+        description: "benchmark server constraints",
+    },
+    Migration {
+        version: 15,
+        description: "batch scheduler job ids",
+    },
+    Migration {
+        version: 16,
+        description: "container settings",
+    },
+    Migration {
+        version: 17,
+        description: "job start times",
+    },
+];
+
+/// Apply the body of migration `version`
+async fn apply(conn: &mut SqliteConnection, version: i64) -> Result<(), String> {
+    match version {
+        1 => db::create_base_tables(conn).await,
+        2 => db::migrate_queue_columns(conn).await,
+        3 => db::migrate_env_profile_columns(conn).await,
+        4 => db::migrate_code_version_column(conn).await,
+        5 => db::migrate_env_fingerprint_column(conn).await,
+        6 => db::migrate_resource_usage_columns(conn).await,
+        7 => db::migrate_benchmark_limits_column(conn).await,
+        8 => db::migrate_log_archive_columns(conn).await,
+        9 => db::migrate_tags_and_notes(conn).await,
+        10 => db::create_results_tables(conn).await,
+        11 => db::create_jobs_indexes(conn).await,
+        12 => db::create_search_index(conn).await,
+        13 => db::migrate_job_server_column(conn).await,
+        14 => db::migrate_benchmark_servers_column(conn).await,
+        15 => db::migrate_job_backend_id_column(conn).await,
+        16 => db::migrate_container_columns(conn).await,
+        17 => db::migrate_not_before_column(conn).await,
+        _ => Err(format!("Unknown schema migration v{version}")),
+    }
+}
+
+/// Bring the database to `SCHEMA_VERSION`
+///
+/// `db_path` is the database file, backed up before migrating an existing
+/// database (`None` for in-memory databases). Returns the version found.
+///
+/// # Errors
+/// Returns error if the database is newer than this build, or if the backup or
+/// a migration fails (that migration is rolled back)
+pub async fn run(pool: &SqlitePool, db_path: Option<&Path>) -> Result<i64, String> {
+    // Table des métadonnées (queue state persistence - Story 2.5, schema_version)
+    sqlx::query(
+        r"
+        CREATE TABLE IF NOT EXISTS metadata (
+            key TEXT PRIMARY KEY,
+            value TEXT
+        )
+        ",
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Erreur création table metadata: {e}"))?;
+
+    let current = schema_version(pool).await?;
+    if current > SCHEMA_VERSION {
+        return Err(format!(
+            "This database uses schema v{current}, written by a newer SolverPilot \
+             (this version supports up to v{SCHEMA_VERSION}). Please update SolverPilot."
+        ));
+    }
+    if current == SCHEMA_VERSION {
+        return Ok(current);
+    }
+
+    if let Some(path) = db_path {
+        let mut conn = pool
+            .acquire()
+            .await
+            .map_err(|e| format!("Erreur connexion SQLite: {e}"))?;
+        if table_exists(&mut conn, "jobs").await? {
+            let backup = backup(&mut conn, path, current).await?;
+            tracing::info!("Database backed up to {}", backup.display());
+        }
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start migration transaction: {e}"))?;
+
+        apply(&mut tx, migration.version)
+            .await
+            .map_err(|e| format!("Migration v{} failed: {e}", migration.version))?;
+        set_schema_version(&mut tx, migration.version).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit migration v{}: {e}", migration.version))?;
+        tracing::info!(
+            "Database migrated to v{} ({})",
+            migration.version,
+            migration.description
+        );
+    }
+
+    Ok(current)
+}
+
+/// Stored schema version (0 for a new or pre-versioning database)
+pub async fn schema_version(pool: &SqlitePool) -> Result<i64, String> {
+    let value: Option<String> =
+        sqlx::query("SELECT value FROM metadata WHERE key = 'schema_version'")
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| format!("Failed to read schema version: {e}"))?
+            .map(|row| row.get("value"));
+
+    value.map_or(Ok(0), |v| {
+        v.parse()
+            .map_err(|_| format!("Invalid schema version in metadata: '{v}'"))
+    })
+}
+
+async fn set_schema_version(conn: &mut SqliteConnection, version: i64) -> Result<(), String> {
+    sqlx::query("INSERT OR REPLACE INTO metadata (key, value) VALUES ('schema_version', ?)")
+        .bind(version.to_string())
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to write schema version: {e}"))?;
+
+    Ok(())
+}
+
+/// Backup file of `db_path` at schema `version`: `solver-pilot.db.v3-20260101T120000.bak`
+pub fn backup_path(db_path: &Path, version: i64) -> PathBuf {
+    let name = db_path
+        .file_name()
+        .map_or_else(|| "solver-pilot.db".into(), |n| n.to_string_lossy());
+    db_path.with_file_name(format!(
+        "{name}.v{version}-{}.bak",
+        Utc::now().format("%Y%m%dT%H%M%S")
+    ))
+}
+
+/// Write a consistent copy of the database before migrating it
+async fn backup(
+    conn: &mut SqliteConnection,
+    db_path: &Path,
+    version: i64,
+) -> Result<PathBuf, String> {
+    let path = backup_path(db_path, version);
+
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy().into_owned())
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to back up database to {}: {e}", path.display()))?;
+
+    Ok(path)
+}
+
+/// Whether `table` (or virtual table) exists
+pub async fn table_exists(conn: &mut SqliteConnection, table: &str) -> Result<bool, String> {
+    sqlx::query("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?")
+        .bind(table)
+        .fetch_optional(&mut *conn)
+        .await
+        .map(|row| row.is_some())
+        .map_err(|e| format!("Failed to inspect table {table}: {e}"))
+}
+
+/// Whether `table` has a `column`
+pub async fn has_column(
+    conn: &mut SqliteConnection,
+    table: &str,
+    column: &str,
+) -> Result<bool, String> {
+    sqlx::query("SELECT 1 FROM pragma_table_info(?) WHERE name = ?")
+        .bind(table)
+        .bind(column)
+        .fetch_optional(&mut *conn)
+        .await
+        .map(|row| row.is_some())
+        .map_err(|e| format!("Failed to inspect table {table}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_migrations_are_contiguous() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, i64::try_from(index).unwrap_or(-1) + 1);
+        }
+        assert_eq!(MIGRATIONS.last().map(|m| m.version), Some(SCHEMA_VERSION));
+    }
+
+    #[tokio::test]
+    async fn test_fresh_database_migrates_to_latest() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let db_path = temp_dir.path().join("solver-pilot.db");
+        let pool = db::init_db(&db_path.to_string_lossy()).await?;
+
+        assert_eq!(schema_version(&pool).await?, SCHEMA_VERSION);
+        let mut conn = pool.acquire().await?;
+        assert!(has_column(&mut conn, "jobs", "notes").await?);
+        assert!(table_exists(&mut conn, "jobs_fts").await?);
+
+        // Nothing to back up for a new database, nothing to do on reopen
+        assert_eq!(std::fs::read_dir(temp_dir.path())?.count(), 1);
+        assert_eq!(run(&pool, Some(&db_path)).await?, SCHEMA_VERSION);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_legacy_database_is_backed_up_and_migrated(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let db_path = temp_dir.path().join("solver-pilot.db");
+        let url = format!("sqlite:{}?mode=rwc", db_path.display());
+
+        // Database left by the ad-hoc migrations: some columns already there, no version
+        {
+            let pool = SqlitePool::connect(&url).await?;
+            let mut conn = pool.acquire().await?;
+            db::create_base_tables(&mut conn).await?;
+            db::migrate_queue_columns(&mut conn).await?;
+            sqlx::query(
+                "INSERT INTO jobs (benchmark_name, status, created_at) \
+                 VALUES ('bench.py', 'completed', '2026-01-11T10:00:00Z')",
+            )
+            .execute(&mut *conn)
+            .await?;
+            drop(conn);
+            pool.close().await;
+        }
+
+        let pool = db::init_db(&db_path.to_string_lossy()).await?;
+        assert_eq!(schema_version(&pool).await?, SCHEMA_VERSION);
+        let jobs: i64 = sqlx::query("SELECT COUNT(*) AS n FROM jobs_fts")
+            .fetch_one(&pool)
+            .await?
+            .get("n");
+        assert_eq!(jobs, 1);
+
+        let backups: Vec<_> = std::fs::read_dir(temp_dir.path())?
+            .filter_map(Result::ok)
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| name.starts_with("solver-pilot.db.v0-"))
+            .collect();
+        assert_eq!(backups.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_newer_database_is_refused() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let db_path = temp_dir.path().join("solver-pilot.db");
+        let pool = db::init_db(&db_path.to_string_lossy()).await?;
+        sqlx::query("UPDATE metadata SET value = ? WHERE key = 'schema_version'")
+            .bind((SCHEMA_VERSION + 1).to_string())
+            .execute(&pool)
+            .await?;
+        pool.close().await;
+
+        let err = db::init_db(&db_path.to_string_lossy())
+            .await
+            .err()
+            .ok_or("newer database was opened")?;
+        assert!(err.contains("newer SolverPilot"));
+        Ok(())
+    }
+}
@@ -95,6 +95,30 @@ pub fn projects_dir() -> Result<PathBuf, String> {
         .join("projects"))
 }
 
+/// Get the path to the code snapshots directory (content-addressed job files).
+///
+/// # Errors
+/// Returns an error if `init()` was not called.
+pub fn snapshots_dir() -> Result<PathBuf, String> {
+    Ok(PATHS
+        .get()
+        .ok_or("paths::init() must be called before snapshots_dir()")?
+        .data_dir
+        .join("snapshots"))
+}
+
+/// Get the path to the local job log archive (compressed logs of finished jobs).
+///
+/// # Errors
+/// Returns an error if `init()` was not called.
+pub fn logs_dir() -> Result<PathBuf, String> {
+    Ok(PATHS
+        .get()
+        .ok_or("paths::init() must be called before logs_dir()")?
+        .data_dir
+        .join("logs"))
+}
+
 /// Get the application paths.
 ///
 /// # Errors
@@ -0,0 +1,275 @@
+//! PBS Pro / Torque batch scheduler
+//!
+//! Jobs are submitted with `qsub`, tracked with `qstat -f` (`qstat -x -f` on
+//! PBS Pro, which keeps finished jobs with their `Exit_status`), interrupted
+//! with `qsig -s INT` and killed with `qdel`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::batch::{self, BatchScheduler, BatchState};
+use crate::limits::JobLimits;
+use crate::state::JobStatus;
+
+/// PBS flavour: resource syntax and job history differ
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PbsFlavor {
+    /// `-l select=1:ncpus=N:mem=Mmb`, finished jobs listed by `qstat -x`
+    #[default]
+    Pro,
+    /// `-l nodes=1:ppn=N`, finished jobs kept as `C` while `keep_completed` allows
+    Torque,
+}
+
+/// PBS options of a server
+///
+/// ```toml
+/// [servers.cluster]
+/// host = "head.cluster.example"
+/// user = "alice"
+/// backend = "pbs"
+///
+/// [servers.cluster.pbs]
+/// queue = "workq"
+/// walltime = "04:00:00"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PbsConfig {
+    #[serde(default)]
+    pub flavor: PbsFlavor,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    /// Wall-clock limit ("04:00:00")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub walltime: Option<String>,
+    /// CPUs when the job's limits set no CPU quota (default: 1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ncpus: Option<u32>,
+    /// Additional `#PBS` options (e.g. "-l place=excl")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_args: Vec<String>,
+}
+
+/// PBS scheduler of a server
+#[derive(Debug, Clone)]
+pub struct Pbs {
+    config: PbsConfig,
+}
+
+impl Pbs {
+    /// # Errors
+    /// Returns error if an option would break out of a `#PBS` line
+    pub fn new(config: PbsConfig) -> Result<Self, String> {
+        batch::validate_word("PBS queue", config.queue.as_deref())?;
+        batch::validate_word("PBS account", config.account.as_deref())?;
+        batch::validate_word("PBS walltime", config.walltime.as_deref())?;
+        batch::validate_extra_args(&config.extra_args)?;
+        Ok(Self { config })
+    }
+}
+
+impl BatchScheduler for Pbs {
+    fn name(&self) -> &'static str {
+        "PBS"
+    }
+
+    fn directives(&self, job_id: i64, limits: &JobLimits) -> Vec<String> {
+        let mut options = vec![format!("-N {}", batch::job_name(job_id))];
+        if let Some(ref queue) = self.config.queue {
+            options.push(format!("-q {queue}"));
+        }
+        if let Some(ref account) = self.config.account {
+            options.push(format!("-A {account}"));
+        }
+        if let Some(ref walltime) = self.config.walltime {
+            options.push(format!("-l walltime={walltime}"));
+        }
+
+        let cpus = batch::cpus(limits, self.config.ncpus);
+        match self.config.flavor {
+            PbsFlavor::Pro => {
+                let resources: Vec<String> = cpus
+                    .map(|cpus| format!("ncpus={cpus}"))
+                    .into_iter()
+                    .chain(limits.memory_mb.map(|mb| format!("mem={mb}mb")))
+                    .collect();
+                if !resources.is_empty() {
+                    options.push(format!("-l select=1:{}", resources.join(":")));
+                }
+            }
+            PbsFlavor::Torque => {
+                if let Some(cpus) = cpus {
+                    options.push(format!("-l nodes=1:ppn={cpus}"));
+                }
+                if let Some(memory_mb) = limits.memory_mb {
+                    options.push(format!("-l mem={memory_mb}mb"));
+                }
+            }
+        }
+        options.extend(self.config.extra_args.iter().cloned());
+
+        options
+            .into_iter()
+            .map(|option| format!("#PBS {option}"))
+            .collect()
+    }
+
+    fn submit_command(&self, script_path: &str, log_path: &str) -> String {
+        format!("qsub -j oe -o {log_path} {script_path}")
+    }
+
+    /// `"1234.pbs-server"`
+    fn parse_submit(&self, output: &str) -> Result<String, String> {
+        output
+            .lines()
+            .map(str::trim)
+            .rfind(|line| !line.is_empty())
+            .filter(|id| id.starts_with(|c: char| c.is_ascii_digit()))
+            .filter(|id| !id.contains(char::is_whitespace))
+            .map(str::to_string)
+            .ok_or_else(|| format!("Unexpected qsub output: '{}'", output.trim()))
+    }
+
+    fn state_command(&self, batch_id: &str) -> String {
+        match self.config.flavor {
+            PbsFlavor::Pro => format!("qstat -x -f {batch_id} 2>/dev/null"),
+            PbsFlavor::Torque => format!("qstat -f {batch_id} 2>/dev/null"),
+        }
+    }
+
+    /// `qstat -f` attributes: `job_state = F`, `Exit_status = 0` (`exit_status` on Torque)
+    fn parse_state(&self, output: &str) -> Option<BatchState> {
+        let attribute = |name: &str| {
+            output.lines().find_map(|line| {
+                let (key, value) = line.split_once('=')?;
+                key.trim()
+                    .eq_ignore_ascii_case(name)
+                    .then(|| value.trim().to_string())
+            })
+        };
+        let state = attribute("job_state")?;
+        let exit_code = attribute("exit_status").and_then(|code| code.parse().ok());
+
+        Some(match state.as_str() {
+            "Q" | "H" | "W" | "T" => BatchState::Queued,
+            "F" | "C" | "X" => finished(exit_code),
+            // R, E (exiting), B (array begun), S/U (suspended), M (moved)
+            _ => BatchState::Running,
+        })
+    }
+
+    fn cancel_command(&self, batch_id: &str, graceful: bool) -> String {
+        if graceful {
+            format!("qsig -s INT {batch_id}")
+        } else {
+            format!("qdel {batch_id}")
+        }
+    }
+}
+
+/// Final state of a PBS job from its exit status
+///
+/// PBS reports 256 + N for a job killed by signal N (limit reached or `qdel`)
+/// and negative values when the job could not be started.
+fn finished(exit_code: Option<i32>) -> BatchState {
+    let (status, reason) = match exit_code {
+        None => (
+            JobStatus::Killed,
+            Some("Deleted from PBS before it ran".to_string()),
+        ),
+        Some(0) => (JobStatus::Completed, None),
+        Some(code) if code >= 256 => (
+            JobStatus::Failed,
+            Some(format!("Killed by PBS with signal {}", code - 256)),
+        ),
+        Some(code) if code < 0 => (
+            JobStatus::Failed,
+            Some(format!("PBS could not run the job (exit status {code})")),
+        ),
+        Some(_) => (JobStatus::Failed, None),
+    };
+    BatchState::Finished {
+        status,
+        exit_code,
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directives() -> Result<(), String> {
+        let limits = JobLimits {
+            memory_mb: Some(8192),
+            cpu_quota_percent: Some(200),
+            ..JobLimits::default()
+        };
+        let config = PbsConfig {
+            queue: Some("workq".to_string()),
+            walltime: Some("04:00:00".to_string()),
+            extra_args: vec!["-l place=excl".to_string()],
+            ..PbsConfig::default()
+        };
+
+        let pro = Pbs::new(config.clone())?;
+        assert_eq!(
+            pro.directives(7, &limits),
+            vec![
+                "#PBS -N solverpilot_7",
+                "#PBS -q workq",
+                "#PBS -l walltime=04:00:00",
+                "#PBS -l select=1:ncpus=2:mem=8192mb",
+                "#PBS -l place=excl",
+            ]
+        );
+        assert!(!pro
+            .directives(7, &JobLimits::default())
+            .iter()
+            .any(|d| d.contains("select")));
+
+        let torque = Pbs::new(PbsConfig {
+            flavor: PbsFlavor::Torque,
+            ..config
+        })?;
+        let directives = torque.directives(7, &limits);
+        assert!(directives.contains(&"#PBS -l nodes=1:ppn=2".to_string()));
+        assert!(directives.contains(&"#PBS -l mem=8192mb".to_string()));
+        assert_eq!(torque.state_command("12"), "qstat -f 12 2>/dev/null");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_outputs() -> Result<(), String> {
+        let pbs = Pbs::new(PbsConfig::default())?;
+        assert_eq!(pbs.parse_submit("1234.head\n")?, "1234.head");
+        assert!(pbs.parse_submit("qsub: Unknown queue\n").is_err());
+
+        let qstat = |state: &str, exit: &str| {
+            format!(
+                "Job Id: 12.head\n    Job_Name = solverpilot_7\n    job_state = {state}\n{exit}"
+            )
+        };
+        assert_eq!(pbs.parse_state(&qstat("Q", "")), Some(BatchState::Queued));
+        assert_eq!(pbs.parse_state(&qstat("R", "")), Some(BatchState::Running));
+        assert_eq!(
+            pbs.parse_state(&qstat("F", "    Exit_status = 0")),
+            Some(BatchState::Finished {
+                status: JobStatus::Completed,
+                exit_code: Some(0),
+                reason: None,
+            })
+        );
+        // Torque: completed state and lowercase attribute, killed by SIGTERM
+        assert_eq!(
+            pbs.parse_state(&qstat("C", "    exit_status = 271"))
+                .and_then(|s| s.failure_message()),
+            Some("Killed by PBS with signal 15".to_string())
+        );
+        assert_eq!(pbs.parse_state(""), None);
+        Ok(())
+    }
+}
@@ -1,17 +1,43 @@
 //! Queue execution service for sequential job processing
 //!
 //! This module implements the core queue execution engine that:
-//! - Processes jobs sequentially (`max_concurrent` = 1 for Beta 1)
+//! - Runs up to `max_concurrent` jobs at once on each server
 //! - Syncs projects via `rsync` before execution
-//! - Launches jobs in `tmux` sessions with wrapper script
-//! - Polls server DB for job completion
+//! - Launches jobs in `tmux` sessions with wrapper script, or submits them to
+//!   the batch scheduler of Slurm/PBS/SGE servers (`batch` module)
+//! - Polls server DB (or the scheduler) for job completion
 //! - Auto-starts next job after current completes
-
-use crate::ssh::SshManager;
+//! - Runs each job on the server it was queued for, or places it automatically
+//!   (`dispatch` module) when it was queued without one
+//! - Checks license availability before each launch (optional preflight)
+//! - Runs only inside the configured execution windows (`schedule` module),
+//!   pausing (and optionally suspending running jobs) when a window ends
+//! - Ingests `benchmark_results.csv` of completed jobs
+
+use crate::batch::{self, BatchScheduler, BatchState};
+use crate::config::{AppConfig, ExecutionBackend, WindowEnd, DEFAULT_SERVER};
+use crate::container;
+use crate::db;
+use crate::dispatch::{self, Candidate};
+use crate::fingerprint;
+use crate::job;
+use crate::license::{self, PreflightOutcome};
+use crate::limits;
+use crate::load_guard;
+use crate::local;
+use crate::log_archive;
+use crate::results;
+use crate::schedule::ExecutionWindows;
+use crate::servers::{self, SshManagers};
+use crate::ssh::{SshManager, SshTransfer};
 use crate::state::{Job, JobStatus};
+use crate::usage;
+use crate::wrapper::WrapperManager;
 use sqlx::{Row, SqlitePool};
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Notify};
+use tokio::task::JoinHandle;
 use tokio::time::{interval, Duration};
 
 /// Queue state for pause/resume functionality
@@ -47,40 +73,69 @@ impl QueueState {
 
 /// Queue execution manager
 ///
-/// Manages sequential job processing with background task execution.
-/// Ensures only one job runs at a time (`max_concurrent` = 1).
+/// Manages job processing with background task execution.
+/// Runs at most `max_concurrent` jobs at a time on each server.
 #[derive(Clone)]
 pub struct QueueManager {
     queue_state: Arc<Mutex<QueueState>>,
-    current_job_id: Arc<Mutex<Option<i64>>>,
+    /// Jobs started by the queue and still running, with their server
+    running_jobs: Arc<Mutex<BTreeMap<i64, String>>>,
+    /// Why the next job is not starting yet (e.g. no license token available)
+    waiting_reason: Arc<Mutex<Option<String>>>,
+    /// Last license warning (e.g. expiry approaching)
+    license_warning: Arc<Mutex<Option<String>>>,
+    /// Paused at the end of an execution window (resumes when the next opens)
+    schedule_paused: Arc<Mutex<bool>>,
+    /// Running jobs suspended at the end of an execution window
+    suspended_jobs: Arc<Mutex<BTreeSet<i64>>>,
+    /// Background processing loop (at most one alive at a time)
+    processing_task: Arc<Mutex<Option<JoinHandle<()>>>>,
+    /// Servers whose wrapper and database were brought to this build since the queue started
+    ready_servers: Arc<Mutex<BTreeSet<String>>>,
+    /// Execution windows of the loaded config
+    windows: Arc<Mutex<ExecutionWindows>>,
+    /// Wakes the processing loop when the queue is paused, resumed or stopped
+    state_changed: Arc<Notify>,
 }
 impl QueueManager {
     /// Create a new queue manager
     pub fn new() -> Self {
         Self {
             queue_state: Arc::new(Mutex::new(QueueState::Idle)),
-            current_job_id: Arc::new(Mutex::new(None)),
+            running_jobs: Arc::new(Mutex::new(BTreeMap::new())),
+            waiting_reason: Arc::new(Mutex::new(None)),
+            license_warning: Arc::new(Mutex::new(None)),
+            schedule_paused: Arc::new(Mutex::new(false)),
+            suspended_jobs: Arc::new(Mutex::new(BTreeSet::new())),
+            processing_task: Arc::new(Mutex::new(None)),
+            ready_servers: Arc::new(Mutex::new(BTreeSet::new())),
+            windows: Arc::new(Mutex::new(ExecutionWindows::default())),
+            state_changed: Arc::new(Notify::new()),
         }
     }
 
     /// Start queue processing loop in background task
     ///
     /// Spawns a background Tokio task that:
-    /// 1. Selects next pending job by `queue_position`
-    /// 2. Executes job (rsync → tmux → poll)
-    /// 3. Auto-starts next job after completion
+    /// 1. Selects the next pending job that has a server with a free slot
+    /// 2. Executes it in its own task (rsync → tmux → poll)
+    /// 3. Auto-starts next job as slots free up
     /// 4. Stops when queue is empty or paused
+    ///
+    /// Outside the execution windows, the queue waits paused for the next one.
     pub async fn start_processing(
         &self,
         db: SqlitePool,
-        ssh: Arc<SshManager>,
-        ssh_host: String,
-        ssh_username: String,
+        ssh_managers: Arc<Mutex<SshManagers>>,
+        config: AppConfig,
     ) -> Result<(), String> {
+        let windows = config.execution_windows()?;
+        self.set_windows(windows.clone()).await;
         let mut state = self.queue_state.lock().await;
 
-        // Prevent starting if already running
-        if *state == QueueState::Running {
+        // Only an idle queue starts, checked and changed under the state lock; a loop
+        // still winding down (e.g. after `stop_processing`) must finish first
+        if *state != QueueState::Idle || self.loop_alive().await {
             return Err("Queue already processing".to_string());
         }
 
@@ -88,11 +143,15 @@ impl QueueManager {
         *state = QueueState::Running;
         drop(state);
 
+        // Wrappers and server databases checked again before each server's first job
+        self.ready_servers.lock().await.clear();
+
         // Persist state to database
         save_queue_state(&db, QueueState::Running).await?;
 
         // Spawn background processing task
-        self.spawn_processing_task(db, ssh, ssh_host, ssh_username);
+        self.spawn_processing_task(db, ssh_managers, config, windows)
+            .await;
 
         Ok(())
     }
@@ -100,18 +159,23 @@ impl QueueManager {
     /// Pause queue processing (graceful)
     ///
     /// Running jobs complete naturally, new jobs don't start.
-    /// Can only pause if currently running.
+    /// Can only pause if currently running, or waiting for an execution
+    /// window (it then stays paused when the window opens).
     pub async fn pause_processing(&self, db: &SqlitePool) -> Result<(), String> {
         let mut state = self.queue_state.lock().await;
+        let mut schedule_paused = self.schedule_paused.lock().await;
 
         // Can only pause if currently running
-        if *state != QueueState::Running {
+        if *state != QueueState::Running && !*schedule_paused {
             return Err(format!("Cannot pause queue in state: {:?}", *state));
         }
 
         // Change state to Paused
         *state = QueueState::Paused;
+        *schedule_paused = false;
+        drop(schedule_paused);
         drop(state);
+        self.state_changed.notify_one();
 
         // Persist state to database
         save_queue_state(db, QueueState::Paused).await?;
@@ -122,51 +186,76 @@ impl QueueManager {
 
     /// Resume queue processing from paused state
     ///
-    /// Can only resume if currently paused.
+    /// Can only resume if currently paused (by hand: a queue waiting for its
+    /// execution window resumes by itself).
     /// After app restart, this also spawns a new background task.
     pub async fn resume_processing(
         &self,
         db: SqlitePool,
-        ssh: Arc<SshManager>,
-        ssh_host: String,
-        ssh_username: String,
+        ssh_managers: Arc<Mutex<SshManagers>>,
+        config: AppConfig,
     ) -> Result<(), String> {
+        let windows = config.execution_windows()?;
+        self.set_windows(windows.clone()).await;
         let mut state = self.queue_state.lock().await;
 
         // Can only resume if currently paused
         if *state != QueueState::Paused {
             return Err(format!("Cannot resume queue in state: {:?}", *state));
         }
+        if *self.schedule_paused.lock().await {
+            return Err("Queue is waiting for its next execution window".to_string());
+        }
 
         // Change state to Running
         *state = QueueState::Running;
         drop(state);
+        self.state_changed.notify_one();
 
         // Persist state to database
         save_queue_state(&db, QueueState::Running).await?;
 
         // Spawn background task to ensure processing continues
         // This handles the case where app was restarted while paused
-        self.spawn_processing_task(db, ssh, ssh_host, ssh_username);
+        self.spawn_processing_task(db, ssh_managers, config, windows)
+            .await;
 
         tracing::info!("Queue resumed - processing pending jobs");
         Ok(())
     }
 
-    /// Spawn the background processing task
+    /// Whether the background processing loop is still alive
+    async fn loop_alive(&self) -> bool {
+        self.processing_task
+            .lock()
+            .await
+            .as_ref()
+            .is_some_and(|task| !task.is_finished())
+    }
+
+    /// Spawn the background processing task, unless its loop is still alive
     ///
     /// Extracted to allow reuse between `start_processing` and `resume_processing`.
-    fn spawn_processing_task(
+    async fn spawn_processing_task(
         &self,
         db: SqlitePool,
-        ssh: Arc<SshManager>,
-        ssh_host: String,
-        ssh_username: String,
+        ssh_managers: Arc<Mutex<SshManagers>>,
+        config: AppConfig,
+        windows: ExecutionWindows,
     ) {
+        let mut task = self.processing_task.lock().await;
+        if task.as_ref().is_some_and(|t| !t.is_finished()) {
+            tracing::debug!("Queue processing loop already running");
+            return;
+        }
+
+        let manager = self.clone();
         let queue_state = Arc::clone(&self.queue_state);
-        let current_job_id = Arc::clone(&self.current_job_id);
+        let running_jobs = Arc::clone(&self.running_jobs);
+        let waiting_reason = Arc::clone(&self.waiting_reason);
+        let license_warning = Arc::clone(&self.license_warning);
 
-        tokio::spawn(async move {
+        *task = Some(tokio::spawn(async move {
             loop {
                 // Check queue state
                 let state = queue_state.lock().await.clone();
@@ -177,32 +266,53 @@ impl QueueManager {
                     break;
                 }
 
+                // Execution windows: pause when one ends, resume when the next opens
+                let open = windows.is_open(chrono::Local::now().naive_local());
+                if state == QueueState::Running && !open {
+                    manager.close_window(&db, &ssh_managers, &config).await;
+                    continue;
+                }
+                if state == QueueState::Paused && open && *manager.schedule_paused.lock().await {
+                    manager.open_window(&db).await;
+                    continue;
+                }
+
                 // Wait if paused
                 if state == QueueState::Paused {
                     tracing::debug!("Queue paused, waiting...");
-                    tokio::time::sleep(Duration::from_secs(2)).await;
+                    manager.wait(Duration::from_secs(2)).await;
                     continue;
                 }
 
-                // Select next job
-                match select_next_job(&db).await {
-                    Ok(Some(job)) => {
-                        tracing::info!("Starting job {} ({})", job.id, job.benchmark_name);
-                        *current_job_id.lock().await = Some(job.id);
-
-                        // Execute job
-                        if let Err(e) = execute_job(&db, &ssh, &job, &ssh_host, &ssh_username).await
-                        {
-                            tracing::error!("Job {} failed: {}", job.id, e);
-                            if let Err(mark_err) = mark_job_failed(&db, job.id, &e).await {
-                                tracing::error!("Failed to mark job as failed: {}", mark_err);
-                            }
-                        }
+                // Jobs suspended by a past window go on with the queue
+                manager
+                    .continue_suspended_jobs(&db, &ssh_managers, &config)
+                    .await;
 
-                        *current_job_id.lock().await = None;
+                let pending = match select_pending_jobs(&db).await {
+                    Ok(pending) => pending,
+                    Err(e) => {
+                        tracing::error!("Failed to select next job: {}", e);
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                        continue;
                     }
-                    Ok(None) => {
-                        // Queue empty, stop processing
+                };
+
+                if pending.is_empty() {
+                    // Jobs left with a start time to come: wait for the first one
+                    match db::next_job_start(&db, &job::start_time_now()).await {
+                        Ok(Some(next)) => {
+                            *waiting_reason.lock().await =
+                                Some(format!("Next job starts at {}", local_time(&next)));
+                            manager.wait(Duration::from_secs(2)).await;
+                            continue;
+                        }
+                        Ok(None) => {}
+                        Err(e) => tracing::error!("Failed to read job start times: {}", e),
+                    }
+                    *waiting_reason.lock().await = None;
+                    // Queue completed once the last running job has finished
+                    if running_jobs.lock().await.is_empty() {
                         *queue_state.lock().await = QueueState::Idle;
                         if let Err(e) = save_queue_state(&db, QueueState::Idle).await {
                             tracing::error!("Failed to save idle state: {}", e);
@@ -210,13 +320,78 @@ impl QueueManager {
                         tracing::info!("Queue completed - all jobs finished");
                         break;
                     }
-                    Err(e) => {
-                        tracing::error!("Failed to select next job: {}", e);
-                        tokio::time::sleep(Duration::from_secs(5)).await;
+                    tokio::time::sleep(Duration::from_secs(2)).await;
+                    continue;
+                }
+
+                let running = running_jobs.lock().await.clone();
+                match place_next_job(
+                    &db,
+                    &ssh_managers,
+                    &config,
+                    pending,
+                    &running,
+                    &manager.ready_servers,
+                )
+                .await
+                {
+                    Placement::Start {
+                        job,
+                        server,
+                        config: job_config,
+                        ssh,
+                        warning,
+                    } => {
+                        if let Some(ref w) = warning {
+                            tracing::warn!("{}", w);
+                        }
+                        *waiting_reason.lock().await = None;
+                        *license_warning.lock().await = warning;
+
+                        if let Err(e) = mark_job_running(&db, job.id, &server).await {
+                            tracing::error!("{}", e);
+                            tokio::time::sleep(Duration::from_secs(5)).await;
+                            continue;
+                        }
+                        tracing::info!(
+                            "Starting job {} ({}) on {}",
+                            job.id,
+                            job.benchmark_name,
+                            server
+                        );
+                        running_jobs.lock().await.insert(job.id, server);
+
+                        // Execute job (the loop goes on to fill the other free slots)
+                        let db = db.clone();
+                        let running_jobs = Arc::clone(&running_jobs);
+                        tokio::spawn(async move {
+                            if let Err(e) = execute_job(&db, &ssh, &job, &job_config).await {
+                                tracing::error!("Job {} failed: {}", job.id, e);
+                                if let Err(mark_err) = mark_job_failed(&db, job.id, &e).await {
+                                    tracing::error!("Failed to mark job as failed: {}", mark_err);
+                                }
+                            }
+                            running_jobs.lock().await.remove(&job.id);
+                        });
+                    }
+                    Placement::Fail { job_id, reason } => {
+                        tracing::error!("Job {} blocked: {}", job_id, reason);
+                        *waiting_reason.lock().await = None;
+                        if let Err(e) = mark_job_failed(&db, job_id, &reason).await {
+                            tracing::error!("Failed to mark job as failed: {}", e);
+                        }
+                    }
+                    Placement::Wait { reason, retry } => {
+                        if let Some(ref r) = reason {
+                            tracing::warn!("Next job delayed: {}", r);
+                        }
+                        *waiting_reason.lock().await = reason;
+                        // Woken early by a pause or stop
+                        manager.wait(retry).await;
                     }
                 }
             }
-        });
+        }));
     }
 
     /// Stop queue processing gracefully (deprecated - use pause instead)
@@ -226,10 +401,29 @@ impl QueueManager {
     #[deprecated(note = "Use pause_processing instead")]
     pub async fn stop_processing(&self) -> Result<(), String> {
         *self.queue_state.lock().await = QueueState::Idle;
+        self.state_changed.notify_one();
         tracing::info!("Queue processing will stop after current job");
         Ok(())
     }
 
+    /// Sleep for `duration`, or until the queue is paused, resumed or stopped
+    async fn wait(&self, duration: Duration) {
+        tokio::select! {
+            () = tokio::time::sleep(duration) => {}
+            () = self.state_changed.notified() => {}
+        }
+    }
+
+    /// Keep the execution windows of a newly loaded or started config
+    pub async fn set_windows(&self, windows: ExecutionWindows) {
+        *self.windows.lock().await = windows;
+    }
+
+    /// Execution windows of the loaded config
+    pub async fn windows(&self) -> ExecutionWindows {
+        self.windows.lock().await.clone()
+    }
+
     /// Get current queue state
     pub async fn get_state(&self) -> QueueState {
         self.queue_state.lock().await.clone()
@@ -241,9 +435,110 @@ impl QueueManager {
         *self.queue_state.lock().await == QueueState::Running
     }
 
-    /// Get currently executing job ID
+    /// Get the most recently started job still running
     pub async fn current_job(&self) -> Option<i64> {
-        *self.current_job_id.lock().await
+        self.running_jobs.lock().await.keys().next_back().copied()
+    }
+
+    /// Jobs started by the queue and still running, with their server
+    pub async fn running_jobs(&self) -> BTreeMap<i64, String> {
+        self.running_jobs.lock().await.clone()
+    }
+
+    /// Why the next job is waiting (license unavailable, ...)
+    pub async fn waiting_reason(&self) -> Option<String> {
+        self.waiting_reason.lock().await.clone()
+    }
+
+    /// Last license warning raised by the preflight check
+    pub async fn license_warning(&self) -> Option<String> {
+        self.license_warning.lock().await.clone()
+    }
+
+    /// Whether the queue is paused until the next execution window
+    pub async fn schedule_paused(&self) -> bool {
+        *self.schedule_paused.lock().await
+    }
+
+    /// Running jobs suspended until the next execution window
+    pub async fn suspended_jobs(&self) -> BTreeSet<i64> {
+        self.suspended_jobs.lock().await.clone()
+    }
+
+    /// Pause at the end of an execution window
+    ///
+    /// Running jobs finish, or are suspended with `window_end = "suspend"`
+    /// (jobs of batch schedulers are left to their scheduler).
+    async fn close_window(
+        &self,
+        db: &SqlitePool,
+        ssh_managers: &Mutex<SshManagers>,
+        config: &AppConfig,
+    ) {
+        let mut state = self.queue_state.lock().await;
+        // Paused or stopped by hand meanwhile
+        if *state != QueueState::Running {
+            return;
+        }
+        *state = QueueState::Paused;
+        *self.schedule_paused.lock().await = true;
+        drop(state);
+
+        if let Err(e) = save_queue_state(db, QueueState::Paused).await {
+            tracing::error!("Failed to save paused state: {}", e);
+        }
+        tracing::info!("Execution window ended - queue paused until the next one");
+
+        if config.queue_settings.window_end != WindowEnd::Suspend {
+            return;
+        }
+        for (job_id, server) in self.running_jobs().await {
+            match suspend_job(db, ssh_managers, config, job_id, &server, true).await {
+                Ok(()) => {
+                    self.suspended_jobs.lock().await.insert(job_id);
+                    tracing::info!("Job {} suspended until the next window", job_id);
+                }
+                Err(e) => tracing::warn!("Job {} not suspended: {}", job_id, e),
+            }
+        }
+    }
+
+    /// Resume when an execution window opens, if the window paused the queue
+    async fn open_window(&self, db: &SqlitePool) {
+        let mut state = self.queue_state.lock().await;
+        let mut schedule_paused = self.schedule_paused.lock().await;
+        if *state != QueueState::Paused || !*schedule_paused {
+            return;
+        }
+        *state = QueueState::Running;
+        *schedule_paused = false;
+        drop(schedule_paused);
+        drop(state);
+
+        if let Err(e) = save_queue_state(db, QueueState::Running).await {
+            tracing::error!("Failed to save running state: {}", e);
+        }
+        tracing::info!("Execution window opened - queue resumed");
+    }
+
+    /// Continue the suspended jobs still running
+    async fn continue_suspended_jobs(
+        &self,
+        db: &SqlitePool,
+        ssh_managers: &Mutex<SshManagers>,
+        config: &AppConfig,
+    ) {
+        let suspended = std::mem::take(&mut *self.suspended_jobs.lock().await);
+        for job_id in suspended {
+            // Finished or killed meanwhile
+            let Some(server) = self.running_jobs.lock().await.get(&job_id).cloned() else {
+                continue;
+            };
+            match suspend_job(db, ssh_managers, config, job_id, &server, false).await {
+                Ok(()) => tracing::info!("Job {} continued", job_id),
+                Err(e) => tracing::warn!("Job {} not continued: {}", job_id, e),
+            }
+        }
     }
 
     /// Restore queue state on application startup
@@ -271,11 +566,9 @@ impl Default for QueueManager {
     }
 }
 
-/// Select next pending job by `queue_position` (FIFO)
-///
-/// Returns the job with lowest `queue_position` where status = 'pending'.
-async fn select_next_job(db: &SqlitePool) -> Result<Option<Job>, String> {
-    let row = sqlx::query(
+/// Select pending jobs whose start time has come, by `queue_position` (FIFO)
+async fn select_pending_jobs(db: &SqlitePool) -> Result<Vec<Job>, String> {
+    let rows = sqlx::query(
         "
         SELECT 
             id,
@@ -291,18 +584,25 @@ async fn select_next_job(db: &SqlitePool) -> Result<Option<Job>, String> {
             error_message,
             log_content,
             queue_position,
-            queued_at
+            queued_at,
+            env_profile,
+            peak_rss_kb,
+            cpu_seconds,
+            notes,
+            server,
+            not_before
         FROM jobs 
         WHERE status = 'pending' 
+          AND (not_before IS NULL OR not_before <= ?)
         ORDER BY queue_position ASC 
-        LIMIT 1
         ",
     )
-    .fetch_optional(db)
+    .bind(job::start_time_now())
+    .fetch_all(db)
     .await
     .map_err(|e| format!("Failed to select next job: {e}"))?;
 
-    let job = row.map(|r| {
+    let jobs = rows.into_iter().map(|r| {
         let status_str: String = r.get("status");
         let status = match status_str.as_str() {
             "running" => JobStatus::Running,
@@ -329,57 +629,506 @@ async fn select_next_job(db: &SqlitePool) -> Result<Option<Job>, String> {
             log_content: r.get("log_content"),
             queue_position: r.get("queue_position"),
             queued_at: r.get("queued_at"),
+            env_profile: r.get("env_profile"),
+            peak_rss_kb: r.get("peak_rss_kb"),
+            cpu_seconds: r.get("cpu_seconds"),
+            notes: r.get("notes"),
+            tags: Vec::new(),
+            server: r.get("server"),
+            not_before: r.get("not_before"),
         }
     });
 
-    if let Some(ref j) = job {
-        tracing::debug!("Selected job {} at position {:?}", j.id, j.queue_position);
+    Ok(jobs.collect())
+}
+
+/// What the queue does next
+#[allow(clippy::large_enum_variant)] // One short-lived value per loop iteration
+enum Placement {
+    /// Start `job` on `server`, with that server's config view and SSH manager
+    Start {
+        job: Job,
+        server: String,
+        config: AppConfig,
+        ssh: SshManager,
+        warning: Option<String>,
+    },
+    /// The job cannot run on any server
+    Fail { job_id: i64, reason: String },
+    /// Nothing can start now: why (when worth reporting), and when to retry
+    Wait {
+        reason: Option<String>,
+        retry: Duration,
+    },
+}
+
+/// Pick the next job that can start, and its server
+///
+/// Jobs are considered in queue order; a job whose servers are all full (or
+/// held by the load guard, or not connected) is skipped so that a later job can
+/// use another free server. Jobs queued without
+/// a server (with dispatch enabled) try the ranked servers in turn, falling back
+/// to the next one when a server is unreachable or has no license available.
+/// A server's wrapper and database are upgraded before its first job (`ready`).
+async fn place_next_job(
+    db: &SqlitePool,
+    ssh_managers: &Mutex<SshManagers>,
+    config: &AppConfig,
+    pending: Vec<Job>,
+    running: &BTreeMap<i64, String>,
+    ready: &Mutex<BTreeSet<String>>,
+) -> Placement {
+    let slots = dispatch::running_per_server(running);
+    // Server loads, sampled once for the first job dispatched automatically
+    let mut candidates: Option<Vec<Candidate>> = None;
+    // Load guard verdicts, checked once per server
+    let mut guarded: BTreeMap<String, Option<String>> = BTreeMap::new();
+    let mut busy = None;
+    let mut disconnected = None;
+    let mut not_ready = None;
+
+    for job in pending {
+        let pinned = match job.server {
+            Some(ref server) => Some(server.clone()),
+            None if !config.dispatch.enabled => Some(DEFAULT_SERVER.to_string()),
+            None => None,
+        };
+
+        let targets = if let Some(ref server) = pinned {
+            let view = match config
+                .resolve_server(Some(server))
+                .and_then(|name| config.for_server(&name))
+            {
+                Ok(view) => view,
+                Err(reason) => {
+                    return Placement::Fail {
+                        job_id: job.id,
+                        reason,
+                    }
+                }
+            };
+            if slots.get(server.as_str()).copied().unwrap_or(0) >= view.ssh.max_concurrent {
+                continue;
+            }
+            vec![server.clone()]
+        } else {
+            let allowed = match job.project_id {
+                Some(project_id) => {
+                    match db::get_benchmark_servers_by_name(db, project_id, &job.benchmark_name)
+                        .await
+                    {
+                        Ok(allowed) => allowed,
+                        Err(reason) => {
+                            return Placement::Fail {
+                                job_id: job.id,
+                                reason,
+                            }
+                        }
+                    }
+                }
+                None => Vec::new(),
+            };
+            if candidates.is_none() {
+                candidates = Some(sample_candidates(ssh_managers, config, &slots).await);
+            }
+            let ranked = dispatch::rank(
+                candidates.as_deref().unwrap_or_default(),
+                &allowed,
+                config.dispatch.max_load_per_core,
+            );
+            if ranked.is_empty() {
+                continue;
+            }
+            ranked
+        };
+
+        let mut delayed = None;
+        let mut blocked = None;
+        for server in targets {
+            let (job_config, ssh) =
+                match servers::resolve(ssh_managers, config, Some(&server)).await {
+                    Ok(context) => context,
+                    // Pinned server without SSH connection: the job waits for it
+                    Err(e) if pinned.is_some() => {
+                        tracing::debug!("Job {} waiting for {}: {}", job.id, server, e);
+                        disconnected = Some(format!("Server {server} not connected"));
+                        continue;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Server {} skipped for job {}: {}", server, job.id, e);
+                        continue;
+                    }
+                };
+
+            // Load guard: leave the job queued while the server is busy
+            if let Some(reason) = server_busy(&mut guarded, &server, &ssh, &job_config).await {
+                tracing::debug!("Job {} held on {}: {}", job.id, server, reason);
+                busy = Some(reason);
+                continue;
+            }
+
+            // Wrapper and server database: deployed or upgraded before the first job
+            if let Some(reason) = server_ready(ready, &server, &ssh).await {
+                tracing::warn!("Job {} not started on {}: {}", job.id, server, reason);
+                not_ready = Some(reason);
+                continue;
+            }
+
+            // License preflight: block, delay or warn before launching
+            match license_preflight(db, &ssh, &job, &job_config).await {
+                PreflightOutcome::Ready(warning) => {
+                    return Placement::Start {
+                        job,
+                        server,
+                        config: job_config,
+                        ssh,
+                        warning,
+                    }
+                }
+                PreflightOutcome::Delay(reason) => {
+                    tracing::debug!("Job {} delayed on {}: {}", job.id, server, reason);
+                    delayed = Some(reason);
+                }
+                PreflightOutcome::Block(reason) => {
+                    tracing::debug!("Job {} blocked on {}: {}", job.id, server, reason);
+                    blocked = Some(reason);
+                }
+            }
+        }
+
+        // Licenses are shared by the following jobs: wait rather than skip ahead
+        if let Some(reason) = delayed {
+            return Placement::Wait {
+                reason: Some(reason),
+                retry: Duration::from_secs(config.license.retry_seconds),
+            };
+        }
+        if let Some(reason) = blocked {
+            return Placement::Fail {
+                job_id: job.id,
+                reason,
+            };
+        }
+    }
+
+    // Every server that could take a job is busy (short of resources, not upgraded,
+    // or not connected)
+    let retry = if busy.is_some() || not_ready.is_some() {
+        config.load_guard.retry_seconds
+    } else {
+        2
+    };
+    Placement::Wait {
+        reason: busy.or(not_ready).or(disconnected),
+        retry: Duration::from_secs(retry),
+    }
+}
+
+/// Bring `server`'s wrapper and database to this build, once per queue start
+///
+/// Returns why the server cannot take jobs when the upgrade fails (retried later).
+async fn server_ready(
+    ready: &Mutex<BTreeSet<String>>,
+    server: &str,
+    ssh: &SshManager,
+) -> Option<String> {
+    if ready.lock().await.contains(server) {
+        return None;
+    }
+    match WrapperManager::new()
+        .ensure_server_ready(ssh.executor())
+        .await
+    {
+        Ok(upgrade) => {
+            if upgrade.wrapper_deployed || upgrade.schema_from != upgrade.schema_to {
+                tracing::info!("Queue infrastructure deployed on {}", server);
+            }
+            ready.lock().await.insert(server.to_string());
+            None
+        }
+        Err(e) => Some(format!("Wrapper upgrade failed on {server}: {e}")),
+    }
+}
+
+/// Why `server` has not the resources for a new job (load guard), if so
+///
+/// Sampled once per placement; a failed sample lets the job start.
+async fn server_busy(
+    guarded: &mut BTreeMap<String, Option<String>>,
+    server: &str,
+    ssh: &SshManager,
+    config: &AppConfig,
+) -> Option<String> {
+    if !config.load_guard.enabled || !load_guard::applies_to(config.ssh.backend) {
+        return None;
+    }
+    if let Some(verdict) = guarded.get(server) {
+        return verdict.clone();
+    }
+
+    let verdict = match load_guard::check(ssh.executor(), &config.load_guard).await {
+        Ok(verdict) => verdict.map(|reason| format!("Waiting for resources on {server}: {reason}")),
+        Err(e) => {
+            tracing::warn!("Resources of server {} not checked: {}", server, e);
+            None
+        }
+    };
+    guarded.insert(server.to_string(), verdict.clone());
+    verdict
+}
+
+/// Load and free slots of every configured server (unconnected ones are unreachable)
+async fn sample_candidates(
+    ssh_managers: &Mutex<SshManagers>,
+    config: &AppConfig,
+    slots: &BTreeMap<&str, u32>,
+) -> Vec<Candidate> {
+    let managers = ssh_managers.lock().await.clone();
+    let mut candidates = Vec::new();
+
+    for name in config.server_names() {
+        let Ok(view) = config.for_server(&name) else {
+            continue;
+        };
+        let mut candidate = Candidate {
+            running: slots.get(name.as_str()).copied().unwrap_or(0),
+            max_concurrent: view.ssh.max_concurrent,
+            load: None,
+            name,
+        };
+
+        // No need to sample a full server
+        if let (true, Some(ssh)) = (candidate.has_slot(), managers.get(&candidate.name)) {
+            match dispatch::sample_load(ssh.executor()).await {
+                Ok(load) => candidate.load = Some(load),
+                Err(e) => tracing::warn!("Server {} unreachable: {}", candidate.name, e),
+            }
+        }
+        candidates.push(candidate);
     }
 
-    Ok(job)
+    candidates
 }
 
 /// Execute a single job: rsync → tmux → poll
 ///
 /// Steps:
-/// 1. Update local DB status to 'running'
+/// 1. Local DB status set to 'running' by the caller (`mark_job_running`)
 /// 2. Rsync project files to remote server
-/// 3. Create tmux session with wrapper invocation
+/// 3. Create tmux session with environment profile + wrapper invocation
 /// 4. Poll server DB every 2 seconds for completion
 /// 5. Update local DB when job completes
+/// 6. Ingest the job's results CSV into the local `results` table
 async fn execute_job(
     db: &SqlitePool,
     ssh: &SshManager,
     job: &Job,
-    ssh_host: &str,
-    ssh_username: &str,
+    config: &AppConfig,
 ) -> Result<(), String> {
-    // 1. Update local DB to running
-    sqlx::query("UPDATE jobs SET status = 'running', started_at = datetime('now') WHERE id = ?")
-        .bind(job.id)
-        .execute(db)
-        .await
-        .map_err(|e| format!("Failed to update job status: {e}"))?;
-
-    tracing::info!("Job {} marked as running", job.id);
-
     // 2. Get project path from database
     let project_path = get_project_path(db, job.project_id).await?;
 
     // 3. Rsync project files
-    if let Err(e) = rsync_project(&project_path, ssh_host, ssh_username).await {
+    if let Err(e) = rsync_project(&project_path, ssh.transfer()).await {
         tracing::error!("Failed to rsync project: {}", e);
         return Err(format!("Failed to sync project files: {e}"));
     }
 
     tracing::info!("Project files synced for job {}", job.id);
 
-    // 4. Create tmux session with wrapper
-    let session_name = generate_session_name(job.id);
-    let wrapper_cmd = format!(
-        "~/.solverpilot/bin/job_wrapper.sh {} python3 {}",
-        job.id, job.benchmark_name
+    // Record the server environment (non-critical)
+    if config.fingerprint.enabled {
+        if let Err(e) = fingerprint::record_job_fingerprint(db, ssh, config, job).await {
+            tracing::warn!(
+                "Environment fingerprint not recorded for job {}: {}",
+                job.id,
+                e
+            );
+        }
+    }
+
+    // 4. Start the job with the wrapper (environment profile applied first)
+    let project_profile = get_project_env_profile(db, job.project_id).await?;
+    let env_prelude = config
+        .resolve_env_profile(job.env_profile.as_deref(), project_profile.as_deref())?
+        .map(|profile| profile.to_shell_prelude())
+        .unwrap_or_default();
+
+    // Memory/CPU limits (config defaults overridden by the benchmark), applied by the
+    // wrapper in tmux or requested from Slurm
+    let job_limits = limits::resolve_job_limits(db, config, job).await?;
+
+    // Container profile (job, project, default): image and digest recorded on the job,
+    // Docker takes the limits itself
+    let container = container::prepare_job(db, ssh.executor(), config, job.id).await?;
+    let python = format!("python3 {}", job.benchmark_name);
+    let job_command = container.as_ref().map_or_else(
+        || python.clone(),
+        |profile| profile.wrap(&python, &job_limits),
     );
+    let limit_env = if container
+        .as_ref()
+        .is_some_and(container::ContainerProfile::applies_limits)
+    {
+        String::new()
+    } else {
+        job_limits.to_env_assignments()
+    };
+
+    // Results CSV kept per job by the wrapper, ingested at completion
+    db::set_job_results_path(db, job.id, &results::job_results_path(job.id)).await?;
+
+    // Job output goes to the remote log streamed, tailed and archived by the app
+    let jobs_path = config.remote_jobs_path();
+    let log_path = format!("{jobs_path}/{}.log", job.id);
+
+    let status = match batch::scheduler(&config.ssh)? {
+        None => {
+            let wrapper_cmd = wrapper_command(
+                job.id,
+                &jobs_path,
+                &log_path,
+                &format!("{env_prelude}{limit_env}"),
+                &job_command,
+            );
+            if config.ssh.backend == ExecutionBackend::Local {
+                let pgid = local::start_job(ssh.executor(), &wrapper_cmd)?;
+                db::set_job_backend_id(db, job.id, &pgid.to_string()).await?;
+                tracing::info!("Job {} started locally in process group {}", job.id, pgid);
+            } else {
+                start_tmux_job(ssh, job.id, &wrapper_cmd).await?;
+            }
+
+            // 5. Poll for completion
+            let status = poll_job_completion(db, ssh, job.id, &log_path).await?;
+
+            // Distinct failure reason when a memory/CPU limit was hit (non-critical)
+            if status == "failed" {
+                match limits::read_limit_reason(ssh.executor(), job.id).await {
+                    Ok(Some(reason)) => {
+                        sqlx::query("UPDATE jobs SET error_message = ? WHERE id = ?")
+                            .bind(&reason)
+                            .bind(job.id)
+                            .execute(db)
+                            .await
+                            .ok();
+                        tracing::info!("Job {} stopped: {}", job.id, reason);
+                    }
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!("Failure reason not read for job {}: {}", job.id, e),
+                }
+            }
+            status
+        }
+        Some(scheduler) => {
+            // Limits become scheduler resources instead of wrapper settings
+            let body = format!(
+                "{env_prelude}~/.solverpilot/bin/job_wrapper.sh {} {job_command}",
+                job.id
+            );
+            let batch_id = batch::submit_job(
+                &*scheduler,
+                ssh.executor(),
+                &jobs_path,
+                job.id,
+                &job_limits,
+                &body,
+            )
+            .await?;
+            db::set_job_backend_id(db, job.id, &batch_id).await?;
+            tracing::info!(
+                "Job {} submitted to {} as {}",
+                job.id,
+                scheduler.name(),
+                batch_id
+            );
+
+            // 5. Poll the scheduler for completion
+            poll_batch_completion(db, ssh, &*scheduler, job.id, &batch_id, &log_path).await?
+        }
+    };
+
+    // 6. Store final peak memory / CPU time (non-critical)
+    if let Err(e) = usage::record_final_usage(db, ssh.executor(), job.id).await {
+        tracing::warn!("Resource usage not recorded for job {}: {}", job.id, e);
+    }
+
+    // 7. Ingest benchmark_results.csv (non-critical)
+    if status == "completed" {
+        if let Err(e) = results::ingest_job_results(db, ssh, job.id).await {
+            tracing::warn!("Results not ingested for job {}: {}", job.id, e);
+        }
+    }
+
+    // 8. Keep the log tail on the job, then archive the full log (non-critical)
+    match ssh.executor().tail_logs(&log_path, 200).await {
+        Ok(logs) => {
+            sqlx::query("UPDATE jobs SET log_content = ? WHERE id = ?")
+                .bind(&logs)
+                .bind(job.id)
+                .execute(db)
+                .await
+                .ok();
+        }
+        Err(e) => tracing::warn!("Log tail not read for job {}: {}", job.id, e),
+    }
+    log_archive::archive_finished_job(db, ssh, config, job.id).await;
+
+    tracing::info!("Job {} completed", job.id);
+    Ok(())
+}
+
+/// Run the license preflight check for a job (no-op when disabled in config)
+///
+/// SSH failures while querying are treated as transient and delay the job.
+pub(crate) async fn license_preflight(
+    db: &SqlitePool,
+    ssh: &SshManager,
+    job: &Job,
+    config: &AppConfig,
+) -> PreflightOutcome {
+    if !config.license.enabled {
+        return PreflightOutcome::Ready(None);
+    }
+
+    let env_prelude =
+        match get_project_env_profile(db, job.project_id)
+            .await
+            .and_then(|project_profile| {
+                config.resolve_env_profile(job.env_profile.as_deref(), project_profile.as_deref())
+            }) {
+            Ok(profile) => profile.map(|p| p.to_shell_prelude()).unwrap_or_default(),
+            Err(e) => return PreflightOutcome::Block(e),
+        };
+
+    match license::query_license(ssh.executor(), &env_prelude, &config.license.command).await {
+        Ok(status) => license::evaluate(
+            &status,
+            chrono::Local::now().date_naive(),
+            config.license.warn_days,
+        ),
+        Err(e) => PreflightOutcome::Delay(e),
+    }
+}
+
+/// Shell command running a job through the wrapper, output sent to its log
+///
+/// `prelude` (environment profile, limit variables) is prefixed to the wrapper call.
+fn wrapper_command(
+    job_id: i64,
+    jobs_path: &str,
+    log_path: &str,
+    prelude: &str,
+    command: &str,
+) -> String {
+    format!(
+        "mkdir -p {jobs_path}; exec > {log_path} 2>&1; export PYTHONUNBUFFERED=1; {prelude}~/.solverpilot/bin/job_wrapper.sh {job_id} {command}"
+    )
+}
+
+/// Start a job in a detached tmux session running the wrapper
+async fn start_tmux_job(ssh: &SshManager, job_id: i64, wrapper_cmd: &str) -> Result<(), String> {
+    let session_name = generate_session_name(job_id);
 
     // Check for session collision (unlikely but handle it)
     let check_session = format!("tmux has-session -t {session_name} 2>/dev/null");
@@ -390,39 +1139,115 @@ async fn execute_job(
     }
 
     // Create new session
-    let create_session = format!("tmux new-session -d -s {session_name} \"{wrapper_cmd}\"");
+    let create_session = format!(
+        "tmux new-session -d -s {session_name} '{}'",
+        job::escape_single_quotes(wrapper_cmd)
+    );
     ssh.executor()
         .execute(&create_session)
         .await
         .map_err(|e| format!("Failed to create tmux session: {e}"))?;
 
-    tracing::info!("tmux session {} created for job {}", session_name, job.id);
+    tracing::info!("tmux session {} created for job {}", session_name, job_id);
+    Ok(())
+}
+
+/// Poll a batch job through its scheduler until it leaves it
+///
+/// Updates the local job (status, `finished_at`, failure reason) and returns
+/// its final status ("completed", "failed" or "killed"). The job's progress is
+/// read from its log every 30 seconds meanwhile.
+async fn poll_batch_completion(
+    db: &SqlitePool,
+    ssh: &SshManager,
+    scheduler: &dyn BatchScheduler,
+    job_id: i64,
+    batch_id: &str,
+    log_path: &str,
+) -> Result<String, String> {
+    let mut poll_interval = interval(Duration::from_secs(2));
+    let mut polls: u32 = 0;
+
+    loop {
+        poll_interval.tick().await;
+        polls += 1;
+        if polls.is_multiple_of(PROGRESS_POLLS) {
+            record_progress(db, ssh, job_id, log_path).await;
+        }
+
+        let state = match batch::query_state(scheduler, ssh.executor(), batch_id).await {
+            Ok(Some(state)) => state,
+            // Not yet visible to the scheduler
+            Ok(None) => continue,
+            Err(e) => {
+                // Temporary SSH issues shouldn't fail the job
+                tracing::warn!(
+                    "Failed to query {} job {}: {}",
+                    scheduler.name(),
+                    batch_id,
+                    e
+                );
+                continue;
+            }
+        };
+
+        let BatchState::Finished {
+            ref status,
+            exit_code,
+            ..
+        } = state
+        else {
+            tracing::debug!("Job {job_id} still active ({state:?})");
+            continue;
+        };
 
-    // Store session name in DB (for future reference)
-    let session_info = format!("tmux_session: {session_name}");
-    sqlx::query("UPDATE jobs SET log_content = ? WHERE id = ?")
-        .bind(&session_info)
-        .bind(job.id)
+        let status = db::status_to_str(status).to_string();
+        tracing::info!("Job {} {} (exit code {:?})", job_id, status, exit_code);
+
+        // The wrapper's own error message, when it wrote one, is more precise
+        sqlx::query(
+            "
+            UPDATE jobs
+            SET status = ?,
+                finished_at = datetime('now'),
+                progress_current = progress_total,
+                error_message = COALESCE(error_message, ?)
+            WHERE id = ?
+            ",
+        )
+        .bind(&status)
+        .bind(state.failure_message())
+        .bind(job_id)
         .execute(db)
         .await
-        .ok(); // Non-critical, continue if fails
+        .map_err(|e| format!("Failed to update job: {e}"))?;
 
-    // 5. Poll for completion
-    poll_job_completion(db, ssh, job.id).await?;
-
-    tracing::info!("Job {} completed", job.id);
-    Ok(())
+        return Ok(status);
+    }
 }
 
 /// Poll server DB every 2 seconds for job completion
 ///
 /// Queries server `SQLite` database via SSH to check job status.
 /// Updates local DB when status changes to 'completed' or 'failed'.
-async fn poll_job_completion(db: &SqlitePool, ssh: &SshManager, job_id: i64) -> Result<(), String> {
+/// Reads the job's progress from its log every 30 seconds meanwhile.
+///
+/// Returns the final status (`completed` or `failed`).
+async fn poll_job_completion(
+    db: &SqlitePool,
+    ssh: &SshManager,
+    job_id: i64,
+    log_path: &str,
+) -> Result<String, String> {
     let mut poll_interval = interval(Duration::from_secs(2));
+    let mut polls: u32 = 0;
 
     loop {
         poll_interval.tick().await;
+        polls += 1;
+        if polls.is_multiple_of(PROGRESS_POLLS) {
+            record_progress(db, ssh, job_id, log_path).await;
+        }
 
         // Query server DB
         // Note: job_id is i64 so SQL injection is not possible
@@ -453,7 +1278,7 @@ async fn poll_job_completion(db: &SqlitePool, ssh: &SshManager, job_id: i64) ->
                         .await
                         .map_err(|e| format!("Failed to update job: {e}"))?;
 
-                        return Ok(());
+                        return Ok(status);
                     }
 
                     tracing::debug!("Job {job_id} still running (status: {status})");
@@ -467,15 +1292,89 @@ async fn poll_job_completion(db: &SqlitePool, ssh: &SshManager, job_id: i64) ->
     }
 }
 
+/// Completion polls between two progress readings (every 30 seconds)
+const PROGRESS_POLLS: u32 = 15;
+
+/// Record the `[x/y]` progress of a running job from its log tail (non-critical)
+///
+/// Feeds the progress rate of the job's completion estimate (`eta` module).
+async fn record_progress(db: &SqlitePool, ssh: &SshManager, job_id: i64, log_path: &str) {
+    let logs = match ssh.executor().tail_logs(log_path, 200).await {
+        Ok(logs) => logs,
+        Err(e) => {
+            tracing::debug!("Progress not read for job {}: {}", job_id, e);
+            return;
+        }
+    };
+    if let Some((current, total)) = job::parse_progress(&logs) {
+        if let Err(e) = db::update_job_progress(db, job_id, current, total).await {
+            tracing::warn!("Progress not recorded for job {}: {}", job_id, e);
+        }
+    }
+}
+
+/// Local time of a UTC start time, for messages
+fn local_time(start_time: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(start_time).map_or_else(
+        |_| start_time.to_string(),
+        |at| {
+            at.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        },
+    )
+}
+
+/// Suspend (`SIGSTOP`) or continue (`SIGCONT`) every process of a running job
+///
+/// Batch scheduler jobs cannot be suspended (usually reserved to administrators).
+async fn suspend_job(
+    db: &SqlitePool,
+    ssh_managers: &Mutex<SshManagers>,
+    config: &AppConfig,
+    job_id: i64,
+    server: &str,
+    suspend: bool,
+) -> Result<(), String> {
+    let (job_config, ssh) = servers::resolve(ssh_managers, config, Some(server)).await?;
+    if let Some(scheduler) = batch::scheduler(&job_config.ssh)? {
+        return Err(format!(
+            "{} jobs are left to the scheduler",
+            scheduler.name()
+        ));
+    }
+
+    if job_config.ssh.backend == ExecutionBackend::Local {
+        let pgid = db::get_job_backend_id(db, job_id)
+            .await?
+            .ok_or_else(|| format!("Job {job_id} has no local process group"))?;
+        return local::suspend(ssh.executor(), local::parse_pgid(&pgid)?, suspend).await;
+    }
+
+    let signal = if suspend { "STOP" } else { "CONT" };
+    ssh.executor()
+        .execute(&tmux_signal_command(&generate_session_name(job_id), signal))
+        .await
+        .map_err(|e| format!("Failed to signal job {job_id}: {e}"))?;
+    Ok(())
+}
+
+/// Command sending a signal to every process of a tmux job
+///
+/// The pane's process leads its own session, which the wrapper and the
+/// solver stay in.
+fn tmux_signal_command(session_name: &str, signal: &str) -> String {
+    format!(
+        "sid=$(tmux display-message -p -t {session_name} '#{{pane_pid}}') && pkill -{signal} -s \"$sid\""
+    )
+}
+
 /// Rsync project to remote server
 ///
-/// Syncs project directory to ~/solverpilot-projects/ on remote server.
+/// Syncs project directory to ~/solverpilot-projects/ on remote server
+/// (in the local home with the local backend).
 /// Excludes .git and __pycache__ directories.
-async fn rsync_project(
-    project_path: &str,
-    ssh_host: &str,
-    ssh_username: &str,
-) -> Result<(), String> {
+async fn rsync_project(project_path: &str, transfer: &SshTransfer) -> Result<(), String> {
     let remote_base = "~/solverpilot-projects/";
 
     // Extract project name from path
@@ -485,7 +1384,10 @@ async fn rsync_project(
         .to_str()
         .ok_or("Invalid project name")?;
 
-    let remote_dest = format!("{ssh_username}@{ssh_host}:{remote_base}{project_name}");
+    let remote_dest = transfer.remote_spec(&format!("{remote_base}{project_name}"));
+    transfer
+        .prepare_destination(&remote_dest)
+        .map_err(|e| e.to_string())?;
 
     tracing::debug!("Executing rsync to {}", remote_dest);
 
@@ -527,6 +1429,24 @@ async fn get_project_path(db: &SqlitePool, project_id: Option<i64>) -> Result<St
     Ok(path)
 }
 
+/// Get the environment profile selected on the job's project
+async fn get_project_env_profile(
+    db: &SqlitePool,
+    project_id: Option<i64>,
+) -> Result<Option<String>, String> {
+    let Some(project_id) = project_id else {
+        return Ok(None);
+    };
+
+    let row = sqlx::query("SELECT env_profile FROM projects WHERE id = ?")
+        .bind(project_id)
+        .fetch_optional(db)
+        .await
+        .map_err(|e| format!("Failed to get project environment profile: {e}"))?;
+
+    Ok(row.and_then(|r| r.get("env_profile")))
+}
+
 /// Generate unique tmux session name
 ///
 /// Format: `solverpilot_{username}_{job_id:0:8}` (truncated to 8 chars)
@@ -566,6 +1486,21 @@ fn parse_sql_output(output: &str) -> Option<(String, Option<i32>, Option<String>
     }
 }
 
+/// Mark job as running on `server` in local DB
+async fn mark_job_running(db: &SqlitePool, job_id: i64, server: &str) -> Result<(), String> {
+    sqlx::query(
+        "UPDATE jobs SET status = 'running', started_at = datetime('now'), server = ? WHERE id = ?",
+    )
+    .bind(server)
+    .bind(job_id)
+    .execute(db)
+    .await
+    .map_err(|e| format!("Failed to update job status: {e}"))?;
+
+    tracing::info!("Job {} marked as running", job_id);
+    Ok(())
+}
+
 /// Mark job as failed in local DB
 async fn mark_job_failed(db: &SqlitePool, job_id: i64, error: &str) -> Result<(), String> {
     sqlx::query(
@@ -618,6 +1553,8 @@ pub async fn load_queue_state(db: &SqlitePool) -> Result<QueueState, String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::test_config;
+    use crate::ssh::LocalShell;
 
     #[test]
     fn test_generate_session_name_format() {
@@ -756,12 +1693,18 @@ mod tests {
     fn test_tmux_create_session_command() {
         // Verify tmux session creation command format
         let session_name = "solverpilot_alice_12345678";
-        let wrapper_cmd = "~/.solverpilot/bin/job_wrapper.sh 12345678 python3 bench.py";
-        let create_cmd = format!("tmux new-session -d -s {session_name} \"{wrapper_cmd}\"");
+        let wrapper_cmd = "export GUROBI_HOME=\"$HOME/gurobi\"; ~/.solverpilot/bin/job_wrapper.sh 12345678 python3 bench.py";
+        let create_cmd = format!(
+            "tmux new-session -d -s {session_name} '{}'",
+            job::escape_single_quotes(wrapper_cmd)
+        );
 
         assert!(create_cmd.starts_with("tmux new-session -d -s"));
         assert!(create_cmd.contains(session_name));
         assert!(create_cmd.contains("job_wrapper.sh"));
+        // Environment prelude stays inside the single-quoted session command
+        assert!(create_cmd.ends_with("bench.py'"));
+        assert!(create_cmd.contains("'export GUROBI_HOME=\"$HOME/gurobi\"; "));
     }
 
     // Story 2.5 - Queue state tests
@@ -833,6 +1776,48 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_start_queue_from_non_idle_fails() -> Result<(), Box<dyn std::error::Error>> {
+        let db = SqlitePool::connect(":memory:").await?;
+        let config = test_config("")?;
+        let ssh_managers = Arc::new(Mutex::new(SshManagers::new()));
+        let manager = QueueManager::new();
+
+        // Paused: resume, not start
+        *manager.queue_state.lock().await = QueueState::Paused;
+        let result = manager
+            .start_processing(db.clone(), Arc::clone(&ssh_managers), config.clone())
+            .await;
+        assert!(result.is_err());
+        assert_eq!(manager.get_state().await, QueueState::Paused);
+
+        // Idle again but the previous loop still alive: refused too
+        *manager.queue_state.lock().await = QueueState::Idle;
+        *manager.processing_task.lock().await = Some(tokio::spawn(std::future::pending()));
+        let result = manager.start_processing(db, ssh_managers, config).await;
+        assert!(result.is_err());
+        assert_eq!(manager.get_state().await, QueueState::Idle);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pause_wakes_waiting_loop() -> Result<(), Box<dyn std::error::Error>> {
+        let db = SqlitePool::connect(":memory:").await?;
+        sqlx::query("CREATE TABLE IF NOT EXISTS metadata (key TEXT PRIMARY KEY, value TEXT)")
+            .execute(&db)
+            .await?;
+        let manager = QueueManager::new();
+        *manager.queue_state.lock().await = QueueState::Running;
+
+        // A long retry (e.g. license wait) cut short by the pause
+        let waiting = manager.clone();
+        let waiter = tokio::spawn(async move { waiting.wait(Duration::MAX).await });
+        manager.pause_processing(&db).await?;
+        tokio::time::timeout(Duration::from_secs(5), waiter).await??;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_resume_queue_from_non_paused_fails() -> Result<(), Box<dyn std::error::Error>> {
         // This test validates that resume_processing checks state before proceeding.
@@ -899,6 +1884,119 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_execution_window_pauses_and_resumes() -> Result<(), Box<dyn std::error::Error>> {
+        let db = SqlitePool::connect(":memory:").await?;
+        sqlx::query("CREATE TABLE IF NOT EXISTS metadata (key TEXT PRIMARY KEY, value TEXT)")
+            .execute(&db)
+            .await?;
+        let config = test_config(
+            r#"
+[queue_settings]
+windows = ["* 20-23 * * *"]
+window_end = "suspend"
+"#,
+        )?;
+        let ssh_managers = Mutex::new(SshManagers::new());
+        let manager = QueueManager::new();
+        *manager.queue_state.lock().await = QueueState::Running;
+
+        // Window ends: paused until the next one, which resumes it
+        manager.close_window(&db, &ssh_managers, &config).await;
+        assert_eq!(manager.get_state().await, QueueState::Paused);
+        assert!(manager.schedule_paused().await);
+        assert_eq!(load_queue_state(&db).await?, QueueState::Paused);
+
+        manager.open_window(&db).await;
+        assert_eq!(manager.get_state().await, QueueState::Running);
+        assert!(!manager.schedule_paused().await);
+        assert_eq!(load_queue_state(&db).await?, QueueState::Running);
+
+        // Paused by hand while waiting: stays paused when the window opens
+        manager.close_window(&db, &ssh_managers, &config).await;
+        manager.pause_processing(&db).await?;
+        assert!(!manager.schedule_paused().await);
+        manager.open_window(&db).await;
+        assert_eq!(manager.get_state().await, QueueState::Paused);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pinned_server_not_connected_waits() -> Result<(), Box<dyn std::error::Error>> {
+        let db = SqlitePool::connect(":memory:").await?;
+        let config = test_config("")?;
+        let job: Job = serde_json::from_value(serde_json::json!({
+            "id": 7,
+            "benchmark_name": "bench.py",
+            "status": "pending",
+            "created_at": "2026-10-19T08:00:00Z",
+            "progress_current": 0,
+            "progress_total": 0,
+            "log_content": "",
+        }))?;
+
+        // No SSH manager for the default server: the job stays queued, with a reason
+        let ssh_managers = Mutex::new(SshManagers::new());
+        let ready = Mutex::new(BTreeSet::new());
+        match place_next_job(
+            &db,
+            &ssh_managers,
+            &config,
+            vec![job],
+            &BTreeMap::new(),
+            &ready,
+        )
+        .await
+        {
+            Placement::Wait { reason, .. } => {
+                assert_eq!(reason.as_deref(), Some("Server default not connected"));
+            }
+            Placement::Start { .. } | Placement::Fail { .. } => {
+                return Err("pinned job should wait for its server".into())
+            }
+        }
+        Ok(())
+    }
+
+    /// Wrapper deployed before a server's first job only; a failed upgrade is the reason
+    #[tokio::test]
+    async fn test_server_ready_once_per_queue_start() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let home = dir.path().join("home");
+        let mut config = test_config("")?;
+        config.ssh.backend = ExecutionBackend::Local;
+        let ssh = SshManager::local(config.clone(), LocalShell::new(home.clone())?);
+        let ready = Mutex::new(BTreeSet::new());
+
+        assert_eq!(server_ready(&ready, "default", &ssh).await, None);
+        let wrapper = home.join(".solverpilot/bin/job_wrapper.sh");
+        assert!(wrapper.exists());
+        assert!(ready.lock().await.contains("default"));
+
+        // Checked once: not redeployed for the following jobs
+        std::fs::remove_file(&wrapper)?;
+        assert_eq!(server_ready(&ready, "default", &ssh).await, None);
+        assert!(!wrapper.exists());
+
+        // Upgrade impossible (bin directory blocked by a file): reported, not cached
+        let broken_home = dir.path().join("broken");
+        std::fs::create_dir_all(&broken_home)?;
+        std::fs::write(broken_home.join(".solverpilot"), "")?;
+        let broken = SshManager::local(config, LocalShell::new(broken_home)?);
+        let reason = server_ready(&ready, "broken", &broken).await;
+        assert!(reason.is_some_and(|r| r.starts_with("Wrapper upgrade failed on broken")));
+        assert!(!ready.lock().await.contains("broken"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_tmux_signal_command() {
+        assert_eq!(
+            tmux_signal_command("solverpilot_alice_42", "STOP"),
+            "sid=$(tmux display-message -p -t solverpilot_alice_42 '#{pane_pid}') && pkill -STOP -s \"$sid\""
+        );
+    }
+
     // Note: Full integration tests with mock SSH/DB require additional infrastructure
     // and are covered in end-to-end testing. See Story 2.4 and 2.5 Dev Notes.
 }
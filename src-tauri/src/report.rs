@@ -0,0 +1,752 @@
+//! Comparison reports across jobs
+//!
+//! Builds per-instance comparison tables from the results of several jobs
+//! (solver A vs B, branch X vs Y, this week vs last week) with:
+//! - Shifted geometric mean of runtimes (over instances common to all runs)
+//! - Pairwise win/loss counts
+//! - Dolan–Moré performance profiles
+//!
+//! Reports export to CSV, Markdown and standalone HTML (inline CSS + SVG).
+
+use crate::results;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+/// Default shift (seconds) for the shifted geometric mean
+pub const DEFAULT_SGM_SHIFT: f64 = 10.0;
+
+/// Relative runtime difference under which two solved runs tie
+const TIE_TOLERANCE: f64 = 0.01;
+
+/// Status values counted as solved (lowercase)
+const SOLVED_STATUSES: &[&str] = &[
+    "optimal",
+    "solved",
+    "ok",
+    "success",
+    "completed",
+    "feasible",
+];
+
+/// One instance result of a run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstanceResult {
+    pub instance: String,
+    pub status: Option<String>,
+    pub runtime: Option<f64>,
+    pub gap: Option<f64>,
+}
+
+impl InstanceResult {
+    /// Solved if the status says so, or if no status is reported but a runtime is
+    pub fn is_solved(&self) -> bool {
+        self.status.as_ref().map_or_else(
+            || self.runtime.is_some(),
+            |s| SOLVED_STATUSES.contains(&s.trim().to_lowercase().as_str()),
+        )
+    }
+}
+
+/// Results of one job, as compared in a report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunResults {
+    pub job_id: i64,
+    pub label: String,
+    pub results: Vec<InstanceResult>,
+}
+
+/// Job left out of a report: no per-job results stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedRun {
+    pub job_id: i64,
+    pub label: String,
+}
+
+/// Per-run summary line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub job_id: i64,
+    pub label: String,
+    pub instances: usize,
+    pub solved: usize,
+    /// Shifted geometric mean of runtime over common instances
+    pub sgm_runtime: Option<f64>,
+}
+
+/// One row of the per-instance table (one cell per run)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceRow {
+    pub instance: String,
+    pub cells: Vec<Option<InstanceResult>>,
+}
+
+/// Performance profile curve of one run: `(tau, fraction of instances)` steps
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileSeries {
+    pub label: String,
+    pub points: Vec<(f64, f64)>,
+}
+
+/// Complete comparison report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparisonReport {
+    pub generated_at: String,
+    pub sgm_shift: f64,
+    pub common_instances: usize,
+    pub runs: Vec<RunSummary>,
+    pub instances: Vec<InstanceRow>,
+    /// `wins[i][j]` = instances where run `i` beats run `j`
+    pub wins: Vec<Vec<u32>>,
+    pub performance_profile: Vec<ProfileSeries>,
+    /// Requested jobs left out (listed in the Markdown and HTML exports)
+    pub skipped: Vec<SkippedRun>,
+}
+
+/// Export format of a report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Csv,
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    /// Parse format name (`csv`, `markdown`/`md`, `html`)
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "markdown" | "md" => Ok(Self::Markdown),
+            "html" => Ok(Self::Html),
+            other => Err(format!("Unknown report format: {other}")),
+        }
+    }
+}
+
+/// Parse a `benchmark_results.csv` file, inferring instance/runtime/status/gap columns
+///
+/// Rows without an instance are skipped (see `results::ResultTable::instance_results`).
+pub fn parse_results_csv(content: &str) -> Result<Vec<InstanceResult>, String> {
+    Ok(results::parse_csv(content)?
+        .instance_results()
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
+/// Shifted geometric mean: `exp(mean(ln(t + shift))) - shift`
+pub fn shifted_geometric_mean(values: &[f64], shift: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let sum: f64 = values.iter().map(|v| (v.max(0.0) + shift).ln()).sum();
+    Some((sum / count_f64(values.len())).exp() - shift)
+}
+
+/// Build the comparison report for the given runs
+pub fn build_report(runs: &[RunResults], sgm_shift: f64) -> ComparisonReport {
+    // Per-run lookup by instance (last row wins on duplicates)
+    let lookups: Vec<BTreeMap<&str, &InstanceResult>> = runs
+        .iter()
+        .map(|run| {
+            run.results
+                .iter()
+                .map(|r| (r.instance.as_str(), r))
+                .collect()
+        })
+        .collect();
+
+    let all_instances: BTreeSet<&str> = lookups.iter().flat_map(|l| l.keys().copied()).collect();
+    let common: Vec<&str> = all_instances
+        .iter()
+        .copied()
+        .filter(|i| lookups.iter().all(|l| l.contains_key(i)))
+        .collect();
+
+    let summaries = runs
+        .iter()
+        .zip(&lookups)
+        .map(|(run, lookup)| {
+            let runtimes: Vec<f64> = common
+                .iter()
+                .filter_map(|i| lookup.get(i).and_then(|r| r.runtime))
+                .collect();
+            RunSummary {
+                job_id: run.job_id,
+                label: run.label.clone(),
+                instances: lookup.len(),
+                solved: lookup.values().filter(|r| r.is_solved()).count(),
+                sgm_runtime: (runtimes.len() == common.len())
+                    .then(|| shifted_geometric_mean(&runtimes, sgm_shift))
+                    .flatten(),
+            }
+        })
+        .collect();
+
+    let instances = all_instances
+        .iter()
+        .map(|instance| InstanceRow {
+            instance: (*instance).to_string(),
+            cells: lookups
+                .iter()
+                .map(|l| l.get(instance).map(|r| (*r).clone()))
+                .collect(),
+        })
+        .collect();
+
+    let wins = lookups
+        .iter()
+        .map(|a| {
+            lookups
+                .iter()
+                .map(|b| {
+                    let count = common.iter().filter(|i| beats(a[*i], b[*i])).count();
+                    u32::try_from(count).unwrap_or(u32::MAX)
+                })
+                .collect()
+        })
+        .collect();
+
+    ComparisonReport {
+        generated_at: chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
+        sgm_shift,
+        common_instances: common.len(),
+        runs: summaries,
+        instances,
+        wins,
+        performance_profile: performance_profile(runs, &lookups, &common),
+        skipped: Vec::new(),
+    }
+}
+
+/// `a` beats `b`: solved while `b` is not, or solved faster beyond the tie tolerance
+fn beats(a: &InstanceResult, b: &InstanceResult) -> bool {
+    match (a.is_solved(), b.is_solved()) {
+        (true, false) => true,
+        (true, true) => match (a.runtime, b.runtime) {
+            (Some(ta), Some(tb)) => ta < tb && (tb - ta) > TIE_TOLERANCE * tb.max(ta),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Dolan–Moré performance profile over common instances
+///
+/// For instance `p` and run `s`, `r = t(p,s) / min_s t(p,s)` (unsolved → ∞).
+/// `rho_s(tau)` is the fraction of instances with `r <= tau`, sampled at every ratio.
+fn performance_profile(
+    runs: &[RunResults],
+    lookups: &[BTreeMap<&str, &InstanceResult>],
+    common: &[&str],
+) -> Vec<ProfileSeries> {
+    let solved_time = |lookup: &BTreeMap<&str, &InstanceResult>, instance: &str| {
+        lookup
+            .get(instance)
+            .filter(|r| r.is_solved())
+            .and_then(|r| r.runtime)
+    };
+
+    let ratios: Vec<Vec<f64>> = lookups
+        .iter()
+        .map(|lookup| {
+            common
+                .iter()
+                .filter_map(|instance| {
+                    let best = lookups
+                        .iter()
+                        .filter_map(|l| solved_time(l, instance))
+                        .fold(f64::INFINITY, f64::min);
+                    // Instances nobody solved do not contribute to any curve
+                    best.is_finite().then(|| {
+                        solved_time(lookup, instance).map_or(f64::INFINITY, |t| {
+                            if best > 0.0 {
+                                t / best
+                            } else if t > 0.0 {
+                                f64::INFINITY
+                            } else {
+                                1.0
+                            }
+                        })
+                    })
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut taus: Vec<f64> = ratios
+        .iter()
+        .flatten()
+        .copied()
+        .filter(|r| r.is_finite())
+        .collect();
+    taus.sort_by(f64::total_cmp);
+    taus.dedup();
+
+    let total = count_f64(common.len());
+    runs.iter()
+        .zip(&ratios)
+        .map(|(run, run_ratios)| ProfileSeries {
+            label: run.label.clone(),
+            points: taus
+                .iter()
+                .map(|tau| {
+                    let within = run_ratios.iter().filter(|r| **r <= *tau).count();
+                    let fraction = if total > 0.0 {
+                        count_f64(within) / total
+                    } else {
+                        0.0
+                    };
+                    (*tau, fraction)
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn count_f64(n: usize) -> f64 {
+    f64::from(u32::try_from(n).unwrap_or(u32::MAX))
+}
+
+fn fmt_opt(value: Option<f64>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| format!("{v:.2}"))
+}
+
+/// Render a report in the requested format
+pub fn render(report: &ComparisonReport, format: ReportFormat) -> Result<String, String> {
+    match format {
+        ReportFormat::Csv => to_csv(report),
+        ReportFormat::Markdown => Ok(to_markdown(report)),
+        ReportFormat::Html => Ok(to_html(report)),
+    }
+}
+
+/// Per-instance table as CSV (`instance`, then status/runtime/gap per run)
+pub fn to_csv(report: &ComparisonReport) -> Result<String, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    let mut header = vec!["instance".to_string()];
+    for run in &report.runs {
+        for col in ["status", "runtime", "gap"] {
+            header.push(format!("{} {col}", run.label));
+        }
+    }
+    writer.write_record(&header).map_err(|e| e.to_string())?;
+
+    for row in &report.instances {
+        let mut record = vec![row.instance.clone()];
+        for cell in &row.cells {
+            record.push(
+                cell.as_ref()
+                    .and_then(|c| c.status.clone())
+                    .unwrap_or_default(),
+            );
+            record.push(
+                cell.as_ref()
+                    .and_then(|c| c.runtime)
+                    .map(|v| v.to_string())
+                    .unwrap_or_default(),
+            );
+            record.push(
+                cell.as_ref()
+                    .and_then(|c| c.gap)
+                    .map(|v| v.to_string())
+                    .unwrap_or_default(),
+            );
+        }
+        writer.write_record(&record).map_err(|e| e.to_string())?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+    String::from_utf8(bytes).map_err(|e| e.to_string())
+}
+
+fn md_escape(s: &str) -> String {
+    s.replace('|', "\\|")
+}
+
+/// Markdown report: summary, win/loss matrix, per-instance table
+pub fn to_markdown(report: &ComparisonReport) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Comparison report\n");
+    let _ = writeln!(
+        out,
+        "Generated {} — {} common instances, SGM shift {}s\n",
+        report.generated_at, report.common_instances, report.sgm_shift
+    );
+
+    if !report.skipped.is_empty() {
+        let _ = writeln!(out, "Skipped (no per-job results stored):\n");
+        for run in &report.skipped {
+            let _ = writeln!(out, "- {} (job {})", md_escape(&run.label), run.job_id);
+        }
+        let _ = writeln!(out);
+    }
+
+    let _ = writeln!(out, "## Summary\n");
+    let _ = writeln!(out, "| Run | Job | Instances | Solved | SGM runtime (s) |");
+    let _ = writeln!(out, "|---|---|---|---|---|");
+    for run in &report.runs {
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} | {} |",
+            md_escape(&run.label),
+            run.job_id,
+            run.instances,
+            run.solved,
+            fmt_opt(run.sgm_runtime)
+        );
+    }
+
+    let _ = writeln!(out, "\n## Wins (row beats column)\n");
+    let _ = write!(out, "| |");
+    for run in &report.runs {
+        let _ = write!(out, " {} |", md_escape(&run.label));
+    }
+    let _ = writeln!(out, "\n|---|{}", "---|".repeat(report.runs.len()));
+    for (run, row) in report.runs.iter().zip(&report.wins) {
+        let _ = write!(out, "| {} |", md_escape(&run.label));
+        for count in row {
+            let _ = write!(out, " {count} |");
+        }
+        let _ = writeln!(out);
+    }
+
+    let _ = writeln!(out, "\n## Instances\n");
+    let _ = write!(out, "| Instance |");
+    for run in &report.runs {
+        let _ = write!(out, " {} |", md_escape(&run.label));
+    }
+    let _ = writeln!(out, "\n|---|{}", "---|".repeat(report.runs.len()));
+    for row in &report.instances {
+        let _ = write!(out, "| {} |", md_escape(&row.instance));
+        for cell in &row.cells {
+            let _ = write!(out, " {} |", md_escape(&cell_text(cell.as_ref())));
+        }
+        let _ = writeln!(out);
+    }
+
+    out
+}
+
+/// Cell summary: `status runtime s (gap %)`
+fn cell_text(cell: Option<&InstanceResult>) -> String {
+    let Some(cell) = cell else {
+        return "-".to_string();
+    };
+    let mut text = cell.status.clone().unwrap_or_default();
+    if let Some(runtime) = cell.runtime {
+        let _ = write!(text, " {runtime:.2}s");
+    }
+    if let Some(gap) = cell.gap {
+        let _ = write!(text, " ({gap}%)");
+    }
+    text.trim().to_string()
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,sans-serif;margin:2rem;color:#1e293b}\
+table{border-collapse:collapse;margin:1rem 0}th,td{border:1px solid #cbd5e1;padding:4px 8px;text-align:right}\
+th:first-child,td:first-child{text-align:left}th{background:#f1f5f9}\
+td.win{background:#dcfce7}td.unsolved{color:#b91c1c}";
+
+const PROFILE_COLORS: &[&str] = &[
+    "#2563eb", "#dc2626", "#16a34a", "#9333ea", "#ea580c", "#0891b2", "#4d7c0f", "#be185d",
+];
+
+/// Standalone HTML report (no external assets)
+pub fn to_html(report: &ComparisonReport) -> String {
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>Comparison report</title>\
+         <style>{HTML_STYLE}</style></head><body><h1>Comparison report</h1>\
+         <p>Generated {} — {} common instances, SGM shift {}s</p>",
+        html_escape(&report.generated_at),
+        report.common_instances,
+        report.sgm_shift
+    );
+
+    if !report.skipped.is_empty() {
+        out.push_str("<p>Skipped (no per-job results stored):</p><ul>");
+        for run in &report.skipped {
+            let _ = write!(
+                out,
+                "<li>{} (job {})</li>",
+                html_escape(&run.label),
+                run.job_id
+            );
+        }
+        out.push_str("</ul>");
+    }
+
+    out.push_str("<h2>Summary</h2><table><tr><th>Run</th><th>Job</th><th>Instances</th><th>Solved</th><th>SGM runtime (s)</th></tr>");
+    for run in &report.runs {
+        let _ = write!(
+            out,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            html_escape(&run.label),
+            run.job_id,
+            run.instances,
+            run.solved,
+            fmt_opt(run.sgm_runtime)
+        );
+    }
+    out.push_str("</table>");
+
+    out.push_str("<h2>Wins (row beats column)</h2><table><tr><th></th>");
+    for run in &report.runs {
+        let _ = write!(out, "<th>{}</th>", html_escape(&run.label));
+    }
+    out.push_str("</tr>");
+    for (i, (run, row)) in report.runs.iter().zip(&report.wins).enumerate() {
+        let _ = write!(out, "<tr><td>{}</td>", html_escape(&run.label));
+        for (j, count) in row.iter().enumerate() {
+            let class = if i != j && report.wins.get(j).and_then(|r| r.get(i)) < Some(count) {
+                " class=\"win\""
+            } else {
+                ""
+            };
+            let _ = write!(out, "<td{class}>{count}</td>");
+        }
+        out.push_str("</tr>");
+    }
+    out.push_str("</table>");
+
+    out.push_str("<h2>Performance profile</h2>");
+    out.push_str(&profile_svg(&report.performance_profile));
+
+    out.push_str("<h2>Instances</h2><table><tr><th>Instance</th>");
+    for run in &report.runs {
+        let _ = write!(out, "<th>{}</th>", html_escape(&run.label));
+    }
+    out.push_str("</tr>");
+    for row in &report.instances {
+        let _ = write!(out, "<tr><td>{}</td>", html_escape(&row.instance));
+        for cell in &row.cells {
+            let class = if cell.as_ref().is_some_and(|c| !c.is_solved()) {
+                " class=\"unsolved\""
+            } else {
+                ""
+            };
+            let _ = write!(
+                out,
+                "<td{class}>{}</td>",
+                html_escape(&cell_text(cell.as_ref()))
+            );
+        }
+        out.push_str("</tr>");
+    }
+    out.push_str("</table></body></html>\n");
+    out
+}
+
+/// Step-plot SVG of the performance profile (log2 tau axis)
+fn profile_svg(series: &[ProfileSeries]) -> String {
+    const WIDTH: f64 = 640.0;
+    const HEIGHT: f64 = 320.0;
+    const MARGIN: f64 = 40.0;
+    const PLOT_WIDTH: f64 = WIDTH - 2.0 * MARGIN;
+    const PLOT_HEIGHT: f64 = HEIGHT - 2.0 * MARGIN;
+
+    let max_tau = series
+        .iter()
+        .flat_map(|s| s.points.iter().map(|(tau, _)| *tau))
+        .fold(1.0_f64, f64::max);
+    let x_span = max_tau.log2().max(1.0);
+    let x = |tau: f64| (tau.log2() / x_span).mul_add(PLOT_WIDTH, MARGIN);
+    let y = |fraction: f64| fraction.mul_add(-PLOT_HEIGHT, HEIGHT - MARGIN);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{HEIGHT}\">\
+         <line x1=\"{MARGIN}\" y1=\"{0}\" x2=\"{1}\" y2=\"{0}\" stroke=\"#64748b\"/>\
+         <line x1=\"{MARGIN}\" y1=\"{MARGIN}\" x2=\"{MARGIN}\" y2=\"{0}\" stroke=\"#64748b\"/>\
+         <text x=\"{1}\" y=\"{2}\" font-size=\"12\" text-anchor=\"end\">τ = {max_tau:.2} (log₂)</text>\
+         <text x=\"4\" y=\"{MARGIN}\" font-size=\"12\">1.0</text>",
+        HEIGHT - MARGIN,
+        WIDTH - MARGIN,
+        HEIGHT - 8.0
+    );
+
+    for (i, s) in series.iter().enumerate() {
+        let color = PROFILE_COLORS[i % PROFILE_COLORS.len()];
+        let mut path = format!("M{MARGIN:.1},{:.1}", y(0.0));
+        let mut last = 0.0;
+        for (tau, fraction) in &s.points {
+            let _ = write!(
+                path,
+                " L{:.1},{:.1} L{:.1},{:.1}",
+                x(*tau),
+                y(last),
+                x(*tau),
+                y(*fraction)
+            );
+            last = *fraction;
+        }
+        let _ = write!(path, " L{:.1},{:.1}", WIDTH - MARGIN, y(last));
+        let _ = write!(
+            svg,
+            "<path d=\"{path}\" fill=\"none\" stroke=\"{color}\" stroke-width=\"2\"/>\
+             <text x=\"{:.1}\" y=\"{:.1}\" font-size=\"12\" fill=\"{color}\">{}</text>",
+            MARGIN + 8.0,
+            14.0_f64.mul_add(count_f64(i + 1), MARGIN),
+            html_escape(&s.label)
+        );
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(instance: &str, status: &str, runtime: f64) -> InstanceResult {
+        InstanceResult {
+            instance: instance.to_string(),
+            status: Some(status.to_string()),
+            runtime: Some(runtime),
+            gap: None,
+        }
+    }
+
+    fn run(job_id: i64, label: &str, results: Vec<InstanceResult>) -> RunResults {
+        RunResults {
+            job_id,
+            label: label.to_string(),
+            results,
+        }
+    }
+
+    #[test]
+    fn test_parse_results_csv_infers_columns() -> Result<(), String> {
+        let csv = "Config,Status,Runtime,MIPGap\nN5,OPTIMAL,1.5,0\nN7,TIME_LIMIT,60,12.5%\n,,,\n";
+        let results = parse_results_csv(csv)?;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].instance, "N5");
+        assert_eq!(results[0].runtime, Some(1.5));
+        assert!(results[0].is_solved());
+        assert_eq!(results[1].gap, Some(12.5));
+        assert!(!results[1].is_solved());
+        Ok(())
+    }
+
+    #[test]
+    fn test_shifted_geometric_mean() -> Result<(), String> {
+        let sgm = shifted_geometric_mean(&[10.0, 10.0], 10.0).ok_or("empty")?;
+        assert!((sgm - 10.0).abs() < 1e-9);
+
+        // sqrt((1+10)*(100+10)) - 10
+        let sgm = shifted_geometric_mean(&[1.0, 100.0], 10.0).ok_or("empty")?;
+        assert!((sgm - (1210.0_f64.sqrt() - 10.0)).abs() < 1e-9);
+
+        assert_eq!(shifted_geometric_mean(&[], 10.0), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wins_and_common_instances() {
+        let a = run(
+            1,
+            "A",
+            vec![
+                result("p1", "optimal", 1.0),
+                result("p2", "optimal", 10.0),
+                result("p3", "time_limit", 60.0),
+                result("only_a", "optimal", 1.0),
+            ],
+        );
+        let b = run(
+            2,
+            "B",
+            vec![
+                result("p1", "optimal", 2.0),
+                result("p2", "optimal", 10.05),
+                result("p3", "optimal", 30.0),
+            ],
+        );
+
+        let report = build_report(&[a, b], DEFAULT_SGM_SHIFT);
+        assert_eq!(report.common_instances, 3);
+        assert_eq!(report.instances.len(), 4);
+        // p1: A faster; p2: tie (within 1%); p3: only B solved
+        assert_eq!(report.wins, vec![vec![0, 1], vec![1, 0]]);
+        assert_eq!(report.runs[0].solved, 3);
+        assert_eq!(report.runs[1].solved, 3);
+    }
+
+    #[test]
+    fn test_performance_profile() {
+        let a = run(
+            1,
+            "A",
+            vec![result("p1", "optimal", 1.0), result("p2", "optimal", 4.0)],
+        );
+        let b = run(
+            2,
+            "B",
+            vec![result("p1", "optimal", 2.0), result("p2", "failed", 1.0)],
+        );
+
+        let report = build_report(&[a, b], DEFAULT_SGM_SHIFT);
+        let profile_a = &report.performance_profile[0].points;
+        let profile_b = &report.performance_profile[1].points;
+
+        // Ratios: A = [1, 1], B = [2, inf]
+        assert_eq!(profile_a, &vec![(1.0, 1.0), (2.0, 1.0)]);
+        assert_eq!(profile_b, &vec![(1.0, 0.0), (2.0, 0.5)]);
+    }
+
+    #[test]
+    fn test_exports() -> Result<(), String> {
+        let a = run(1, "A", vec![result("p1", "optimal", 1.0)]);
+        let b = run(2, "B|x", vec![result("p1", "optimal", 3.0)]);
+        let report = build_report(&[a, b], DEFAULT_SGM_SHIFT);
+
+        let csv = render(&report, ReportFormat::Csv)?;
+        assert!(csv.starts_with("instance,A status,A runtime,A gap,B|x status"));
+        assert!(csv.contains("p1,optimal,1,,optimal,3,"));
+
+        let md = render(&report, ReportFormat::Markdown)?;
+        assert!(md.contains("| B\\|x |"));
+        assert!(md.contains("| p1 | optimal 1.00s | optimal 3.00s |"));
+
+        let html = render(&report, ReportFormat::Html)?;
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<svg"));
+        assert!(html.contains("<td class=\"win\">1</td>"));
+        assert!(!html.contains("Skipped"));
+
+        assert_eq!(ReportFormat::parse("MD")?, ReportFormat::Markdown);
+        assert!(ReportFormat::parse("pdf").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_skipped_runs_listed() -> Result<(), String> {
+        let a = run(1, "A", vec![result("p1", "optimal", 1.0)]);
+        let b = run(2, "B", vec![result("p1", "optimal", 3.0)]);
+        let mut report = build_report(&[a, b], DEFAULT_SGM_SHIFT);
+        report.skipped = vec![SkippedRun {
+            job_id: 3,
+            label: "old <run>".to_string(),
+        }];
+
+        let md = render(&report, ReportFormat::Markdown)?;
+        assert!(md.contains("Skipped (no per-job results stored):\n\n- old <run> (job 3)\n"));
+        let html = render(&report, ReportFormat::Html)?;
+        assert!(html.contains("<li>old &lt;run&gt; (job 3)</li>"));
+        // The CSV export stays a plain per-instance table
+        assert!(!render(&report, ReportFormat::Csv)?.contains("old"));
+        Ok(())
+    }
+}
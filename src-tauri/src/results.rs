@@ -0,0 +1,368 @@
+//! Ingestion of `benchmark_results.csv` into the local database
+//!
+//! When a job completes, its results CSV is read from the per-job location recorded
+//! at launch (`~/.solverpilot-server/jobs/<id>/`), column types are
+//! inferred (integer, real, boolean, text) and every row is stored in the local
+//! `results` table keyed by job:
+//! - Well-known columns (instance, status, runtime, gap) are promoted to real columns
+//! - The full row is kept as a JSON object (`json_extract(data, '$.column')`)
+//! - The inferred schema is kept in `result_columns`
+
+use crate::db;
+use crate::job;
+use crate::report::{self, InstanceResult};
+use crate::ssh::SshManager;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqlitePool;
+
+/// Header candidates used to infer well-known result columns (lowercase)
+const INSTANCE_COLUMNS: &[&str] = &["instance", "config", "name", "problem", "model"];
+const RUNTIME_COLUMNS: &[&str] = &[
+    "runtime",
+    "time",
+    "solve_time",
+    "runtime_s",
+    "time_s",
+    "elapsed",
+    "duration",
+];
+const STATUS_COLUMNS: &[&str] = &["status", "result", "solver_status"];
+const GAP_COLUMNS: &[&str] = &["gap", "mipgap", "mip_gap", "gap_pct"];
+
+/// File name produced by benchmarks
+pub const RESULTS_FILE_NAME: &str = "benchmark_results.csv";
+
+/// Inferred type of a result column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnKind {
+    Integer,
+    Real,
+    Boolean,
+    Text,
+}
+
+impl ColumnKind {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Integer => "integer",
+            Self::Real => "real",
+            Self::Boolean => "boolean",
+            Self::Text => "text",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "integer" => Self::Integer,
+            "real" => Self::Real,
+            "boolean" => Self::Boolean,
+            _ => Self::Text,
+        }
+    }
+
+    /// Narrowest kind accepting every non-empty value
+    fn infer<'a>(values: impl Iterator<Item = &'a str>) -> Self {
+        let mut kind = None;
+        for value in values.filter(|v| !v.is_empty()) {
+            let value_kind = if value.parse::<i64>().is_ok() {
+                Self::Integer
+            } else if parse_real(value).is_some() {
+                Self::Real
+            } else if parse_bool(value).is_some() {
+                Self::Boolean
+            } else {
+                return Self::Text;
+            };
+            kind = Some(match (kind, value_kind) {
+                (None, k) => k,
+                (Some(a), b) if a == b => a,
+                (Some(Self::Integer | Self::Real), Self::Integer | Self::Real) => Self::Real,
+                _ => return Self::Text,
+            });
+        }
+        kind.unwrap_or(Self::Text)
+    }
+
+    /// Convert a raw CSV value to JSON according to the column kind
+    fn convert(self, value: &str) -> Value {
+        if value.is_empty() {
+            return Value::Null;
+        }
+        match self {
+            Self::Integer => value.parse::<i64>().map_or(Value::Null, Value::from),
+            Self::Real => parse_real(value).map_or(Value::Null, Value::from),
+            Self::Boolean => parse_bool(value).map_or(Value::Null, Value::from),
+            Self::Text => Value::from(value),
+        }
+    }
+}
+
+/// Numbers, allowing a trailing `%` (e.g. MIP gap)
+fn parse_real(value: &str) -> Option<f64> {
+    value
+        .trim_end_matches('%')
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" => Some(true),
+        "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// One column of the inferred schema
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResultColumn {
+    pub name: String,
+    pub kind: ColumnKind,
+}
+
+/// Parsed results CSV with inferred schema (typed JSON values, `null` when empty)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResultTable {
+    pub columns: Vec<ResultColumn>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+/// Per-job summary shown in history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultSummary {
+    pub job_id: i64,
+    pub rows: usize,
+    pub solved: usize,
+    /// Shifted geometric mean of runtime over rows reporting one
+    pub sgm_runtime: Option<f64>,
+    pub max_gap: Option<f64>,
+}
+
+impl ResultTable {
+    /// Position of the first column matching one of the candidates (case-insensitive)
+    fn find_column(&self, candidates: &[&str]) -> Option<usize> {
+        candidates.iter().find_map(|c| {
+            self.columns
+                .iter()
+                .position(|col| col.name.eq_ignore_ascii_case(c))
+        })
+    }
+
+    /// Row as a JSON object keyed by column name
+    pub fn row_object(&self, row: &[Value]) -> serde_json::Map<String, Value> {
+        self.columns
+            .iter()
+            .zip(row)
+            .map(|(col, value)| (col.name.clone(), value.clone()))
+            .collect()
+    }
+
+    /// Well-known columns of every row (`None` for rows without an instance)
+    ///
+    /// The instance column falls back to the first column.
+    pub fn instance_results(&self) -> Vec<Option<InstanceResult>> {
+        let instance_col = self.find_column(INSTANCE_COLUMNS).unwrap_or(0);
+        let runtime_col = self.find_column(RUNTIME_COLUMNS);
+        let status_col = self.find_column(STATUS_COLUMNS);
+        let gap_col = self.find_column(GAP_COLUMNS);
+
+        let text = |row: &[Value], col: Option<usize>| match col.and_then(|c| row.get(c)) {
+            Some(Value::String(s)) => Some(s.clone()),
+            Some(Value::Null) | None => None,
+            Some(other) => Some(other.to_string()),
+        };
+        let number = |row: &[Value], col: Option<usize>| {
+            col.and_then(|c| row.get(c)).and_then(|v| match v {
+                Value::String(s) => parse_real(s),
+                other => other.as_f64(),
+            })
+        };
+
+        self.rows
+            .iter()
+            .map(|row| {
+                text(row, Some(instance_col)).map(|instance| InstanceResult {
+                    instance,
+                    status: text(row, status_col),
+                    runtime: number(row, runtime_col),
+                    gap: number(row, gap_col),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Parse a results CSV and infer column kinds
+pub fn parse_csv(content: &str) -> Result<ResultTable, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("Invalid results CSV header: {e}"))?
+        .clone();
+
+    let mut raw_rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("Invalid results CSV row: {e}"))?;
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+        raw_rows.push(record);
+    }
+
+    let columns: Vec<ResultColumn> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, name)| ResultColumn {
+            name: name.to_string(),
+            kind: ColumnKind::infer(raw_rows.iter().map(|r| r.get(i).unwrap_or_default())),
+        })
+        .collect();
+
+    let rows = raw_rows
+        .iter()
+        .map(|record| {
+            columns
+                .iter()
+                .enumerate()
+                .map(|(i, col)| col.kind.convert(record.get(i).unwrap_or_default()))
+                .collect()
+        })
+        .collect();
+
+    Ok(ResultTable { columns, rows })
+}
+
+/// Summarize a job's results (solved count, SGM runtime, worst gap)
+pub fn summarize(job_id: i64, results: &[InstanceResult]) -> ResultSummary {
+    let runtimes: Vec<f64> = results.iter().filter_map(|r| r.runtime).collect();
+    ResultSummary {
+        job_id,
+        rows: results.len(),
+        solved: results.iter().filter(|r| r.is_solved()).count(),
+        sgm_runtime: report::shifted_geometric_mean(&runtimes, report::DEFAULT_SGM_SHIFT),
+        max_gap: results.iter().filter_map(|r| r.gap).reduce(f64::max),
+    }
+}
+
+/// Per-job results file on the server
+///
+/// The wrapper exports its directory as `SOLVERPILOT_RESULTS_DIR` and copies a
+/// `benchmark_results.csv` written to the working directory during the run there.
+pub fn job_results_path(job_id: i64) -> String {
+    format!("~/.solverpilot-server/jobs/{job_id}/{RESULTS_FILE_NAME}")
+}
+
+/// Remote location of a job's results CSV, as recorded when the job started
+pub async fn remote_results_path(pool: &SqlitePool, job_id: i64) -> Result<String, String> {
+    let job = db::load_jobs_by_ids(pool, &[job_id])
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("Job {job_id} not found"))?;
+
+    job.results_path
+        .ok_or_else(|| format!("Job {job_id} has no recorded results file"))
+}
+
+/// Download, parse and store a job's results CSV (replaces previous rows)
+///
+/// Returns the number of stored rows.
+pub async fn ingest_job_results(
+    pool: &SqlitePool,
+    ssh: &SshManager,
+    job_id: i64,
+) -> Result<usize, String> {
+    let path = remote_results_path(pool, job_id).await?;
+
+    let content = ssh
+        .executor()
+        .execute(&format!("cat {}", job::shell_path(&path)))
+        .await
+        .map_err(|e| format!("No results for job {job_id}: {e}"))?;
+
+    let table = parse_csv(&content)?;
+    db::replace_job_results(pool, job_id, &table, &path).await?;
+
+    tracing::info!(
+        "Ingested {} result rows for job {} from {}",
+        table.rows.len(),
+        job_id,
+        path
+    );
+    Ok(table.rows.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_column_kinds() -> Result<(), String> {
+        let csv = "instance,seed,runtime,gap,optimal,solver\n\
+                   a,1,1.5,0%,true,gurobi\n\
+                   b,2,3,12.5%,false,\n";
+        let table = parse_csv(csv)?;
+
+        let kinds: Vec<ColumnKind> = table.columns.iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ColumnKind::Text,
+                ColumnKind::Integer,
+                ColumnKind::Real,
+                ColumnKind::Real,
+                ColumnKind::Boolean,
+                ColumnKind::Text,
+            ]
+        );
+        assert_eq!(table.rows[1][2], Value::from(3.0));
+        assert_eq!(table.rows[1][3], Value::from(12.5));
+        assert_eq!(table.rows[1][5], Value::Null);
+        Ok(())
+    }
+
+    #[test]
+    fn test_instance_results_and_summary() -> Result<(), String> {
+        let csv = "Config,Status,Time\nN5,OPTIMAL,1\nN7,TIME_LIMIT,60\n";
+        let table = parse_csv(csv)?;
+        let results: Vec<InstanceResult> = table.instance_results().into_iter().flatten().collect();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].instance, "N5");
+        assert_eq!(results[1].runtime, Some(60.0));
+
+        let summary = summarize(7, &results);
+        assert_eq!(summary.rows, 2);
+        assert_eq!(summary.solved, 1);
+        assert!(summary.sgm_runtime.is_some());
+        assert_eq!(summary.max_gap, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_row_object() -> Result<(), String> {
+        let table = parse_csv("name,nodes\nx,42\n")?;
+        let obj = table.row_object(&table.rows[0]);
+        assert_eq!(obj.get("nodes"), Some(&Value::from(42)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_job_results_path_quoted() {
+        assert_eq!(
+            job_results_path(12),
+            "~/.solverpilot-server/jobs/12/benchmark_results.csv"
+        );
+        assert_eq!(
+            job::shell_path(&job_results_path(12)),
+            "\"$HOME\"/'.solverpilot-server/jobs/12/benchmark_results.csv'"
+        );
+    }
+}
@@ -0,0 +1,331 @@
+//! Queue execution windows
+//!
+//! The queue can be restricted to windows of time (e.g. weekday nights and
+//! weekends on a server shared during the day), each described by a cron-like
+//! expression matching the minutes it covers:
+//!
+//! ```toml
+//! [queue_settings]
+//! # minute hour day-of-month month day-of-week (local time)
+//! windows = ["* 0-6,20-23 * * mon-fri", "* * * * sat,sun"]
+//! window_end = "suspend"
+//! ```
+//!
+//! Fields accept `*`, values, ranges, lists and steps (`*/15`, `8-18/2`),
+//! month and weekday names; Sunday is 0 or 7. As in cron, when both the day of
+//! month and the day of week are restricted, a day matching either one counts.
+//! The queue runs while the current minute matches any window; with no window
+//! configured it always runs.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// How far ahead `next_change` looks for the next opening or closing
+const LOOKAHEAD_DAYS: i64 = 366;
+
+/// Minute field mask matching every minute of an hour
+const ALL_MINUTES: u64 = (1 << 60) - 1;
+
+/// A cron-like expression: the minutes it matches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpr {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Day of month / day of week left as `*`
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronExpr {
+    /// Parse `minute hour day-of-month month day-of-week`
+    ///
+    /// # Errors
+    /// Returns error if the expression has not 5 fields or a field is invalid
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields.as_slice() else {
+            return Err(format!(
+                "Invalid window '{expr}': expected 5 fields (minute hour day month weekday)"
+            ));
+        };
+        let invalid = |e: String| format!("Invalid window '{expr}': {e}");
+
+        // Day of week 7 is Sunday, like 0
+        let weekdays = parse_field(weekday, 0, 7, &WEEKDAYS).map_err(invalid)?;
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, &[]).map_err(invalid)?,
+            hours: parse_field(hour, 0, 23, &[]).map_err(invalid)?,
+            days: parse_field(day, 1, 31, &[]).map_err(invalid)?,
+            months: parse_field(month, 1, 12, &MONTHS).map_err(invalid)?,
+            weekdays: (weekdays | (weekdays >> 7)) & 0x7f,
+            any_day: *day == "*",
+            any_weekday: *weekday == "*",
+        })
+    }
+
+    /// Whether the minute of `at` matches
+    pub fn matches(&self, at: NaiveDateTime) -> bool {
+        self.matches_day(at.date()) && bit(self.minutes, at.minute()) && bit(self.hours, at.hour())
+    }
+
+    /// Whether some minutes of `date` match
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day_matches = bit(self.days, date.day());
+        let weekday_matches = bit(self.weekdays, date.weekday().num_days_from_sunday());
+        let day = match (self.any_day, self.any_weekday) {
+            (false, false) => day_matches || weekday_matches,
+            _ => day_matches && weekday_matches,
+        };
+
+        day && bit(self.months, date.month())
+    }
+}
+
+/// The windows the queue runs in (none = always)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecutionWindows {
+    windows: Vec<CronExpr>,
+}
+
+impl ExecutionWindows {
+    /// Parse the configured window expressions
+    ///
+    /// # Errors
+    /// Returns error if an expression is invalid
+    pub fn parse(exprs: &[String]) -> Result<Self, String> {
+        Ok(Self {
+            windows: exprs
+                .iter()
+                .map(|expr| CronExpr::parse(expr))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Whether any window is configured
+    pub const fn is_restricted(&self) -> bool {
+        !self.windows.is_empty()
+    }
+
+    /// Whether the queue may run at `at`
+    pub fn is_open(&self, at: NaiveDateTime) -> bool {
+        self.windows.is_empty() || self.windows.iter().any(|w| w.matches(at))
+    }
+
+    /// First minute after `from` where the queue opens (if closed) or closes
+    /// (if open), within a year
+    ///
+    /// Days and hours spent wholly open or closed are skipped at once.
+    pub fn next_change(&self, from: NaiveDateTime) -> Option<NaiveDateTime> {
+        if !self.is_restricted() {
+            return None;
+        }
+        let open = self.is_open(from);
+        let start = from.with_second(0)?.with_nanosecond(0)?;
+        let end = start + Duration::days(LOOKAHEAD_DAYS);
+
+        let mut at = start + Duration::minutes(1);
+        while at <= end {
+            let (span, state) = self.uniform_span(at);
+            if state != open {
+                return Some(at);
+            }
+            at += span;
+        }
+        None
+    }
+
+    /// Longest day, hour or minute starting at `at` that the queue spends wholly
+    /// open or closed, and that state
+    fn uniform_span(&self, at: NaiveDateTime) -> (Duration, bool) {
+        if at.hour() == 0 && at.minute() == 0 {
+            if let Some(state) = self.day_state(at.date()) {
+                return (Duration::days(1), state);
+            }
+        }
+        if at.minute() == 0 {
+            if let Some(state) = self.hour_state(at) {
+                return (Duration::hours(1), state);
+            }
+        }
+        (Duration::minutes(1), self.is_open(at))
+    }
+
+    /// Whether the queue is open all day (`Some(true)`), closed all day
+    /// (`Some(false)`), or both in turn (`None`)
+    fn day_state(&self, date: NaiveDate) -> Option<bool> {
+        let masks: Vec<u64> = (0..24).map(|hour| self.open_minutes(date, hour)).collect();
+        if masks.iter().all(|&mask| mask == 0) {
+            Some(false)
+        } else if masks.iter().all(|&mask| mask == ALL_MINUTES) {
+            Some(true)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the queue is open (`Some(true)`) or closed (`Some(false)`) for the
+    /// whole hour starting at `at`, or both in turn (`None`)
+    fn hour_state(&self, at: NaiveDateTime) -> Option<bool> {
+        match self.open_minutes(at.date(), at.hour()) {
+            0 => Some(false),
+            ALL_MINUTES => Some(true),
+            _ => None,
+        }
+    }
+
+    /// Minutes of `hour` on `date` the queue is open, as a bitmask
+    fn open_minutes(&self, date: NaiveDate, hour: u32) -> u64 {
+        self.windows
+            .iter()
+            .filter(|w| w.matches_day(date) && bit(w.hours, hour))
+            .fold(0, |mask, w| mask | w.minutes)
+    }
+}
+
+/// Bitmask of the values matched by one cron field
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let mut mask = 0;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|&step| step > 0)
+                    .ok_or_else(|| format!("invalid step '{step}'"))?,
+            ),
+            None => (item, 1),
+        };
+
+        let (first, last) = if range == "*" {
+            (min, max)
+        } else if let Some((first, last)) = range.split_once('-') {
+            (
+                parse_value(first, min, names)?,
+                parse_value(last, min, names)?,
+            )
+        } else {
+            let value = parse_value(range, min, names)?;
+            // `5/15` means from 5 to the end, every 15
+            (value, if step > 1 { max } else { value })
+        };
+        if first < min || last > max || first > last {
+            return Err(format!("'{item}' is outside {min}-{max}"));
+        }
+
+        for value in (first..=last).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+/// A number, or a name (`names[0]` has value `min`)
+fn parse_value(value: &str, min: u32, names: &[&str]) -> Result<u32, String> {
+    value.parse().or_else(|_| {
+        names
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(value))
+            .and_then(|index| u32::try_from(index).ok())
+            .map(|index| index + min)
+            .ok_or_else(|| format!("invalid value '{value}'"))
+    })
+}
+
+const fn bit(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(day: u32, hour: u32, minute: u32) -> Result<NaiveDateTime, String> {
+        // 2026-10-19 is a Monday
+        NaiveDate::from_ymd_opt(2026, 10, day)
+            .and_then(|date| date.and_hms_opt(hour, minute, 0))
+            .ok_or_else(|| "invalid date".to_string())
+    }
+
+    #[test]
+    fn test_parse_cron_fields() -> Result<(), String> {
+        let expr = CronExpr::parse("*/15 8-18/2 1,15 jan-MAR 7")?;
+        assert_eq!(expr.minutes, (1 << 0) | (1 << 15) | (1 << 30) | (1 << 45));
+        assert_eq!(
+            expr.hours,
+            (8..=18).step_by(2).map(|h| 1_u64 << h).sum::<u64>()
+        );
+        assert_eq!(expr.days, (1 << 1) | (1 << 15));
+        assert_eq!(expr.months, (1 << 1) | (1 << 2) | (1 << 3));
+        assert_eq!(expr.weekdays, 1);
+
+        assert!(CronExpr::parse("* * * *").is_err());
+        assert!(CronExpr::parse("60 * * * *").is_err());
+        assert!(CronExpr::parse("* 20-7 * * *").is_err());
+        assert!(CronExpr::parse("* * * * monday").is_err());
+        assert!(CronExpr::parse("*/0 * * * *").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_weekday_nights_and_weekends() -> Result<(), String> {
+        let windows = ExecutionWindows::parse(&[
+            "* 0-6,20-23 * * mon-fri".to_string(),
+            "* * * * sat,sun".to_string(),
+        ])?;
+
+        assert!(!windows.is_open(at(19, 12, 0)?)); // Monday noon
+        assert!(windows.is_open(at(19, 20, 0)?));
+        assert!(windows.is_open(at(20, 6, 59)?));
+        assert!(!windows.is_open(at(20, 7, 0)?));
+        assert!(windows.is_open(at(24, 12, 0)?)); // Saturday
+
+        assert_eq!(windows.next_change(at(19, 12, 30)?), Some(at(19, 20, 0)?));
+        assert_eq!(windows.next_change(at(20, 3, 0)?), Some(at(20, 7, 0)?));
+        // Friday night runs into the weekend, until Monday 7:00
+        assert_eq!(windows.next_change(at(23, 21, 0)?), Some(at(26, 7, 0)?));
+
+        let always = ExecutionWindows::parse(&[])?;
+        assert!(always.is_open(at(19, 12, 0)?));
+        assert_eq!(always.next_change(at(19, 12, 0)?), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_day_of_month_or_weekday() -> Result<(), String> {
+        // Either the 1st of the month or a Sunday, as in cron
+        let windows = ExecutionWindows::parse(&["* * 1 * sun".to_string()])?;
+        assert!(windows.is_open(at(1, 9, 0)?)); // Thursday 1st
+        assert!(windows.is_open(at(25, 9, 0)?)); // Sunday
+        assert!(!windows.is_open(at(21, 9, 0)?));
+        Ok(())
+    }
+
+    #[test]
+    fn test_window_that_never_changes() -> Result<(), String> {
+        // Always open (half-hours from two windows), or never open (no 31 February)
+        let always =
+            ExecutionWindows::parse(&["0-29 * * * *".to_string(), "30-59 * * * *".to_string()])?;
+        assert!(always.is_open(at(19, 12, 0)?));
+        assert_eq!(always.next_change(at(19, 12, 0)?), None);
+
+        let never = ExecutionWindows::parse(&["* * 31 feb *".to_string()])?;
+        assert!(!never.is_open(at(19, 12, 0)?));
+        assert_eq!(never.next_change(at(19, 12, 0)?), None);
+
+        // Changes within a mixed hour are still found to the minute
+        let quarter = ExecutionWindows::parse(&["15-44 9 * * *".to_string()])?;
+        assert_eq!(quarter.next_change(at(19, 8, 50)?), Some(at(19, 9, 15)?));
+        assert_eq!(quarter.next_change(at(19, 9, 20)?), Some(at(19, 9, 45)?));
+        assert_eq!(quarter.next_change(at(19, 9, 50)?), Some(at(20, 9, 15)?));
+        Ok(())
+    }
+}
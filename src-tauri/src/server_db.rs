@@ -1,3 +1,5 @@
+use std::fmt::Write;
+
 #[cfg(test)]
 use std::fs;
 #[cfg(test)]
@@ -11,6 +13,60 @@ pub fn generate_init_script() -> String {
     SCHEMA.to_string()
 }
 
+/// Schema version written by `server_schema.sql` (the last server migration)
+pub const SERVER_SCHEMA_VERSION: i64 = 2;
+
+/// Forward migration of the server database
+///
+/// Servers initialized before `schema_version` existed are at version 1.
+#[derive(Debug, Clone, Copy)]
+pub struct ServerMigration {
+    pub version: i64,
+    pub description: &'static str,
+    /// Columns added to `jobs` (name, SQL type), skipped when already present
+    pub job_columns: &'static [(&'static str, &'static str)],
+}
+
+/// Server migrations, oldest first (append only)
+pub const SERVER_MIGRATIONS: &[ServerMigration] = &[ServerMigration {
+    version: 2,
+    description: "job resource usage",
+    job_columns: &[("peak_rss_kb", "INTEGER"), ("cpu_seconds", "REAL")],
+}];
+
+/// SQL upgrading a server database from schema `current`, `None` when up to date
+///
+/// Each migration runs in its own transaction with its version bump; run with
+/// `sqlite3 -bail` so a failed step stops the upgrade. `existing_columns` are the
+/// current columns of `jobs`, so a partially upgraded server is not altered twice.
+pub fn migration_script(current: i64, existing_columns: &[String]) -> Option<String> {
+    let pending: Vec<_> = SERVER_MIGRATIONS
+        .iter()
+        .filter(|m| m.version > current)
+        .collect();
+    if pending.is_empty() {
+        return None;
+    }
+
+    let mut script = String::new();
+    for migration in pending {
+        script.push_str("BEGIN IMMEDIATE;\n");
+        for (name, kind) in migration.job_columns {
+            if !existing_columns.iter().any(|c| c == name) {
+                let _ = writeln!(script, "ALTER TABLE jobs ADD COLUMN {name} {kind};");
+            }
+        }
+        let _ = writeln!(
+            script,
+            "INSERT OR REPLACE INTO metadata (key, value, updated_at) \
+             VALUES ('schema_version', '{}', datetime('now'));\nCOMMIT;",
+            migration.version
+        );
+    }
+
+    Some(script)
+}
+
 /// Initialize server database for local testing only
 /// Production deployment uses SSH execution (Story 2.3)
 #[cfg(test)]
@@ -241,7 +297,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_metadata_wrapper_version() -> Result<(), Box<dyn std::error::Error>> {
+    async fn test_metadata_schema_version() -> Result<(), Box<dyn std::error::Error>> {
         use sqlx::sqlite::SqlitePool;
 
         let temp_dir = TempDir::new()?;
@@ -250,22 +306,71 @@ mod tests {
 
         init_local_test_db(db_path_str).await?;
 
-        // Connect and verify wrapper_version was inserted
+        // Connect and verify schema_version was inserted (wrapper_version comes with the wrapper)
         let pool = SqlitePool::connect(&format!("sqlite:{db_path_str}?mode=ro")).await?;
 
         let result: (String, String) =
-            sqlx::query_as("SELECT key, value FROM metadata WHERE key = 'wrapper_version'")
+            sqlx::query_as("SELECT key, value FROM metadata WHERE key = 'schema_version'")
                 .fetch_one(&pool)
                 .await?;
 
-        assert_eq!(result.0, "wrapper_version");
-        assert_eq!(result.1, "1.0.0");
+        assert_eq!(result.0, "schema_version");
+        assert_eq!(result.1, SERVER_SCHEMA_VERSION.to_string());
 
         pool.close().await;
 
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_migration_script_upgrades_legacy_server() -> Result<(), Box<dyn std::error::Error>>
+    {
+        use sqlx::sqlite::SqlitePool;
+
+        let temp_dir = TempDir::new()?;
+        let db_path = temp_dir.path().join("test_server.db");
+        let db_path_str = db_path.to_str().ok_or("Invalid path")?;
+        init_local_test_db(db_path_str).await?;
+        assert!(migration_script(SERVER_SCHEMA_VERSION, &[]).is_none());
+
+        // Version 1 server: no resource columns, no schema_version
+        let pool = SqlitePool::connect(&format!("sqlite:{db_path_str}?mode=rw")).await?;
+        for statement in [
+            "ALTER TABLE jobs DROP COLUMN peak_rss_kb",
+            "ALTER TABLE jobs DROP COLUMN cpu_seconds",
+            "DELETE FROM metadata WHERE key = 'schema_version'",
+        ] {
+            sqlx::query(statement).execute(&pool).await?;
+        }
+
+        let columns: Vec<String> =
+            sqlx::query_as::<_, (String,)>("SELECT name FROM pragma_table_info('jobs')")
+                .fetch_all(&pool)
+                .await?
+                .into_iter()
+                .map(|(name,)| name)
+                .collect();
+        let script = migration_script(1, &columns).ok_or("no migration for v1")?;
+        sqlx::query(&script).execute(&pool).await?;
+
+        let (version,): (String,) =
+            sqlx::query_as("SELECT value FROM metadata WHERE key = 'schema_version'")
+                .fetch_one(&pool)
+                .await?;
+        assert_eq!(version, SERVER_SCHEMA_VERSION.to_string());
+        sqlx::query("UPDATE jobs SET peak_rss_kb = 1, cpu_seconds = 0.5")
+            .execute(&pool)
+            .await?;
+
+        // Columns already present are not added again
+        let partial = migration_script(1, &["peak_rss_kb".to_string()]).ok_or("no script")?;
+        assert!(!partial.contains("peak_rss_kb"));
+        assert!(partial.contains("ADD COLUMN cpu_seconds REAL"));
+
+        pool.close().await;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_init_local_test_db_invalid_path() -> Result<(), Box<dyn std::error::Error>> {
         // Test graceful failure with invalid path (e.g., permission denied simulation)
@@ -0,0 +1,141 @@
+//! Named compute servers
+//!
+//! The `[ssh]` section is the server named `"default"`; `[servers.<name>]`
+//! profiles add more. Each initialized server has its own `SshManager`, built
+//! from `AppConfig::for_server`, so its executor, transfers and remote paths
+//! all target that server. Jobs record the server they were queued for and
+//! every job command is routed to it.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::config::{AppConfig, ExecutionBackend, DEFAULT_SERVER};
+use crate::local;
+use crate::ssh::{self, LocalShell, SshAuth, SshManager};
+
+/// SSH managers of the initialized servers, by server name
+pub type SshManagers = BTreeMap<String, SshManager>;
+
+/// Configured server, as listed to the frontend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerInfo {
+    pub name: String,
+    pub host: String,
+    pub user: String,
+    pub port: u16,
+    /// Whether an SSH manager is initialized for it
+    pub connected: bool,
+}
+
+/// All configured servers, `"default"` first
+pub fn list(config: &AppConfig, managers: &SshManagers) -> Result<Vec<ServerInfo>, String> {
+    config
+        .server_names()
+        .into_iter()
+        .map(|name| {
+            let view = config.for_server(&name)?;
+            Ok(ServerInfo {
+                connected: managers.contains_key(&name),
+                host: view.ssh.host,
+                user: view.ssh.user,
+                port: view.ssh.port,
+                name,
+            })
+        })
+        .collect()
+}
+
+/// Open an SSH manager (connection pool of 10) for server `name`
+///
+/// Local servers need no connection: commands run on this machine.
+///
+/// # Errors
+/// Returns error if the server is unknown or the connection fails
+pub async fn connect(
+    config: &AppConfig,
+    name: &str,
+    passphrase: Option<String>,
+) -> Result<SshManager, String> {
+    let view = config.for_server(name)?;
+    if view.ssh.backend == ExecutionBackend::Local {
+        let home = local::home_dir(view.ssh.local.as_ref())?;
+        let shell = LocalShell::new(home).map_err(|e| format!("Server '{name}': {e}"))?;
+        return Ok(SshManager::local(view, shell));
+    }
+
+    let key_path = ssh::get_ssh_key_path(&view);
+    let auth = match passphrase {
+        Some(pass) => SshAuth::key_with_passphrase(key_path, pass),
+        None => SshAuth::key(key_path),
+    };
+
+    SshManager::new(view, auth, 10)
+        .await
+        .map_err(|e| format!("Server '{name}': {e}"))
+}
+
+/// Config view and SSH manager of a server (`None` = the default server)
+///
+/// # Errors
+/// Returns error if the server is unknown or not initialized
+pub async fn resolve(
+    managers: &Mutex<SshManagers>,
+    config: &AppConfig,
+    server: Option<&str>,
+) -> Result<(AppConfig, SshManager), String> {
+    let name = config.resolve_server(server)?;
+    let manager =
+        managers.lock().await.get(&name).cloned().ok_or_else(|| {
+            format!("SSH not initialized for server '{name}' - call init_ssh first")
+        })?;
+
+    Ok((config.for_server(&name)?, manager))
+}
+
+/// Server a job runs on: the one it was queued for, `"default"` for older jobs
+pub fn job_server(server: Option<&str>) -> &str {
+    server.unwrap_or(DEFAULT_SERVER)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Result<AppConfig, toml::de::Error> {
+        crate::config::test_config(
+            r#"
+[servers.gpu1]
+host = "gpu1.lab"
+user = "bob"
+port = 2222
+"#,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_list_and_resolve_servers() -> Result<(), Box<dyn std::error::Error>> {
+        let config = config()?;
+        let managers = Mutex::new(SshManagers::new());
+
+        let servers = list(&config, &*managers.lock().await)?;
+        let names: Vec<_> = servers.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["default", "gpu1"]);
+        assert_eq!(servers[1].port, 2222);
+        assert!(servers.iter().all(|s| !s.connected));
+
+        let err = resolve(&managers, &config, Some("gpu1"))
+            .await
+            .err()
+            .ok_or("resolved a server without SSH manager")?;
+        assert!(err.contains("not initialized for server 'gpu1'"));
+        assert!(resolve(&managers, &config, Some("gpu9"))
+            .await
+            .is_err_and(|e| e.contains("Unknown server")));
+
+        assert_eq!(job_server(None), DEFAULT_SERVER);
+        assert_eq!(job_server(Some("gpu1")), "gpu1");
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+//! Sun/Son of Grid Engine batch scheduler
+//!
+//! Jobs are submitted with `qsub -terse`, tracked with `qstat` while queued or
+//! running and with `qacct` (exit status, failure code) once finished, and
+//! removed with `qdel`. SGE cannot forward SIGINT to a job: stopping a job
+//! deletes it like killing it does.
+
+use serde::{Deserialize, Serialize};
+
+use crate::batch::{self, BatchScheduler, BatchState};
+use crate::limits::JobLimits;
+use crate::state::JobStatus;
+
+/// SGE options of a server
+///
+/// ```toml
+/// [servers.grid]
+/// host = "grid.example"
+/// user = "alice"
+/// backend = "sge"
+///
+/// [servers.grid.sge]
+/// queue = "all.q"
+/// time_limit = "04:00:00"
+/// parallel_env = "smp"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SgeConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Wall-clock limit (`h_rt`, "04:00:00")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_limit: Option<String>,
+    /// Parallel environment of multi-core jobs (default: "smp")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parallel_env: Option<String>,
+    /// Slots when the job's limits set no CPU quota (default: 1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slots: Option<u32>,
+    /// Additional `#$` options (e.g. "-l gpu=1")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_args: Vec<String>,
+}
+
+/// SGE scheduler of a server
+#[derive(Debug, Clone)]
+pub struct Sge {
+    config: SgeConfig,
+}
+
+impl Sge {
+    /// # Errors
+    /// Returns error if an option would break out of a `#$` line
+    pub fn new(config: SgeConfig) -> Result<Self, String> {
+        batch::validate_word("SGE queue", config.queue.as_deref())?;
+        batch::validate_word("SGE project", config.project.as_deref())?;
+        batch::validate_word("SGE time_limit", config.time_limit.as_deref())?;
+        batch::validate_word("SGE parallel_env", config.parallel_env.as_deref())?;
+        batch::validate_extra_args(&config.extra_args)?;
+        Ok(Self { config })
+    }
+}
+
+impl BatchScheduler for Sge {
+    fn name(&self) -> &'static str {
+        "SGE"
+    }
+
+    fn directives(&self, job_id: i64, limits: &JobLimits) -> Vec<String> {
+        let mut options = vec![
+            format!("-N {}", batch::job_name(job_id)),
+            "-S /bin/bash".to_string(),
+        ];
+        if let Some(ref queue) = self.config.queue {
+            options.push(format!("-q {queue}"));
+        }
+        if let Some(ref project) = self.config.project {
+            options.push(format!("-P {project}"));
+        }
+        if let Some(ref time_limit) = self.config.time_limit {
+            options.push(format!("-l h_rt={time_limit}"));
+        }
+        let slots = batch::cpus(limits, self.config.slots).unwrap_or(1);
+        if slots > 1 {
+            let pe = self.config.parallel_env.as_deref().unwrap_or("smp");
+            options.push(format!("-pe {pe} {slots}"));
+        }
+        // h_vmem is per slot
+        if let Some(memory_mb) = limits.memory_mb {
+            options.push(format!(
+                "-l h_vmem={}M",
+                memory_mb.div_ceil(u64::from(slots))
+            ));
+        }
+        options.extend(self.config.extra_args.iter().cloned());
+
+        options
+            .into_iter()
+            .map(|option| format!("#$ {option}"))
+            .collect()
+    }
+
+    fn submit_command(&self, script_path: &str, log_path: &str) -> String {
+        format!("qsub -terse -j y -o {log_path} {script_path}")
+    }
+
+    /// `"5678"` (`"5678.1-10:1"` for array jobs)
+    fn parse_submit(&self, output: &str) -> Result<String, String> {
+        let id = output
+            .lines()
+            .map(str::trim)
+            .rfind(|line| !line.is_empty())
+            .and_then(|line| line.split('.').next())
+            .unwrap_or_default();
+
+        if !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) {
+            Ok(id.to_string())
+        } else {
+            Err(format!("Unexpected qsub output: '{}'", output.trim()))
+        }
+    }
+
+    /// `state <letters>` while listed by `qstat`, else the `qacct` record
+    fn state_command(&self, batch_id: &str) -> String {
+        format!(
+            "qstat 2>/dev/null | awk -v id={batch_id} '$1 == id {{ print \"state\", $5; found = 1 }} END {{ exit !found }}' || qacct -j {batch_id} 2>/dev/null"
+        )
+    }
+
+    fn parse_state(&self, output: &str) -> Option<BatchState> {
+        let first = output.lines().map(str::trim).find(|l| !l.is_empty())?;
+        if let Some(state) = first.strip_prefix("state ") {
+            return Some(map_qstat_state(state.trim()));
+        }
+
+        // qacct record (absent until the accounting file is written)
+        let field = |name: &str| {
+            output.lines().find_map(|line| {
+                let (key, value) = line.trim().split_once(char::is_whitespace)?;
+                (key == name).then(|| value.trim().to_string())
+            })
+        };
+        let exit_code: i32 = field("exit_status")?.parse().ok()?;
+        let failed = field("failed").unwrap_or_default();
+
+        let (status, reason) = if !failed.is_empty() && !failed.starts_with('0') {
+            (JobStatus::Failed, Some(format!("SGE failure {failed}")))
+        } else if exit_code == 0 {
+            (JobStatus::Completed, None)
+        } else if exit_code > 128 {
+            (
+                JobStatus::Failed,
+                Some(format!("Killed by SGE with signal {}", exit_code - 128)),
+            )
+        } else {
+            (JobStatus::Failed, None)
+        };
+        Some(BatchState::Finished {
+            status,
+            exit_code: Some(exit_code),
+            reason,
+        })
+    }
+
+    fn cancel_command(&self, batch_id: &str, _graceful: bool) -> String {
+        format!("qdel {batch_id}")
+    }
+}
+
+/// State of a job listed by `qstat` ("qw", "hqw", "r", "t", "Eqw", ...)
+fn map_qstat_state(state: &str) -> BatchState {
+    if state.contains('E') {
+        // Stays in the queue until deleted: report it rather than wait forever
+        BatchState::Finished {
+            status: JobStatus::Failed,
+            exit_code: None,
+            reason: Some(format!("SGE job in error state {state}")),
+        }
+    } else if state.contains('q') {
+        BatchState::Queued
+    } else {
+        BatchState::Running
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directives() -> Result<(), String> {
+        let sge = Sge::new(SgeConfig {
+            queue: Some("all.q".to_string()),
+            time_limit: Some("04:00:00".to_string()),
+            ..SgeConfig::default()
+        })?;
+        let limits = JobLimits {
+            memory_mb: Some(8192),
+            cpu_quota_percent: Some(400),
+            ..JobLimits::default()
+        };
+
+        assert_eq!(
+            sge.directives(7, &limits),
+            vec![
+                "#$ -N solverpilot_7",
+                "#$ -S /bin/bash",
+                "#$ -q all.q",
+                "#$ -l h_rt=04:00:00",
+                "#$ -pe smp 4",
+                "#$ -l h_vmem=2048M",
+            ]
+        );
+        // Single-slot job: no parallel environment
+        assert!(!sge
+            .directives(7, &JobLimits::default())
+            .iter()
+            .any(|d| d.contains("-pe")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_outputs() -> Result<(), String> {
+        let sge = Sge::new(SgeConfig::default())?;
+        assert_eq!(sge.parse_submit("5678\n")?, "5678");
+        assert_eq!(sge.parse_submit("5678.1-10:1\n")?, "5678");
+        assert!(sge.parse_submit("Unable to run job: denied\n").is_err());
+
+        assert_eq!(sge.parse_state("state qw\n"), Some(BatchState::Queued));
+        assert_eq!(sge.parse_state("state r\n"), Some(BatchState::Running));
+        assert!(matches!(
+            sge.parse_state("state Eqw\n"),
+            Some(BatchState::Finished {
+                status: JobStatus::Failed,
+                ..
+            })
+        ));
+
+        let qacct = |failed: &str, exit: &str| {
+            format!("==========\nqname        all.q\nfailed       {failed}\nexit_status  {exit}\n")
+        };
+        assert_eq!(
+            sge.parse_state(&qacct("0", "137"))
+                .and_then(|s| s.failure_message()),
+            Some("Killed by SGE with signal 9".to_string())
+        );
+        assert_eq!(
+            sge.parse_state(&qacct("100 : assumedly after job", "0"))
+                .and_then(|s| s.failure_message()),
+            Some("SGE failure 100 : assumedly after job".to_string())
+        );
+        // Accounting not written yet
+        assert_eq!(sge.parse_state("error: job id 5678 not found\n"), None);
+        Ok(())
+    }
+}
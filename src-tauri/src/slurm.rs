@@ -0,0 +1,272 @@
+//! Slurm batch scheduler
+//!
+//! Jobs are submitted with `sbatch --parsable`, tracked with `squeue` (while
+//! queued or running) and `sacct` (once finished, with the exit code), and
+//! stopped or killed with `scancel`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::batch::{self, BatchScheduler, BatchState};
+use crate::limits::JobLimits;
+use crate::state::JobStatus;
+
+/// Slurm options of a server
+///
+/// ```toml
+/// [servers.hpc]
+/// host = "login.hpc.example"
+/// user = "alice"
+/// backend = "slurm"
+///
+/// [servers.hpc.slurm]
+/// partition = "compute"
+/// account = "proj-42"
+/// time_limit = "04:00:00"
+/// extra_args = ["--qos=normal"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlurmConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partition: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    /// Wall-clock limit in any `--time` format ("04:00:00", "2-00:00:00")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_limit: Option<String>,
+    /// CPUs per task when the job's limits set no CPU quota (default: 1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpus_per_task: Option<u32>,
+    /// Additional `#SBATCH` options (e.g. "--qos=normal", "--constraint=avx512")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_args: Vec<String>,
+}
+
+/// Slurm scheduler of a server
+#[derive(Debug, Clone)]
+pub struct Slurm {
+    config: SlurmConfig,
+}
+
+impl Slurm {
+    /// # Errors
+    /// Returns error if an option would break out of an `#SBATCH` line
+    pub fn new(config: SlurmConfig) -> Result<Self, String> {
+        batch::validate_word("Slurm partition", config.partition.as_deref())?;
+        batch::validate_word("Slurm account", config.account.as_deref())?;
+        batch::validate_word("Slurm time_limit", config.time_limit.as_deref())?;
+        batch::validate_extra_args(&config.extra_args)?;
+        Ok(Self { config })
+    }
+}
+
+impl BatchScheduler for Slurm {
+    fn name(&self) -> &'static str {
+        "Slurm"
+    }
+
+    fn directives(&self, job_id: i64, limits: &JobLimits) -> Vec<String> {
+        let mut options = vec![format!("--job-name={}", batch::job_name(job_id))];
+        if let Some(ref partition) = self.config.partition {
+            options.push(format!("--partition={partition}"));
+        }
+        if let Some(ref account) = self.config.account {
+            options.push(format!("--account={account}"));
+        }
+        if let Some(ref time_limit) = self.config.time_limit {
+            options.push(format!("--time={time_limit}"));
+        }
+        if let Some(cpus) = batch::cpus(limits, self.config.cpus_per_task) {
+            options.push(format!("--cpus-per-task={cpus}"));
+        }
+        if let Some(memory_mb) = limits.memory_mb {
+            options.push(format!("--mem={memory_mb}M"));
+        }
+        options.extend(self.config.extra_args.iter().cloned());
+
+        options
+            .into_iter()
+            .map(|option| format!("#SBATCH {option}"))
+            .collect()
+    }
+
+    fn submit_command(&self, script_path: &str, log_path: &str) -> String {
+        format!("sbatch --parsable --output={log_path} {script_path}")
+    }
+
+    /// `"4242"` or `"4242;cluster"`
+    fn parse_submit(&self, output: &str) -> Result<String, String> {
+        let id = output
+            .lines()
+            .map(str::trim)
+            .rfind(|line| !line.is_empty())
+            .and_then(|line| line.split(';').next())
+            .unwrap_or_default();
+
+        if !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) {
+            Ok(id.to_string())
+        } else {
+            Err(format!("Unexpected sbatch output: '{}'", output.trim()))
+        }
+    }
+
+    fn state_command(&self, batch_id: &str) -> String {
+        format!(
+            "state=$(squeue -h -j {batch_id} -o %T 2>/dev/null); if [ -n \"$state\" ]; then echo \"$state\"; else sacct -n -X -P -j {batch_id} -o State,ExitCode 2>/dev/null; fi"
+        )
+    }
+
+    /// `squeue` prints the state ("RUNNING"), `sacct` the state and exit code
+    /// ("CANCELLED by 1234|0:15", "FAILED|2:0")
+    fn parse_state(&self, output: &str) -> Option<BatchState> {
+        let line = output.lines().map(str::trim).find(|l| !l.is_empty())?;
+        let mut fields = line.split('|');
+        let state = fields
+            .next()
+            .and_then(|f| f.split_whitespace().next())?
+            .trim_end_matches('+')
+            .to_ascii_uppercase();
+        let exit_code = fields
+            .next()
+            .and_then(|f| f.split(':').next())
+            .and_then(|code| code.parse().ok());
+
+        Some(match map_state(&state) {
+            JobStatus::Pending => BatchState::Queued,
+            JobStatus::Running => BatchState::Running,
+            status => BatchState::Finished {
+                status,
+                exit_code,
+                reason: failure_reason(&state),
+            },
+        })
+    }
+
+    fn cancel_command(&self, batch_id: &str, graceful: bool) -> String {
+        if graceful {
+            format!("scancel --signal=INT --full {batch_id}")
+        } else {
+            format!("scancel {batch_id}")
+        }
+    }
+}
+
+/// `JobStatus` of a Slurm state (queued in Slurm counts as pending)
+pub fn map_state(state: &str) -> JobStatus {
+    match state {
+        "PENDING" | "REQUEUED" | "REQUEUE_HOLD" | "REQUEUE_FED" | "RESV_DEL_HOLD" => {
+            JobStatus::Pending
+        }
+        "COMPLETED" => JobStatus::Completed,
+        "CANCELLED" => JobStatus::Killed,
+        "FAILED" | "TIMEOUT" | "OUT_OF_MEMORY" | "NODE_FAIL" | "PREEMPTED" | "BOOT_FAIL"
+        | "DEADLINE" | "REVOKED" => JobStatus::Failed,
+        // RUNNING, CONFIGURING, COMPLETING, SUSPENDED, STOPPED, ...
+        _ => JobStatus::Running,
+    }
+}
+
+/// Why Slurm ended a job (`None` when it completed or simply failed: see its exit code)
+pub fn failure_reason(state: &str) -> Option<String> {
+    let reason = match state {
+        "COMPLETED" | "FAILED" => return None,
+        "CANCELLED" => "Cancelled in Slurm",
+        "TIMEOUT" => "Slurm time limit reached",
+        "OUT_OF_MEMORY" => "Slurm memory limit exceeded",
+        "NODE_FAIL" => "Slurm node failure",
+        "PREEMPTED" => "Preempted by Slurm",
+        "BOOT_FAIL" => "Slurm node failed to boot",
+        "DEADLINE" => "Slurm deadline reached",
+        _ => return Some(format!("Slurm job ended in state {state}")),
+    };
+    Some(reason.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directives() -> Result<(), String> {
+        let slurm = Slurm::new(SlurmConfig {
+            partition: Some("compute".to_string()),
+            account: Some("proj-42".to_string()),
+            time_limit: Some("04:00:00".to_string()),
+            cpus_per_task: Some(2),
+            extra_args: vec!["--qos=normal".to_string()],
+        })?;
+        let limits = JobLimits {
+            memory_mb: Some(16384),
+            cpu_quota_percent: Some(350),
+            ..JobLimits::default()
+        };
+
+        assert_eq!(
+            slurm.directives(7, &limits),
+            vec![
+                "#SBATCH --job-name=solverpilot_7",
+                "#SBATCH --partition=compute",
+                "#SBATCH --account=proj-42",
+                "#SBATCH --time=04:00:00",
+                "#SBATCH --cpus-per-task=4",
+                "#SBATCH --mem=16384M",
+                "#SBATCH --qos=normal",
+            ]
+        );
+
+        // Without a CPU quota the configured default applies
+        let directives = slurm.directives(7, &JobLimits::default());
+        assert!(directives.contains(&"#SBATCH --cpus-per-task=2".to_string()));
+        assert!(!directives.iter().any(|d| d.contains("--mem=")));
+
+        assert!(Slurm::new(SlurmConfig {
+            partition: Some("a b".to_string()),
+            ..SlurmConfig::default()
+        })
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_outputs() -> Result<(), String> {
+        let slurm = Slurm::new(SlurmConfig::default())?;
+        assert_eq!(slurm.parse_submit("4242\n")?, "4242");
+        assert_eq!(slurm.parse_submit("4242;cluster\n")?, "4242");
+        assert!(slurm
+            .parse_submit("sbatch: error: Batch job submission failed")
+            .is_err());
+        assert!(slurm.parse_submit("").is_err());
+
+        assert_eq!(slurm.parse_state("PENDING\n"), Some(BatchState::Queued));
+        assert_eq!(slurm.parse_state("COMPLETING\n"), Some(BatchState::Running));
+        assert_eq!(
+            slurm.parse_state("CANCELLED by 1234|0:15\n"),
+            Some(BatchState::Finished {
+                status: JobStatus::Killed,
+                exit_code: Some(0),
+                reason: Some("Cancelled in Slurm".to_string()),
+            })
+        );
+        assert_eq!(
+            slurm
+                .parse_state("FAILED|2:0\n")
+                .and_then(|s| s.failure_message()),
+            Some("Exit code 2".to_string())
+        );
+        assert_eq!(slurm.parse_state("\n"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_states() {
+        assert_eq!(map_state("PENDING"), JobStatus::Pending);
+        assert_eq!(map_state("RUNNING"), JobStatus::Running);
+        assert_eq!(map_state("COMPLETED"), JobStatus::Completed);
+        assert_eq!(map_state("CANCELLED"), JobStatus::Killed);
+        assert_eq!(map_state("OUT_OF_MEMORY"), JobStatus::Failed);
+        assert_eq!(
+            failure_reason("TIMEOUT").as_deref(),
+            Some("Slurm time limit reached")
+        );
+        assert_eq!(failure_reason("COMPLETED"), None);
+    }
+}
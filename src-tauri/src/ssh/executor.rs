@@ -7,6 +7,7 @@ use russh::ChannelMsg;
 use std::time::Duration;
 
 use super::error::{Result, SshError};
+use super::local::LocalShell;
 use super::pool::{SshConnection, SshPool};
 
 /// Result of a command execution
@@ -40,22 +41,55 @@ impl CommandResult {
     }
 }
 
+/// Where commands run
+#[derive(Clone)]
+enum Target {
+    Ssh(SshPool),
+    Local(LocalShell),
+}
+
 /// SSH command executor
 #[derive(Clone)]
 pub struct SshExecutor {
-    pool: SshPool,
+    target: Target,
 }
 
 impl SshExecutor {
     /// Create a new executor from a connection pool
     pub const fn new(pool: SshPool) -> Self {
-        Self { pool }
+        Self {
+            target: Target::Ssh(pool),
+        }
+    }
+
+    /// Create an executor running commands on this machine
+    pub const fn local(shell: LocalShell) -> Self {
+        Self {
+            target: Target::Local(shell),
+        }
+    }
+
+    /// Local shell of the local execution backend (`None` over SSH)
+    pub const fn local_shell(&self) -> Option<&LocalShell> {
+        match self.target {
+            Target::Local(ref shell) => Some(shell),
+            Target::Ssh(_) => None,
+        }
     }
 
     /// Execute a command and return the result
     pub async fn execute_raw(&self, command: &str) -> Result<CommandResult> {
-        let conn = self.pool.get().await?;
-        execute_command_on_connection(&conn, command, Duration::from_secs(300)).await
+        self.run(command, Duration::from_secs(300)).await
+    }
+
+    async fn run(&self, command: &str, timeout: Duration) -> Result<CommandResult> {
+        match self.target {
+            Target::Ssh(ref pool) => {
+                let conn = pool.get().await?;
+                execute_command_on_connection(&conn, command, timeout).await
+            }
+            Target::Local(ref shell) => shell.run(command, timeout).await,
+        }
     }
 
     /// Execute a command and return stdout on success
@@ -65,10 +99,7 @@ impl SshExecutor {
 
     /// Execute a command with custom timeout
     pub async fn execute_with_timeout(&self, command: &str, timeout: Duration) -> Result<String> {
-        let conn = self.pool.get().await?;
-        execute_command_on_connection(&conn, command, timeout)
-            .await?
-            .into_result()
+        self.run(command, timeout).await?.into_result()
     }
 
     /// Execute a command, ignoring exit status
@@ -86,15 +117,33 @@ impl SshExecutor {
 
     /// Execute a command that starts a long-running process
     pub async fn execute_background(&self, command: &str) -> Result<()> {
-        let conn = self.pool.get().await?;
-        let channel = conn.channel_open_session().await?;
-
-        channel.exec(true, command).await?;
-        drop(channel);
+        match self.target {
+            Target::Ssh(ref pool) => {
+                let conn = pool.get().await?;
+                let channel = conn.channel_open_session().await?;
 
+                channel.exec(true, command).await?;
+                drop(channel);
+            }
+            Target::Local(ref shell) => {
+                shell.spawn_group(command)?;
+            }
+        }
         Ok(())
     }
 
+    /// Start a long-running process in its own process group (local backend only)
+    ///
+    /// Returns the process group id, used to signal the whole job.
+    pub fn spawn_process_group(&self, command: &str) -> Result<u32> {
+        match self.target {
+            Target::Local(ref shell) => shell.spawn_group(command),
+            Target::Ssh(_) => Err(SshError::other(
+                "Process groups are only available with the local backend",
+            )),
+        }
+    }
+
     /// Check if a tmux session exists
     pub async fn tmux_session_exists(&self, session_name: &str) -> Result<bool> {
         let cmd = format!("tmux has-session -t {session_name} 2>/dev/null && echo yes || echo no");
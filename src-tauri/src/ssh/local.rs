@@ -0,0 +1,122 @@
+//! Command execution on this machine (local execution backend)
+//!
+//! Stands in for an SSH server: commands run in `bash -c` with `$HOME` set to
+//! the local server's home, so every `~/` path of the remote layout (server
+//! database, wrapper, jobs and results directories) lands in that directory.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use tokio::process::Command;
+
+use super::error::{Result, SshError};
+use super::executor::CommandResult;
+
+/// Shell on this machine with its own home directory
+#[derive(Debug, Clone)]
+pub struct LocalShell {
+    home: PathBuf,
+}
+
+impl LocalShell {
+    /// Create a shell whose `$HOME` is `home` (created if missing)
+    pub fn new(home: PathBuf) -> Result<Self> {
+        std::fs::create_dir_all(&home)?;
+        Ok(Self { home })
+    }
+
+    /// Home directory of the local server
+    pub fn home(&self) -> &Path {
+        &self.home
+    }
+
+    /// `bash -c command`, run from and with `$HOME` set to the local home
+    pub fn command(&self, command: &str) -> Command {
+        let mut cmd = Command::new("bash");
+        cmd.arg("-c")
+            .arg(command)
+            .env("HOME", &self.home)
+            .current_dir(&self.home)
+            .stdin(Stdio::null());
+        cmd
+    }
+
+    /// Run a command and capture its output
+    pub async fn run(&self, command: &str, timeout: Duration) -> Result<CommandResult> {
+        let mut cmd = self.command(command);
+        cmd.kill_on_drop(true);
+
+        let output = tokio::time::timeout(timeout, cmd.output())
+            .await
+            .map_err(|_| SshError::timeout("command execution", timeout.as_secs()))??;
+
+        Ok(CommandResult {
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            exit_code: output
+                .status
+                .code()
+                .and_then(|code| u32::try_from(code).ok())
+                .unwrap_or(255),
+        })
+    }
+
+    /// Start a command in a new process group without waiting for it
+    ///
+    /// Returns the process group id (the pid of its `bash`). The process is
+    /// reaped when it exits, so the group disappears with its last member.
+    pub fn spawn_group(&self, command: &str) -> Result<u32> {
+        let mut child = self
+            .command(command)
+            .process_group(0)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let pgid = child
+            .id()
+            .ok_or_else(|| SshError::other("Local process exited before it started"))?;
+
+        tokio::spawn(async move {
+            let _ = child.wait().await;
+        });
+        Ok(pgid)
+    }
+
+    /// Path on this machine of a server path (`~/` = the local home)
+    pub fn resolve(&self, path: &str) -> PathBuf {
+        if path == "~" {
+            return self.home.clone();
+        }
+        path.strip_prefix("~/")
+            .map_or_else(|| PathBuf::from(path), |rest| self.home.join(rest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_in_local_home() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let shell = LocalShell::new(dir.path().join("home"))?;
+
+        let result = shell
+            .run(
+                "mkdir -p ~/jobs && echo $HOME; exit 3",
+                Duration::from_secs(10),
+            )
+            .await?;
+        assert_eq!(result.stdout.trim(), shell.home().display().to_string());
+        assert_eq!(result.exit_code, 3);
+        assert!(shell.home().join("jobs").is_dir());
+
+        assert_eq!(
+            shell.resolve("~/jobs/1.log"),
+            shell.home().join("jobs/1.log")
+        );
+        assert_eq!(shell.resolve("/tmp/x"), PathBuf::from("/tmp/x"));
+        Ok(())
+    }
+}
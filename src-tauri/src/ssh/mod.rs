@@ -8,6 +8,7 @@
 mod auth;
 mod error;
 mod executor;
+mod local;
 mod pool;
 mod transfer;
 
@@ -15,6 +16,7 @@ mod transfer;
 pub use auth::{get_ssh_key_path, SecureString, SshAuth};
 pub use error::{AuthMethod, Result, SshError};
 pub use executor::{CommandResult, SshExecutor};
+pub use local::LocalShell;
 pub use pool::SshPool;
 pub use transfer::SshTransfer;
 
@@ -25,7 +27,7 @@ use crate::config::AppConfig;
 pub struct SshManager {
     executor: SshExecutor,
     transfer: SshTransfer,
-    pool: SshPool,
+    pool: Option<SshPool>,
 }
 
 impl SshManager {
@@ -53,10 +55,24 @@ impl SshManager {
         Ok(Self {
             executor: SshExecutor::new(pool.clone()),
             transfer: SshTransfer::new(config.clone()),
-            pool: pool.clone(),
+            pool: Some(pool.clone()),
         })
     }
 
+    /// Create a manager running everything on this machine (local backend)
+    pub fn local(config: AppConfig, shell: LocalShell) -> Self {
+        tracing::info!(
+            "Using local execution backend in {}",
+            shell.home().display()
+        );
+
+        Self {
+            executor: SshExecutor::local(shell.clone()),
+            transfer: SshTransfer::local(config, shell),
+            pool: None,
+        }
+    }
+
     /// Get the command executor
     pub const fn executor(&self) -> &SshExecutor {
         &self.executor
@@ -67,9 +83,16 @@ impl SshManager {
         &self.transfer
     }
 
+    /// Get the connection pool (dedicated connections for long-lived channels)
+    ///
+    /// `None` with the local backend.
+    pub const fn pool(&self) -> Option<&SshPool> {
+        self.pool.as_ref()
+    }
+
     /// Get pool state for monitoring
-    pub fn pool_state(&self) -> bb8::State {
-        self.pool.state()
+    pub fn pool_state(&self) -> Option<bb8::State> {
+        self.pool.as_ref().map(SshPool::state)
     }
 
     /// Test SSH connection
@@ -190,9 +190,12 @@ impl SshPool {
         self.pool.state()
     }
 
-    /// Dedicated connection for operations (convenience wrapper)
-    pub async fn dedicated(&self) -> Result<bb8::PooledConnection<'_, SshConnectionManager>> {
-        self.get().await
+    /// Dedicated connection outside the pool, for long-lived channels (e.g. `tail -F`)
+    ///
+    /// Not counted against `max_size`, so it never starves pooled operations;
+    /// closed when dropped.
+    pub async fn dedicated(&self) -> Result<SshConnection> {
+        self.pool.dedicated_connection().await
     }
 }
 
@@ -8,17 +8,53 @@ use std::path::Path;
 use tokio::process::Command;
 
 use super::error::{Result, SshError};
+use super::local::LocalShell;
 
 /// SSH file transfer manager
 #[derive(Clone)]
 pub struct SshTransfer {
     config: AppConfig,
+    local: Option<LocalShell>,
 }
 
 impl SshTransfer {
     /// Create a new file transfer manager
     pub const fn new(config: AppConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            local: None,
+        }
+    }
+
+    /// Transfer manager of the local backend (plain local rsync)
+    pub const fn local(config: AppConfig, shell: LocalShell) -> Self {
+        Self {
+            config,
+            local: Some(shell),
+        }
+    }
+
+    /// rsync location of a server path: `user@host:path`, or the path on
+    /// this machine with the local backend
+    pub fn remote_spec(&self, path: &str) -> String {
+        self.local.as_ref().map_or_else(
+            || format!("{}@{}:{}", self.config.ssh.user, self.config.ssh.host, path),
+            |shell| shell.resolve(path).display().to_string(),
+        )
+    }
+
+    /// Create a local destination directory (rsync only creates the last level)
+    ///
+    /// No-op over SSH.
+    pub fn prepare_destination(&self, remote_path: &str) -> Result<()> {
+        if self.local.is_some() {
+            std::fs::create_dir_all(remote_path).map_err(|e| SshError::TransferError {
+                source: "local backend".to_string(),
+                destination: remote_path.to_string(),
+                reason: format!("Failed to create directory: {e}"),
+            })?;
+        }
+        Ok(())
     }
 
     /// Build rsync SSH command string
@@ -39,13 +75,10 @@ impl SshTransfer {
 
     /// Sync project files to remote server
     pub async fn rsync_project(&self, project_name: &str, project_dir: &Path) -> Result<()> {
-        let remote_path = format!(
-            "{}@{}:{}/projects/{}/",
-            self.config.ssh.user,
-            self.config.ssh.host,
-            self.config.remote.remote_base,
-            project_name
-        );
+        let remote_path = self.remote_spec(&format!(
+            "{}/projects/{}/",
+            self.config.remote.remote_base, project_name
+        ));
 
         let local_path = format!("{}/", project_dir.display());
 
@@ -64,13 +97,10 @@ impl SshTransfer {
         benchmark_dir: &Path,
         files: Vec<String>,
     ) -> Result<()> {
-        let remote_path = format!(
-            "{}@{}:{}/projects/{}/code/",
-            self.config.ssh.user,
-            self.config.ssh.host,
-            self.config.remote.remote_base,
-            project_name
-        );
+        let remote_path = self.remote_spec(&format!(
+            "{}/projects/{}/code/",
+            self.config.remote.remote_base, project_name
+        ));
 
         // Create temporary file list for rsync --files-from
         let temp_dir = std::env::temp_dir();
@@ -112,10 +142,7 @@ impl SshTransfer {
 
     /// Download files from remote server
     pub async fn rsync_from_server(&self, remote_path: &str, local_path: &str) -> Result<()> {
-        let remote = format!(
-            "{}@{}:{}",
-            self.config.ssh.user, self.config.ssh.host, remote_path
-        );
+        let remote = self.remote_spec(remote_path);
 
         let ssh_cmd = self.rsync_ssh_command();
 
@@ -150,6 +177,7 @@ impl SshTransfer {
         remote_path: &str,
         includes: Vec<&str>,
     ) -> Result<()> {
+        self.prepare_destination(remote_path)?;
         let ssh_cmd = self.rsync_ssh_command();
 
         let mut command = Command::new("rsync");
@@ -191,6 +219,7 @@ impl SshTransfer {
         remote_path: &str,
         files_list: &str,
     ) -> Result<()> {
+        self.prepare_destination(remote_path)?;
         let ssh_cmd = self.rsync_ssh_command();
 
         let mut command = Command::new("rsync");
@@ -231,13 +260,10 @@ impl SshTransfer {
         project_name: &str,
         project_dir: &Path,
     ) -> Result<Vec<String>> {
-        let remote_path = format!(
-            "{}@{}:{}/projects/{}/",
-            self.config.ssh.user,
-            self.config.ssh.host,
-            self.config.remote.remote_base,
-            project_name
-        );
+        let remote_path = self.remote_spec(&format!(
+            "{}/projects/{}/",
+            self.config.remote.remote_base, project_name
+        ));
 
         let local_path = format!("{}/", project_dir.display());
         let ssh_cmd = self.rsync_ssh_command();
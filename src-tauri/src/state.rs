@@ -4,20 +4,24 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::config::AppConfig;
+use crate::log_stream::LogStreams;
 use crate::queue_service::QueueManager;
-use crate::ssh::SshManager;
+use crate::servers::SshManagers;
 
 /// État global de l'application (thread-safe)
 pub struct AppState {
     pub config: Arc<Mutex<Option<AppConfig>>>,
     pub db: Arc<Mutex<Option<SqlitePool>>>,
-    pub ssh_manager: Arc<Mutex<Option<SshManager>>>,
+    /// SSH managers of the initialized servers, by server name
+    pub ssh_managers: Arc<Mutex<SshManagers>>,
     pub current_job_id: Arc<Mutex<Option<i64>>>,
     pub job_start_time: Arc<Mutex<Option<std::time::Instant>>>,
     /// Projet actuellement sélectionné
     pub current_project_id: Arc<Mutex<Option<i64>>>,
     /// Queue manager for sequential job processing (Story 2.4)
     pub queue_manager: Arc<Mutex<QueueManager>>,
+    /// Live log streams (`tail -F`) shared by subscribers
+    pub log_streams: Arc<LogStreams>,
 }
 
 impl Default for AppState {
@@ -25,11 +29,12 @@ impl Default for AppState {
         Self {
             config: Arc::new(Mutex::new(None)),
             db: Arc::new(Mutex::new(None)),
-            ssh_manager: Arc::new(Mutex::new(None)),
+            ssh_managers: Arc::new(Mutex::new(SshManagers::new())),
             current_job_id: Arc::new(Mutex::new(None)),
             job_start_time: Arc::new(Mutex::new(None)),
             current_project_id: Arc::new(Mutex::new(None)),
             queue_manager: Arc::new(Mutex::new(QueueManager::new())),
+            log_streams: Arc::new(LogStreams::new()),
         }
     }
 }
@@ -48,6 +53,11 @@ pub struct Project {
     pub python_version: String,
     pub created_at: String,
     pub updated_at: String,
+    /// Environment profile selected for this project's jobs
+    pub env_profile: Option<String>,
+    /// Container profile selected for this project's jobs
+    #[serde(default)]
+    pub container: Option<String>,
 }
 
 /// Un benchmark Python (référence par chemin absolu)
@@ -89,6 +99,58 @@ pub struct Job {
     // Story 1.2 - Queue fields (nullable for non-queued jobs)
     pub queue_position: Option<i64>,
     pub queued_at: Option<String>,
+    /// Environment profile override for this job (falls back to the project's)
+    pub env_profile: Option<String>,
+    /// Peak resident memory of the job's process tree (reported by the wrapper)
+    pub peak_rss_kb: Option<i64>,
+    /// User + system CPU time of the job
+    pub cpu_seconds: Option<f64>,
+    /// Free-form notes (why it was run, what was learned)
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// Labels such as "baseline" or "paper-v2"
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Target server (`None` = placed by the dispatcher when the job starts)
+    #[serde(default)]
+    pub server: Option<String>,
+    /// Earliest start time of a queued job (UTC RFC 3339, `None` = as soon as possible)
+    #[serde(default)]
+    pub not_before: Option<String>,
+}
+
+/// Tag et nombre de jobs qui le portent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tag {
+    pub name: String,
+    pub job_count: i64,
+}
+
+/// Filtres d'une recherche plein texte sur les jobs (tous optionnels)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JobSearchFilters {
+    #[serde(default)]
+    pub status: Option<JobStatus>,
+    #[serde(default)]
+    pub project_id: Option<i64>,
+    /// Date de création minimale (YYYY-MM-DD, incluse)
+    #[serde(default)]
+    pub from: Option<String>,
+    /// Date de création maximale (YYYY-MM-DD, incluse)
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+/// Job trouvé par une recherche plein texte
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobSearchHit {
+    pub job: Job,
+    /// Extrait de la meilleure colonne, termes trouvés entre `<mark>` et `</mark>`
+    pub snippet: String,
+    /// Score BM25 (plus bas = plus pertinent)
+    pub rank: f64,
 }
 
 /// Status de synchronisation du code
@@ -112,4 +174,6 @@ pub struct JobStatusResponse {
     pub elapsed_seconds: u64,
     pub is_finished: bool,
     pub error: Option<String>,
+    /// Latest resource sample (final totals once the job exited)
+    pub resource_usage: Option<crate::usage::ResourceUsage>,
 }
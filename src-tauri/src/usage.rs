@@ -0,0 +1,100 @@
+//! Resource usage of running jobs
+//!
+//! `job_wrapper.sh` samples the job's process tree from `/proc` (CPU%, RSS, threads,
+//! I/O) into `~/.solverpilot-server/jobs/<id>.usage`, and rewrites it at exit with
+//! the final peak RSS and CPU time (`"final": true`). This module reads that file
+//! over SSH and stores final values with the job.
+
+use crate::db;
+use crate::ssh::SshExecutor;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+/// One usage sample (live) or the final totals of a job
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceUsage {
+    /// CPU usage over the last sampling interval (100 = one core)
+    pub cpu_percent: f64,
+    /// Resident memory summed over the process tree
+    pub rss_kb: u64,
+    pub peak_rss_kb: u64,
+    pub threads: u32,
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+    /// User + system CPU time consumed so far
+    pub cpu_seconds: f64,
+    pub sampled_at: String,
+    /// `true` once the job exited (only peak and CPU time are meaningful)
+    #[serde(rename = "final")]
+    pub is_final: bool,
+}
+
+/// Remote path of a job's usage file
+pub fn usage_file_path(job_id: i64) -> String {
+    format!("~/.solverpilot-server/jobs/{job_id}.usage")
+}
+
+/// Parse the JSON written by the wrapper (`None` when missing or partial)
+pub fn parse_usage(content: &str) -> Option<ResourceUsage> {
+    serde_json::from_str(content.trim()).ok()
+}
+
+/// Read the latest usage sample of a job (`None` before the first sample)
+///
+/// # Errors
+/// Returns error if the SSH command cannot be executed
+pub async fn read_job_usage(
+    executor: &SshExecutor,
+    job_id: i64,
+) -> Result<Option<ResourceUsage>, String> {
+    let result = executor
+        .execute_raw(&format!("cat {} 2>/dev/null", usage_file_path(job_id)))
+        .await
+        .map_err(|e| format!("Failed to read resource usage: {e}"))?;
+
+    Ok(parse_usage(&result.stdout))
+}
+
+/// Store a job's final peak memory and CPU time (non-final samples are ignored)
+pub async fn record_final_usage(
+    pool: &SqlitePool,
+    executor: &SshExecutor,
+    job_id: i64,
+) -> Result<Option<ResourceUsage>, String> {
+    let usage = read_job_usage(executor, job_id).await?;
+
+    if let Some(ref usage) = usage {
+        if usage.is_final {
+            db::update_job_resource_usage(pool, job_id, usage.peak_rss_kb, usage.cpu_seconds)
+                .await?;
+        }
+    }
+
+    Ok(usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_live_sample() {
+        let content = r#"{"cpu_percent": 101.0, "rss_kb": 218536, "peak_rss_kb": 218536, "threads": 1, "read_bytes": 0, "write_bytes": 4096, "cpu_seconds": 1.95, "sampled_at": "2026-10-19T01:26:28+00:00", "final": false}
+"#;
+        let usage = parse_usage(content);
+        assert!(usage.as_ref().is_some_and(|u| !u.is_final));
+        assert_eq!(usage.as_ref().map(|u| u.rss_kb), Some(218_536));
+        assert_eq!(usage.map(|u| u.write_bytes), Some(4096));
+    }
+
+    #[test]
+    fn test_parse_final_and_invalid() {
+        let content = r#"{"cpu_percent": 0, "rss_kb": 0, "peak_rss_kb": 1024, "threads": 0, "read_bytes": 0, "write_bytes": 0, "cpu_seconds": 3.15, "sampled_at": "2026-10-19T01:26:29+00:00", "final": true}"#;
+        let usage = parse_usage(content);
+        assert!(usage.as_ref().is_some_and(|u| u.is_final));
+        assert_eq!(usage.map(|u| u.peak_rss_kb), Some(1024));
+
+        assert_eq!(parse_usage(""), None);
+        assert_eq!(parse_usage("{\"cpu_percent\": 1"), None);
+    }
+}
@@ -1,25 +1,109 @@
 //! Wrapper script deployment and management module
 //!
 //! This module handles:
-//! - Embedding the `job_wrapper.sh` script via `include_str!`
+//! - Embedding the `job_wrapper.sh` script and its `job_helpers.sh` via `include_str!`
 //! - Deploying the wrapper to remote servers via SSH
 //! - Version tracking for debugging and compatibility
-//! - Idempotent deployment (skip if already installed)
+//! - Version negotiation: an outdated wrapper or server schema is upgraded in
+//!   place (atomic rename, so running jobs keep the script they started with)
 
+use crate::code_version::sha256_hex;
+use crate::server_db::{self, SERVER_SCHEMA_VERSION};
 use crate::ssh::SshExecutor;
+use serde::{Deserialize, Serialize};
 
-/// Version of the wrapper script (Story 2.3)
-pub const WRAPPER_VERSION: &str = "1.0.0";
+/// Version of the wrapper script (Story 2.3), bump with every `job_wrapper.sh` or
+/// `job_helpers.sh` change
+pub const WRAPPER_VERSION: &str = "1.2.0";
 
 /// Embedded wrapper script content
 pub const WRAPPER_SCRIPT: &str = include_str!("../scripts/job_wrapper.sh");
 
+/// Embedded helpers sourced by the wrapper (resource usage sampling and limits)
+pub const HELPERS_SCRIPT: &str = include_str!("../scripts/job_helpers.sh");
+
 /// Remote path where the wrapper is deployed
 pub const REMOTE_WRAPPER_PATH: &str = "~/.solverpilot/bin/job_wrapper.sh";
 
+/// Remote path of the wrapper helpers (next to the wrapper, which sources them)
+pub const REMOTE_HELPERS_PATH: &str = "~/.solverpilot/bin/job_helpers.sh";
+
+/// Remote path of the server database
+pub const REMOTE_SERVER_DB_PATH: &str = "~/.solverpilot-server/server.db";
+
+/// State of the wrapper deployed on a server, compared with this build
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum WrapperStatus {
+    Missing,
+    UpToDate,
+    /// Older version, or same version with different content
+    Outdated {
+        installed: Option<String>,
+    },
+    /// Deployed by a newer release: left untouched
+    Newer {
+        installed: String,
+    },
+}
+
+/// What is installed on the server (wrapper script and server database)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerInstall {
+    /// SHA-256 of the deployed `job_wrapper.sh` followed by `job_helpers.sh`
+    pub wrapper_checksum: Option<String>,
+    /// `wrapper_version` recorded in the server metadata
+    pub wrapper_version: Option<String>,
+    pub db_exists: bool,
+    /// `schema_version` of the server database (absent before versioning)
+    pub schema_version: Option<i64>,
+    /// Columns of the server `jobs` table
+    pub job_columns: Vec<String>,
+}
+
+/// Outcome of `WrapperManager::ensure_server_ready`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerUpgrade {
+    /// Wrapper state before the upgrade
+    pub wrapper: WrapperStatus,
+    pub wrapper_deployed: bool,
+    /// Server schema version before / after (0 = no database)
+    pub schema_from: i64,
+    pub schema_to: i64,
+}
+
+/// Parse `x.y.z` into comparable numbers (`None` if malformed)
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    version
+        .trim()
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect()
+}
+
+/// Parse the `key=value` lines printed by the server inspection command
+pub fn parse_server_install(output: &str) -> ServerInstall {
+    let mut install = ServerInstall::default();
+    for line in output.lines() {
+        let Some((key, value)) = line.trim().split_once('=') else {
+            continue;
+        };
+        match key {
+            "checksum" if !value.is_empty() => install.wrapper_checksum = Some(value.to_string()),
+            "db" => install.db_exists = value == "1",
+            "wrapper_version" => install.wrapper_version = Some(value.to_string()),
+            "schema_version" => install.schema_version = value.parse().ok(),
+            "column" => install.job_columns.push(value.to_string()),
+            _ => {}
+        }
+    }
+    install
+}
+
 /// Manager for wrapper script deployment and operations
 pub struct WrapperManager {
     script_content: String,
+    helpers_content: String,
     version: String,
 }
 
@@ -28,10 +112,194 @@ impl WrapperManager {
     pub fn new() -> Self {
         Self {
             script_content: WRAPPER_SCRIPT.to_string(),
+            helpers_content: HELPERS_SCRIPT.to_string(),
             version: WRAPPER_VERSION.to_string(),
         }
     }
 
+    /// SHA-256 of the wrapper then its helpers, as written on the server
+    pub fn script_checksum(&self) -> String {
+        sha256_hex(
+            format!(
+                "{}\n{}\n",
+                self.script_content.trim_end_matches('\n'),
+                self.helpers_content.trim_end_matches('\n')
+            )
+            .as_bytes(),
+        )
+    }
+
+    /// Compare the deployed wrapper with this build
+    pub fn wrapper_status(&self, install: &ServerInstall) -> WrapperStatus {
+        let Some(ref checksum) = install.wrapper_checksum else {
+            return WrapperStatus::Missing;
+        };
+        if *checksum == self.script_checksum() {
+            return WrapperStatus::UpToDate;
+        }
+
+        let installed = install.wrapper_version.clone();
+        match (
+            installed.as_deref().and_then(parse_version),
+            parse_version(&self.version),
+        ) {
+            (Some(theirs), Some(ours)) if theirs > ours => WrapperStatus::Newer {
+                installed: installed.unwrap_or_default(),
+            },
+            _ => WrapperStatus::Outdated { installed },
+        }
+    }
+
+    /// Read the deployed wrapper checksum, versions and server `jobs` columns
+    ///
+    /// # Errors
+    /// Returns error if SSH command fails or connection is lost
+    pub async fn inspect_server(&self, executor: &SshExecutor) -> Result<ServerInstall, String> {
+        let inspect_cmd = format!(
+            "if [ -f {REMOTE_WRAPPER_PATH} ]; then \
+               echo \"checksum=$(cat {REMOTE_WRAPPER_PATH} {REMOTE_HELPERS_PATH} 2>/dev/null \
+                 | sha256sum | cut -d' ' -f1)\"; fi; \
+             if [ -f {REMOTE_SERVER_DB_PATH} ]; then echo db=1; \
+               sqlite3 {REMOTE_SERVER_DB_PATH} \
+               \"SELECT key || '=' || value FROM metadata \
+                 WHERE key IN ('wrapper_version', 'schema_version'); \
+                 SELECT 'column=' || name FROM pragma_table_info('jobs');\"; fi"
+        );
+
+        let output = executor
+            .execute(&inspect_cmd)
+            .await
+            .map_err(|e| format!("Failed to inspect server installation: {e}"))?;
+
+        Ok(parse_server_install(&output))
+    }
+
+    /// Bring the server to this build: database created or migrated, wrapper
+    /// deployed or upgraded, `wrapper_version` recorded
+    ///
+    /// Running jobs are not disturbed: schema migrations only add columns, and
+    /// the wrapper is replaced by rename, so running shells keep the old file.
+    ///
+    /// # Errors
+    /// Returns error if an SSH command fails
+    pub async fn ensure_server_ready(
+        &self,
+        executor: &SshExecutor,
+    ) -> Result<ServerUpgrade, String> {
+        let install = self.inspect_server(executor).await?;
+        let (schema_from, schema_to) = self.ensure_server_db(executor, &install).await?;
+
+        // Wrapper: deployed when missing or outdated, never downgraded
+        let wrapper = self.wrapper_status(&install);
+        let wrapper_deployed = match wrapper {
+            WrapperStatus::Missing | WrapperStatus::Outdated { .. } => {
+                self.deploy_to_server(executor).await?;
+                true
+            }
+            WrapperStatus::Newer { ref installed } => {
+                tracing::warn!(
+                    "Server wrapper {installed} is newer than this build ({}), keeping it",
+                    self.version
+                );
+                false
+            }
+            WrapperStatus::UpToDate => false,
+        };
+
+        let stale_version = install.wrapper_version.as_deref() != Some(self.version.as_str());
+        if !matches!(wrapper, WrapperStatus::Newer { .. }) && (wrapper_deployed || stale_version) {
+            self.record_version(executor).await?;
+        }
+
+        Ok(ServerUpgrade {
+            wrapper,
+            wrapper_deployed,
+            schema_from,
+            schema_to,
+        })
+    }
+
+    /// Create the server database at the latest schema, or migrate it
+    ///
+    /// Returns the schema version before and after (0 = no database).
+    ///
+    /// # Errors
+    /// Returns error if an SSH command or a migration fails
+    pub async fn ensure_server_db(
+        &self,
+        executor: &SshExecutor,
+        install: &ServerInstall,
+    ) -> Result<(i64, i64), String> {
+        let schema_from = if install.db_exists {
+            install.schema_version.unwrap_or(1)
+        } else {
+            0
+        };
+        let mut schema_to = schema_from;
+        if !install.db_exists {
+            self.init_server_db(executor).await?;
+            schema_to = SERVER_SCHEMA_VERSION;
+        } else if schema_from > SERVER_SCHEMA_VERSION {
+            tracing::warn!(
+                "Server database schema v{schema_from} is newer than this build (v{SERVER_SCHEMA_VERSION}), not migrating"
+            );
+        } else if let Some(script) = server_db::migration_script(schema_from, &install.job_columns)
+        {
+            executor
+                .execute(&format!(
+                    "sqlite3 -bail {REMOTE_SERVER_DB_PATH} <<'SQL_EOF'\n.timeout 5000\n{script}SQL_EOF"
+                ))
+                .await
+                .map_err(|e| format!("Failed to migrate server database: {e}"))?;
+            schema_to = SERVER_SCHEMA_VERSION;
+            tracing::info!("Server database migrated from v{schema_from} to v{schema_to}");
+        }
+
+        Ok((schema_from, schema_to))
+    }
+
+    /// Create the server database from `server_schema.sql` (permissions 0600)
+    async fn init_server_db(&self, executor: &SshExecutor) -> Result<(), String> {
+        let init_script = server_db::generate_init_script();
+
+        executor
+            .execute("mkdir -p ~/.solverpilot-server")
+            .await
+            .map_err(|e| format!("Failed to create server directory: {e}"))?;
+
+        // Using heredoc to avoid escaping issues
+        executor
+            .execute(&format!(
+                "sqlite3 {REMOTE_SERVER_DB_PATH} <<'SQL_EOF'\n{init_script}\nSQL_EOF"
+            ))
+            .await
+            .map_err(|e| format!("Failed to initialize server database: {e}"))?;
+
+        executor
+            .execute(&format!("chmod 600 {REMOTE_SERVER_DB_PATH}"))
+            .await
+            .map_err(|e| format!("Failed to set database permissions: {e}"))?;
+
+        tracing::info!("Server database initialized at {REMOTE_SERVER_DB_PATH}");
+        Ok(())
+    }
+
+    /// Record the deployed wrapper version in the server metadata
+    async fn record_version(&self, executor: &SshExecutor) -> Result<(), String> {
+        // Heredoc keeps the SQL away from shell quoting; the version is a constant of this build
+        let update_version_cmd = format!(
+            "sqlite3 {REMOTE_SERVER_DB_PATH} <<'SQL_EOF'\n.timeout 5000\nINSERT OR REPLACE INTO metadata (key, value, updated_at) VALUES ('wrapper_version', '{}', datetime('now'));\nSQL_EOF",
+            self.version
+        );
+
+        executor
+            .execute(&update_version_cmd)
+            .await
+            .map_err(|e| format!("Failed to update wrapper version in metadata: {e}"))?;
+
+        Ok(())
+    }
+
     /// Check if wrapper is installed on remote server
     ///
     /// # Errors
@@ -48,14 +316,16 @@ impl WrapperManager {
         Ok(output.trim() == "installed")
     }
 
-    /// Deploy wrapper to server at `~/.solverpilot/bin/job_wrapper.sh`
+    /// Deploy wrapper to server at `~/.solverpilot/bin/job_wrapper.sh`, with its helpers
     ///
     /// Performs the following steps:
     /// 1. Create remote directory
-    /// 2. Write wrapper via heredoc
+    /// 2. Write wrapper and helpers via heredocs to temporary files
     /// 3. Make executable
+    /// 4. Rename helpers, then wrapper, over the deployed files (atomic: running jobs
+    ///    keep the old wrapper, and sourced the old helpers when they started)
     ///
-    /// On failure, attempts to clean up partial deployment to prevent inconsistent state.
+    /// On failure, the temporary files are removed and the deployed wrapper is left untouched.
     ///
     /// # Errors
     /// Returns error if any SSH command fails
@@ -66,33 +336,29 @@ impl WrapperManager {
             .await
             .map_err(|e| format!("Failed to create wrapper directory: {e}"))?;
 
-        // Step 2: Write script via heredoc (single-quoted delimiter prevents variable expansion)
+        // Steps 2-4: write, chmod and rename in one shell (single-quoted delimiters
+        // prevent variable expansion; $$ keeps concurrent deployments apart)
         let write_cmd = format!(
-            "cat > {} << 'WRAPPER_EOF'\n{}\nWRAPPER_EOF",
-            REMOTE_WRAPPER_PATH, self.script_content
+            "tmp={REMOTE_WRAPPER_PATH}.tmp.$$\n\
+             helpers_tmp={REMOTE_HELPERS_PATH}.tmp.$$\n\
+             cat > \"$tmp\" << 'WRAPPER_EOF'\n{}\nWRAPPER_EOF\n\
+             cat > \"$helpers_tmp\" << 'HELPERS_EOF'\n{}\nHELPERS_EOF\n\
+             chmod +x \"$tmp\" \"$helpers_tmp\" && mv -f \"$helpers_tmp\" {REMOTE_HELPERS_PATH} \
+             && mv -f \"$tmp\" {REMOTE_WRAPPER_PATH} || {{ rm -f \"$tmp\" \"$helpers_tmp\"; exit 1; }}",
+            self.script_content.trim_end_matches('\n'),
+            self.helpers_content.trim_end_matches('\n')
         );
 
-        if let Err(e) = executor.execute(&write_cmd).await {
-            // Cleanup: remove partial file if write failed
-            let _ = executor
-                .execute(&format!("rm -f {REMOTE_WRAPPER_PATH}"))
-                .await;
-            return Err(format!("Failed to write wrapper script: {e}"));
-        }
-
-        // Step 3: Make executable
-        if let Err(e) = executor
-            .execute(&format!("chmod +x {REMOTE_WRAPPER_PATH}"))
+        executor
+            .execute(&write_cmd)
             .await
-        {
-            // Cleanup: remove script if chmod failed (script exists but not executable = broken state)
-            let _ = executor
-                .execute(&format!("rm -f {REMOTE_WRAPPER_PATH}"))
-                .await;
-            return Err(format!("Failed to make wrapper executable: {e}"));
-        }
+            .map_err(|e| format!("Failed to write wrapper script: {e}"))?;
 
-        tracing::info!("Wrapper deployed successfully to {}", REMOTE_WRAPPER_PATH);
+        tracing::info!(
+            "Wrapper {} deployed successfully to {}",
+            self.version,
+            REMOTE_WRAPPER_PATH
+        );
         Ok(())
     }
 
@@ -144,13 +410,13 @@ mod tests {
 
     #[test]
     fn test_wrapper_version() {
-        assert_eq!(WRAPPER_VERSION, "1.0.0");
+        assert_eq!(WRAPPER_VERSION, "1.2.0");
     }
 
     #[test]
     fn test_wrapper_manager_creation() {
         let manager = WrapperManager::new();
-        assert_eq!(manager.version(), "1.0.0");
+        assert_eq!(manager.version(), "1.2.0");
         assert!(!manager.script_content.is_empty());
     }
 
@@ -207,7 +473,7 @@ mod tests {
     #[test]
     fn test_default_trait() {
         let manager = WrapperManager::default();
-        assert_eq!(manager.version(), "1.0.0");
+        assert_eq!(manager.version(), "1.2.0");
     }
 
     // =========================================================================
@@ -352,6 +618,72 @@ mod tests {
         assert_eq!(cmd, "~/.solverpilot/bin/job_wrapper.sh test-job ");
     }
 
+    #[test]
+    fn test_parse_server_install() {
+        let install = parse_server_install(
+            "checksum=abc123\ndb=1\nwrapper_version=1.0.0\ncolumn=id\ncolumn=status\n",
+        );
+        assert_eq!(install.wrapper_checksum.as_deref(), Some("abc123"));
+        assert_eq!(install.wrapper_version.as_deref(), Some("1.0.0"));
+        assert!(install.db_exists);
+        assert_eq!(install.schema_version, None);
+        assert_eq!(install.job_columns, vec!["id", "status"]);
+
+        assert_eq!(parse_server_install(""), ServerInstall::default());
+    }
+
+    #[test]
+    fn test_wrapper_status_negotiation() {
+        let manager = WrapperManager::new();
+        let mut install = ServerInstall::default();
+        assert_eq!(manager.wrapper_status(&install), WrapperStatus::Missing);
+
+        install.wrapper_checksum = Some(manager.script_checksum());
+        assert_eq!(manager.wrapper_status(&install), WrapperStatus::UpToDate);
+
+        // Same version string but different content (e.g. edited on the server)
+        install.wrapper_checksum = Some("0".repeat(64));
+        install.wrapper_version = Some(WRAPPER_VERSION.to_string());
+        assert!(matches!(
+            manager.wrapper_status(&install),
+            WrapperStatus::Outdated { .. }
+        ));
+
+        install.wrapper_version = Some("1.0.0".to_string());
+        assert_eq!(
+            manager.wrapper_status(&install),
+            WrapperStatus::Outdated {
+                installed: Some("1.0.0".to_string())
+            }
+        );
+
+        install.wrapper_version = Some("1.10.0".to_string());
+        assert_eq!(
+            manager.wrapper_status(&install),
+            WrapperStatus::Newer {
+                installed: "1.10.0".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_script_checksum_matches_deployed_content() {
+        // The heredoc writes the script with exactly one trailing newline
+        let manager = WrapperManager::new();
+        assert_eq!(
+            manager.script_checksum(),
+            sha256_hex(
+                format!(
+                    "{}\n{}\n",
+                    WRAPPER_SCRIPT.trim_end_matches('\n'),
+                    HELPERS_SCRIPT.trim_end_matches('\n')
+                )
+                .as_bytes()
+            )
+        );
+        assert_eq!(manager.script_checksum().len(), 64);
+    }
+
     #[test]
     fn test_wrapper_script_line_count() {
         // Verify script is reasonable size (not empty, not huge)
@@ -359,4 +691,16 @@ mod tests {
         assert!(lines.len() > 50, "Script should have substantial content");
         assert!(lines.len() < 200, "Script should be concise");
     }
+
+    #[test]
+    fn test_wrapper_sources_helpers() {
+        // Sampler and limits live in the helpers, deployed next to the wrapper
+        assert!(WRAPPER_SCRIPT.contains("/job_helpers.sh\""));
+        assert!(HELPERS_SCRIPT.contains("monitor_usage()"));
+        assert!(HELPERS_SCRIPT.contains("$BASE_DIR/jobs/$JOB_ID.usage"));
+        assert_eq!(
+            REMOTE_HELPERS_PATH.rsplit_once('/').map(|(dir, _)| dir),
+            REMOTE_WRAPPER_PATH.rsplit_once('/').map(|(dir, _)| dir)
+        );
+    }
 }
@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import { open } from '@tauri-apps/plugin-dialog';
 import type {
   AppConfig,
@@ -10,6 +11,27 @@ import type {
   JobStatusResponse,
   DependencyAnalysis,
   QueueStatus,
+  LicenseReport,
+  ReportFormat,
+  ResultTable,
+  ResultSummary,
+  CodeVersion,
+  EnvFingerprint,
+  FingerprintChange,
+  JobContainer,
+  JobLimits,
+  LogChunk,
+  ArchivedLog,
+  RetentionReport,
+  JobSearchFilters,
+  JobSearchHit,
+  HistoryQuery,
+  HistoryPage,
+  HistoryFilters,
+  HistoryStats,
+  ServerInfo,
+  ServerUpgrade,
+  Tag,
 } from './types';
 
 // =============================================================================
@@ -48,31 +70,41 @@ export async function loadConfig(): Promise<AppConfig> {
 // SSH
 // =============================================================================
 
-export async function initSsh(): Promise<string> {
-  return invoke('init_ssh');
+/**
+ * Initialise le SSH d'un serveur, ou de tous les serveurs configurés
+ */
+export async function initSsh(server?: string): Promise<string> {
+  return invoke('init_ssh', { server: server ?? null });
 }
 
 export async function closeSsh(): Promise<void> {
   return invoke('close_ssh');
 }
 
-export async function testSsh(): Promise<boolean> {
-  return invoke('test_ssh');
+export async function testSsh(server?: string): Promise<boolean> {
+  return invoke('test_ssh', { server: server ?? null });
 }
 
 /**
  * Test SSH direct (pour le wizard de setup)
  */
-export async function testSshDirect(passphrase?: string): Promise<void> {
-  return invoke('test_ssh_direct', { passphrase: passphrase ?? null });
+export async function testSshDirect(passphrase?: string, server?: string): Promise<void> {
+  return invoke('test_ssh_direct', { passphrase: passphrase ?? null, server: server ?? null });
 }
 
-export async function checkSshKeyStatus(): Promise<SshKeyStatus> {
-  return invoke('check_ssh_key_status');
+export async function checkSshKeyStatus(server?: string): Promise<SshKeyStatus> {
+  return invoke('check_ssh_key_status', { server: server ?? null });
 }
 
-export async function addSshKey(passphrase: string): Promise<void> {
-  return invoke('add_ssh_key', { passphrase });
+export async function addSshKey(passphrase: string, server?: string): Promise<void> {
+  return invoke('add_ssh_key', { passphrase, server: server ?? null });
+}
+
+/**
+ * Liste les serveurs configurés ([ssh] = "default", puis [servers.*])
+ */
+export async function listServers(): Promise<ServerInfo[]> {
+  return invoke('list_servers');
 }
 
 // =============================================================================
@@ -84,8 +116,8 @@ export async function addSshKey(passphrase: string): Promise<void> {
  * Creates ~/.solverpilot-server/server.db with jobs table, indexes, and PRAGMAs.
  * Idempotent: safe to call multiple times.
  */
-export async function initServerDb(): Promise<void> {
-  return invoke('init_server_db');
+export async function initServerDb(server?: string): Promise<void> {
+  return invoke('init_server_db', { server: server ?? null });
 }
 
 // =============================================================================
@@ -98,14 +130,15 @@ export async function initServerDb(): Promise<void> {
  *
  * @returns true if wrapper is installed, false otherwise
  */
-export async function checkWrapperInstalled(): Promise<boolean> {
-  return invoke('check_wrapper_installed');
+export async function checkWrapperInstalled(server?: string): Promise<boolean> {
+  return invoke('check_wrapper_installed', { server: server ?? null });
 }
 
 /**
  * Deploy wrapper script to remote server at ~/.solverpilot/bin/job_wrapper.sh.
- * Automatically initializes server database if needed.
- * Idempotent: safe to call multiple times (skips if already installed).
+ * Automatically initializes or migrates the server database.
+ * Idempotent: safe to call multiple times (skips if already up to date). An outdated
+ * wrapper is replaced atomically; running jobs keep the version they started with.
  *
  * **Frontend Toast Requirements (Story 2.3 AC):**
  * - On start: Show toast "Installing queue infrastructure on server..."
@@ -115,28 +148,39 @@ export async function checkWrapperInstalled(): Promise<boolean> {
  * Note: The backend auto-deploys wrapper on first queue execution via `startNextJob()`,
  * so explicit calls to this function are typically for manual deployment scenarios.
  */
-export async function deployWrapper(): Promise<void> {
-  return invoke('deploy_wrapper');
+export async function deployWrapper(server?: string): Promise<ServerUpgrade> {
+  return invoke('deploy_wrapper', { server: server ?? null });
+}
+
+// =============================================================================
+// License Preflight
+// =============================================================================
+
+/**
+ * Query solver license status on the server (expiry, token usage)
+ */
+export async function checkLicense(server?: string): Promise<LicenseReport> {
+  return invoke('check_license', { server: server ?? null });
 }
 
 // =============================================================================
 // Sync
 // =============================================================================
 
-export async function checkSyncStatus(): Promise<SyncStatus> {
-  return invoke('check_sync_status');
+export async function checkSyncStatus(server?: string): Promise<SyncStatus> {
+  return invoke('check_sync_status', { server: server ?? null });
 }
 
-export async function syncCode(): Promise<void> {
-  return invoke('sync_code');
+export async function syncCode(server?: string): Promise<void> {
+  return invoke('sync_code', { server: server ?? null });
 }
 
 /**
  * Synchronise uniquement les fichiers de dépendances d'un benchmark
  * @returns Le nombre de fichiers synchronisés
  */
-export async function syncBenchmarkDeps(benchmarkPath: string): Promise<number> {
-  return invoke('sync_benchmark_deps', { benchmarkPath });
+export async function syncBenchmarkDeps(benchmarkPath: string, server?: string): Promise<number> {
+  return invoke('sync_benchmark_deps', { benchmarkPath, server: server ?? null });
 }
 
 // =============================================================================
@@ -178,6 +222,38 @@ export async function getActiveProject(): Promise<Project | null> {
   return invoke('get_active_project');
 }
 
+// =============================================================================
+// Environment Profiles
+// =============================================================================
+
+/**
+ * Liste les profils d'environnement définis dans la configuration
+ */
+export async function listEnvProfiles(): Promise<string[]> {
+  return invoke('list_env_profiles');
+}
+
+/**
+ * Sélectionne le profil d'environnement du projet actif (null = profil par défaut)
+ */
+export async function setProjectEnvProfile(envProfile: string | null): Promise<void> {
+  return invoke('set_project_env_profile', { envProfile });
+}
+
+/**
+ * Liste les profils de conteneur configurés
+ */
+export async function listContainerProfiles(): Promise<string[]> {
+  return invoke('list_container_profiles');
+}
+
+/**
+ * Sélectionne le profil de conteneur du projet actif (null = profil par défaut)
+ */
+export async function setProjectContainer(container: string | null): Promise<void> {
+  return invoke('set_project_container', { container });
+}
+
 // =============================================================================
 // Python Version Management
 // =============================================================================
@@ -241,6 +317,133 @@ export async function getBenchmarkDependencies(benchmarkPath: string): Promise<D
   return invoke('get_benchmark_dependencies', { benchmarkPath });
 }
 
+/**
+ * Limites mémoire/CPU propres à un benchmark (null = valeurs par défaut de la config)
+ */
+export async function getBenchmarkLimits(benchmarkId: number): Promise<JobLimits | null> {
+  return invoke('get_benchmark_limits', { benchmarkId });
+}
+
+/**
+ * Définit les limites d'un benchmark (null pour revenir aux valeurs par défaut)
+ */
+export async function setBenchmarkLimits(
+  benchmarkId: number,
+  limits: JobLimits | null,
+): Promise<void> {
+  return invoke('set_benchmark_limits', { benchmarkId, limits });
+}
+
+/**
+ * Serveurs autorisés pour un benchmark (vide = tous)
+ */
+export async function getBenchmarkServers(benchmarkId: number): Promise<string[]> {
+  return invoke('get_benchmark_servers', { benchmarkId });
+}
+
+/**
+ * Restreint un benchmark à certains serveurs pour le dispatch (vide = tous)
+ */
+export async function setBenchmarkServers(benchmarkId: number, servers: string[]): Promise<void> {
+  return invoke('set_benchmark_servers', { benchmarkId, servers });
+}
+
+// =============================================================================
+// Results
+// =============================================================================
+
+/**
+ * Download and store a job's benchmark_results.csv (replaces previous rows)
+ * @returns Number of stored rows
+ */
+export async function ingestJobResults(jobId: number): Promise<number> {
+  return invoke('ingest_job_results', { jobId });
+}
+
+/**
+ * Get a job's ingested results with their inferred schema (null if not ingested)
+ */
+export async function getJobResults(jobId: number): Promise<ResultTable | null> {
+  return invoke('get_job_results', { jobId });
+}
+
+/**
+ * Get per-job result summaries (jobs without results are omitted)
+ */
+export async function getResultSummaries(jobIds: number[]): Promise<ResultSummary[]> {
+  return invoke('get_result_summaries', { jobIds });
+}
+
+// =============================================================================
+// Code Versions
+// =============================================================================
+
+/**
+ * Get the code version recorded for a job (git state + synced file hashes)
+ */
+export async function getJobCodeVersion(jobId: number): Promise<CodeVersion | null> {
+  return invoke('get_job_code_version', { jobId });
+}
+
+/**
+ * Rerun a job with exactly its recorded code version
+ * (restores and syncs the snapshot, then queues a new job)
+ */
+export async function rerunJobVersion(jobId: number): Promise<Job> {
+  return invoke('rerun_job_version', { jobId });
+}
+
+// =============================================================================
+// Environment Fingerprints
+// =============================================================================
+
+/**
+ * Get the environment fingerprint captured when a job started
+ */
+export async function getJobEnvFingerprint(jobId: number): Promise<EnvFingerprint | null> {
+  return invoke('get_job_env_fingerprint', { jobId });
+}
+
+/**
+ * Get the container image (and its digest) a job ran in
+ */
+export async function getJobContainer(jobId: number): Promise<JobContainer | null> {
+  return invoke('get_job_container', { jobId });
+}
+
+/**
+ * Diff the environment fingerprints of two jobs (packages compared individually)
+ */
+export async function diffJobEnvFingerprints(
+  leftJobId: number,
+  rightJobId: number,
+): Promise<FingerprintChange[]> {
+  return invoke('diff_job_env_fingerprints', { leftJobId, rightJobId });
+}
+
+// =============================================================================
+// Reports
+// =============================================================================
+
+/**
+ * Build a comparison report across jobs (SGM, wins, performance profiles)
+ * @param jobIds - Jobs to compare (at least two with results; jobs without are listed as skipped)
+ * @param format - Export format: 'csv', 'markdown' or 'html'
+ * @param outputPath - Optional file path to write the report to
+ * @param sgmShift - Shift for the shifted geometric mean (default: 10s)
+ * @param tags - Completed jobs carrying any of these tags are compared too
+ * @returns The rendered report
+ */
+export async function generateComparisonReport(
+  jobIds: number[],
+  format: ReportFormat,
+  outputPath: string | null = null,
+  sgmShift: number | null = null,
+  tags: string[] = [],
+): Promise<string> {
+  return invoke('generate_comparison_report', { jobIds, format, outputPath, sgmShift, tags });
+}
+
 // =============================================================================
 // Project Dependencies
 // =============================================================================
@@ -293,12 +496,26 @@ export async function queueJobs(benchmarkNames: string[]): Promise<Job[]> {
  * Enhanced with duplicate detection in Story 1.5
  * @param benchmarkIds - Array of benchmark IDs to queue
  * @param forceDuplicate - If true, bypass duplicate detection (default: false)
+ * @param envProfile - Optional environment profile override for these jobs
+ * @param tags - Labels applied to every queued job
+ * @param server - Target server (default: the dispatcher if enabled, else `default_server`)
  */
 export async function queueBenchmarks(
   benchmarkIds: number[],
   forceDuplicate = false,
+  envProfile: string | null = null,
+  tags: string[] = [],
+  server: string | null = null,
+  container: string | null = null,
 ): Promise<Job[]> {
-  return invoke('queue_benchmarks', { benchmarkIds, forceDuplicate });
+  return invoke('queue_benchmarks', {
+    benchmarkIds,
+    forceDuplicate,
+    envProfile,
+    tags,
+    server,
+    container,
+  });
 }
 
 /**
@@ -345,6 +562,14 @@ export async function reorderQueueJob(jobId: number, newPosition: number): Promi
   return invoke('reorder_queue_job', { jobId, newPosition });
 }
 
+/**
+ * Set or clear the earliest start time of a pending job (ISO 8601 / RFC 3339)
+ * The queue skips the job until then.
+ */
+export async function setJobNotBefore(jobId: number, notBefore: string | null): Promise<void> {
+  return invoke('set_job_not_before', { jobId, notBefore });
+}
+
 /**
  * Cancel all pending jobs in the queue (Story 1.4)
  * Running and completed jobs are preserved. Returns count of deleted jobs.
@@ -369,10 +594,73 @@ export async function getJobLogs(lines: number): Promise<string> {
   return invoke('get_job_logs', { lines });
 }
 
+/**
+ * S'abonne au log d'un job en direct ; les morceaux arrivent via `onJobLogChunk`.
+ * `fromOffset` (octets) permet de reprendre là où un abonnement précédent s'est arrêté.
+ */
+export async function subscribeJobLogs(jobId: number, fromOffset?: number): Promise<number> {
+  return invoke('subscribe_job_logs', { jobId, fromOffset: fromOffset ?? null });
+}
+
+export async function unsubscribeJobLogs(subscriptionId: number): Promise<void> {
+  return invoke('unsubscribe_job_logs', { subscriptionId });
+}
+
+/**
+ * Écoute les morceaux de log de tous les abonnements (filtrer sur `subscription_id`)
+ */
+export async function onJobLogChunk(handler: (chunk: LogChunk) => void): Promise<UnlistenFn> {
+  return listen<LogChunk>('job-log-chunk', event => handler(event.payload));
+}
+
 export async function getJobStatus(): Promise<JobStatusResponse> {
   return invoke('get_job_status');
 }
 
+// =============================================================================
+// Tags & Notes
+// =============================================================================
+
+/**
+ * Remplace les tags d'un job ("baseline", "paper-v2", ...)
+ */
+export async function setJobTags(jobId: number, tags: string[]): Promise<void> {
+  return invoke('set_job_tags', { jobId, tags });
+}
+
+/**
+ * Enregistre les notes libres d'un job (null ou vide = effacées)
+ */
+export async function setJobNotes(jobId: number, notes: string | null): Promise<void> {
+  return invoke('set_job_notes', { jobId, notes });
+}
+
+export async function listTags(): Promise<Tag[]> {
+  return invoke('list_tags');
+}
+
+// =============================================================================
+// Log Archive
+// =============================================================================
+
+/**
+ * Log complet archivé localement (null s'il n'a pas été archivé ou a expiré)
+ */
+export async function getArchivedJobLog(jobId: number): Promise<string | null> {
+  return invoke('get_archived_job_log', { jobId });
+}
+
+export async function archiveJobLog(jobId: number): Promise<ArchivedLog> {
+  return invoke('archive_job_log', { jobId });
+}
+
+/**
+ * Supprime les archives au-delà de la rétention configurée (âge, taille totale)
+ */
+export async function pruneLogArchive(): Promise<RetentionReport> {
+  return invoke('prune_log_archive');
+}
+
 // =============================================================================
 // Queue Processing (Story 2.4)
 // =============================================================================
@@ -439,6 +727,31 @@ export async function loadHistory(limit: number): Promise<Job[]> {
   return invoke('load_history', { limit });
 }
 
+/**
+ * Historique filtré et trié, paginé par curseur (passer `next_cursor` pour la page suivante)
+ */
+export async function queryHistory(query: HistoryQuery): Promise<HistoryPage> {
+  return invoke('query_history', { query });
+}
+
+/**
+ * Taux de succès, durée moyenne par benchmark et tendance des échecs sur l'historique filtré
+ */
+export async function getHistoryStats(filters?: HistoryFilters): Promise<HistoryStats> {
+  return invoke('get_history_stats', { filters: filters ?? null });
+}
+
+/**
+ * Recherche plein texte (logs archivés, erreurs, benchmarks, paramètres), triée par pertinence.
+ * Mots requis ; `"phrase exacte"` et `préfixe*` supportés.
+ */
+export async function searchJobs(
+  query: string,
+  filters?: JobSearchFilters,
+): Promise<JobSearchHit[]> {
+  return invoke('search_jobs', { query, filters: filters ?? null });
+}
+
 export async function deleteJob(jobId: number): Promise<void> {
   return invoke('delete_job', { jobId });
 }
@@ -5,7 +5,7 @@
 // Polling is initialized on first use via initPolling().
 
 import * as api from '$lib/api';
-import type { QueueStatus } from '$lib/types';
+import type { QueueEta, QueueStatus, QueueWindow } from '$lib/types';
 
 interface QueueStore {
   state: 'idle' | 'running' | 'paused';
@@ -13,6 +13,18 @@ interface QueueStore {
   pendingCount: number;
   runningCount: number;
   completedCount: number;
+  /** Why the next job is held back (e.g. no license token available) */
+  waitingReason: string | null;
+  /** License expiry warning from the preflight check */
+  licenseWarning: string | null;
+  /** Execution windows, when configured: open now, and next opening/closing */
+  window: QueueWindow | null;
+  /** Paused until the next execution window opens */
+  schedulePaused: boolean;
+  /** Jobs suspended at the end of the last window */
+  suspendedJobs: number[];
+  /** Estimated completion of each job and of the whole queue */
+  eta: QueueEta | null;
   /** True for one polling cycle when queue naturally completes (all jobs done) */
   justCompleted: boolean;
 }
@@ -29,6 +41,12 @@ const queueStore = $state<QueueStore>({
   pendingCount: 0,
   runningCount: 0,
   completedCount: 0,
+  waitingReason: null,
+  licenseWarning: null,
+  window: null,
+  schedulePaused: false,
+  suspendedJobs: [],
+  eta: null,
   justCompleted: false,
 });
 
@@ -49,6 +67,12 @@ async function pollQueueStatus() {
     queueStore.pendingCount = status.pendingCount;
     queueStore.runningCount = status.runningCount;
     queueStore.completedCount = status.completedCount;
+    queueStore.waitingReason = status.waitingReason;
+    queueStore.licenseWarning = status.licenseWarning;
+    queueStore.window = status.window;
+    queueStore.schedulePaused = status.schedulePaused;
+    queueStore.suspendedJobs = status.suspendedJobs;
+    queueStore.eta = status.eta;
     queueStore.justCompleted = completed;
 
     // Track for next poll
@@ -119,6 +143,24 @@ export const queue = {
   get completedCount() {
     return queueStore.completedCount;
   },
+  get waitingReason() {
+    return queueStore.waitingReason;
+  },
+  get licenseWarning() {
+    return queueStore.licenseWarning;
+  },
+  get window() {
+    return queueStore.window;
+  },
+  get schedulePaused() {
+    return queueStore.schedulePaused;
+  },
+  get suspendedJobs() {
+    return queueStore.suspendedJobs;
+  },
+  get eta() {
+    return queueStore.eta;
+  },
   /** True for one polling cycle when queue naturally completes */
   get justCompleted() {
     return queueStore.justCompleted;
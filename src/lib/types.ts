@@ -3,11 +3,20 @@
 // =============================================================================
 
 export interface AppConfig {
+  // Server used when none is selected (default: the [ssh] server, "default")
+  default_server?: string;
   ssh: {
     host: string;
     user: string;
     port: number;
     key_path: string;
+    // Jobs the queue runs at once on this server (default: 1)
+    max_concurrent?: number;
+    backend?: ExecutionBackend;
+    slurm?: SlurmConfig;
+    pbs?: PbsConfig;
+    sge?: SgeConfig;
+    local?: LocalConfig;
   };
   remote: {
     remote_base: string;
@@ -15,17 +24,134 @@ export interface AppConfig {
   polling: {
     interval_seconds: number;
   };
+  // Legacy Gurobi section, exposed as the "gurobi" environment profile
   gurobi: {
     home: string;
     license_file: string;
   };
+  // Solver-agnostic environment profiles
+  environment?: {
+    default_profile?: string;
+    profiles: Record<string, EnvProfile>;
+  };
+  // Container images jobs can run in
+  containers?: {
+    default_profile?: string;
+    profiles: Record<string, ContainerProfile>;
+  };
   tools: {
     uv_path: string;
   };
   // Story 1.5 - Queue settings for duplicate handling
   queue_settings?: {
     duplicate_handling: 'warn' | 'prevent' | 'allow';
+    /** Cron-like windows the queue runs in (minute hour day month weekday, local time) */
+    windows?: string[];
+    /** Running jobs when a window ends: let them finish, or suspend them */
+    window_end?: 'finish' | 'suspend';
+  };
+  license?: {
+    enabled: boolean;
+    command: string;
+    warn_days: number;
+    retry_seconds: number;
+  };
+  fingerprint?: {
+    enabled: boolean;
+    solver_command: string;
+  };
+  limits?: JobLimits;
+  logs?: {
+    archive: boolean;
+    retention_days: number;
+    max_total_mb: number;
+    prune_remote: boolean;
+  };
+  // Automatic placement of jobs queued without a server
+  dispatch?: {
+    enabled: boolean;
+    max_load_per_core: number;
+  };
+  // Jobs held ("waiting for resources") while their server is busy
+  load_guard?: {
+    enabled: boolean;
+    max_load_per_core: number;
+    min_free_memory_mb: number;
+    heavy_process_cpu: number;
+    max_heavy_processes: number;
+    retry_seconds: number;
   };
+  // Additional compute servers by name ([ssh] is the server "default")
+  servers?: Record<string, ServerProfile>;
+}
+
+export interface ServerProfile {
+  host: string;
+  user: string;
+  port: number;
+  key_path: string;
+  max_concurrent?: number;
+  backend?: ExecutionBackend;
+  slurm?: SlurmConfig;
+  pbs?: PbsConfig;
+  sge?: SgeConfig;
+  local?: LocalConfig;
+  remote_base?: string;
+}
+
+/** How jobs are started on a server: tmux sessions (default), batch scheduler jobs or local processes */
+export type ExecutionBackend = 'tmux' | 'slurm' | 'pbs' | 'sge' | 'local';
+
+/** `sbatch` options of a Slurm server */
+export interface SlurmConfig {
+  partition?: string;
+  account?: string;
+  /** Wall-clock limit in `--time` format (e.g. "04:00:00") */
+  time_limit?: string;
+  /** CPUs per task when the job's limits set no CPU quota */
+  cpus_per_task?: number;
+  /** Additional `#SBATCH` options (e.g. "--qos=normal") */
+  extra_args?: string[];
+}
+
+/** `qsub` options of a PBS Pro / Torque server */
+export interface PbsConfig {
+  flavor?: 'pro' | 'torque';
+  queue?: string;
+  account?: string;
+  walltime?: string;
+  /** CPUs when the job's limits set no CPU quota */
+  ncpus?: number;
+  /** Additional `#PBS` options (e.g. "-l place=excl") */
+  extra_args?: string[];
+}
+
+/** `qsub` options of a Grid Engine server */
+export interface SgeConfig {
+  queue?: string;
+  project?: string;
+  /** Wall-clock limit (`h_rt`) */
+  time_limit?: string;
+  /** Parallel environment of multi-core jobs (default: "smp") */
+  parallel_env?: string;
+  /** Slots when the job's limits set no CPU quota */
+  slots?: number;
+  /** Additional `#$` options */
+  extra_args?: string[];
+}
+
+/** Options of a local server (jobs run on this machine) */
+export interface LocalConfig {
+  /** Directory used as `$HOME` by local jobs (default: the user's home) */
+  home?: string;
+}
+
+export interface EnvProfile {
+  env: Record<string, string>;
+  path: string[];
+  ld_library_path: string[];
+  modules: string[];
+  pre_run: string[];
 }
 
 // =============================================================================
@@ -38,6 +164,32 @@ export interface Project {
   python_version: string;
   created_at: string;
   updated_at: string;
+  env_profile: string | null;
+  /** Container profile of this project's jobs (null = default profile) */
+  container: string | null;
+}
+
+/** A pinned image and how jobs run in it */
+export interface ContainerProfile {
+  runtime?: 'apptainer' | 'singularity' | 'docker';
+  /** SIF file or image reference */
+  image: string;
+  /** Bind mounts `src[:dst[:options]]` */
+  binds?: string[];
+  /** Host environment variables passed into the container */
+  env?: string[];
+  /** uv inside the container (default: tools.uv_path) */
+  uv_path?: string;
+  extra_args?: string[];
+}
+
+/** Container a job ran in, recorded when it started */
+export interface JobContainer {
+  /** Profile selected on the job itself (null = project or default profile) */
+  profile: string | null;
+  image: string | null;
+  /** `sha256:...` digest of the image (null when it could not be determined) */
+  digest: string | null;
 }
 
 // =============================================================================
@@ -52,6 +204,16 @@ export interface Benchmark {
   created_at: string;
 }
 
+/** Memory/CPU limits of a job (omitted = unlimited) */
+export interface JobLimits {
+  memory_mb?: number;
+  /** Percent of one core (200 = 2 cores) */
+  cpu_quota_percent?: number;
+  /** CPU list for taskset (e.g. "0-3,8") */
+  cpu_affinity?: string;
+  cpu_time_limit_s?: number;
+}
+
 // =============================================================================
 // Jobs
 // =============================================================================
@@ -74,6 +236,33 @@ export interface Job {
   // Story 1.2 - Queue fields (nullable for non-queued jobs)
   queue_position: number | null;
   queued_at: string | null;
+  env_profile: string | null;
+  // Final resource usage reported by the wrapper
+  peak_rss_kb: number | null;
+  cpu_seconds: number | null;
+  notes: string | null;
+  tags: string[];
+  /** Target server (null = placed by the dispatcher when the job starts) */
+  server: string | null;
+  /** Earliest start time of a queued job (UTC ISO 8601, null = as soon as possible) */
+  not_before: string | null;
+}
+
+export interface Tag {
+  name: string;
+  job_count: number;
+}
+
+export interface ResourceUsage {
+  cpu_percent: number;
+  rss_kb: number;
+  peak_rss_kb: number;
+  threads: number;
+  read_bytes: number;
+  write_bytes: number;
+  cpu_seconds: number;
+  sampled_at: string;
+  final: boolean;
 }
 
 export interface JobStatusResponse {
@@ -84,6 +273,110 @@ export interface JobStatusResponse {
   elapsed_seconds: number;
   is_finished: boolean;
   error: string | null;
+  resource_usage: ResourceUsage | null;
+}
+
+/** Live log output pushed by a `subscribeJobLogs` subscription */
+export interface LogChunk {
+  subscription_id: number;
+  job_id: number;
+  /** Byte offset of `data` in the log file */
+  offset: number;
+  /** Offset following `data` (resume point for a later subscription) */
+  next_offset: number;
+  data: string;
+  /** Output before `offset` was skipped (lagging subscriber or truncated log) */
+  reset: boolean;
+}
+
+/** Result column value required on at least one result row (compared as text) */
+export interface ParamFilter {
+  name: string;
+  value: string;
+}
+
+/** History filters (all optional; no status = every finished job, dates YYYY-MM-DD inclusive) */
+export interface HistoryFilters {
+  project_id?: number;
+  benchmark?: string;
+  statuses?: JobStatus[];
+  from?: string;
+  to?: string;
+  params?: ParamFilter[];
+  /** Tags the job must all carry */
+  tags?: string[];
+}
+
+export type HistorySort = 'finished_at' | 'created_at' | 'duration' | 'benchmark' | 'peak_memory';
+
+export interface HistoryQuery {
+  filters?: HistoryFilters;
+  sort?: HistorySort;
+  /** Newest / largest first unless set */
+  ascending?: boolean;
+  limit?: number;
+  /** `next_cursor` of the previous page */
+  cursor?: string | null;
+}
+
+export interface HistoryPage {
+  jobs: Job[];
+  next_cursor: string | null;
+  /** Jobs matching the filters, all pages included */
+  total: number;
+}
+
+export interface BenchmarkStats {
+  benchmark_name: string;
+  runs: number;
+  completed: number;
+  failed: number;
+  success_rate: number;
+  /** Mean duration of completed runs (seconds) */
+  mean_duration_seconds: number | null;
+}
+
+export interface HistoryStats {
+  total: number;
+  completed: number;
+  failed: number;
+  killed: number;
+  success_rate: number;
+  per_benchmark: BenchmarkStats[];
+  /** Runs and failures per creation day, oldest first */
+  failure_trend: { day: string; runs: number; failed: number }[];
+}
+
+/** Filters of a full-text job search (all optional, dates as YYYY-MM-DD, inclusive) */
+export interface JobSearchFilters {
+  status?: JobStatus;
+  project_id?: number;
+  from?: string;
+  to?: string;
+  limit?: number;
+}
+
+export interface JobSearchHit {
+  job: Job;
+  /** Excerpt of the best matching field, matched terms wrapped in `<mark>`/`</mark>` */
+  snippet: string;
+  /** BM25 score (lower is more relevant) */
+  rank: number;
+}
+
+/** Compressed full log of a finished job kept in local storage */
+export interface ArchivedLog {
+  job_id: number;
+  path: string;
+  /** Compressed size in bytes */
+  size: number;
+  archived_at: string;
+}
+
+export interface RetentionReport {
+  deleted: number;
+  freed_bytes: number;
+  remaining_bytes: number;
 }
 
 // =============================================================================
@@ -98,9 +391,98 @@ export interface JobStatusResponse {
 export interface QueueStatus {
   state: 'idle' | 'running' | 'paused';
   currentJobId: number | null;
+  /** Jobs started by the queue and still running, with their server */
+  runningJobs: Record<number, string>;
   pendingCount: number;
   runningCount: number;
   completedCount: number;
+  // License preflight: why the next job is held back, and expiry warnings
+  waitingReason: string | null;
+  licenseWarning: string | null;
+  // Execution windows (null when the queue may always run)
+  window: QueueWindow | null;
+  /** Paused until the next execution window opens */
+  schedulePaused: boolean;
+  /** Jobs suspended at the end of the last window */
+  suspendedJobs: number[];
+  /** Estimated completion of the queue (null when the config is not loaded) */
+  eta: QueueEta | null;
+}
+
+/** Execution window state of the queue */
+export interface QueueWindow {
+  open: boolean;
+  /** Local time of the next opening (when closed) or closing (when open) */
+  nextChange: string | null;
+}
+
+/** Estimated completion of a pending or running job */
+export interface JobEta {
+  jobId: number;
+  /** Median runtime of similar past runs, in seconds */
+  expectedRuntime: number | null;
+  remainingSeconds: number | null;
+  /** UTC RFC 3339 */
+  finishesAt: string | null;
+}
+
+/** Estimated completion of the whole queue, accounting for concurrent slots */
+export interface QueueEta {
+  jobs: JobEta[];
+  /** Until the last job finishes (null if any job has no estimate) */
+  remainingSeconds: number | null;
+  finishesAt: string | null;
+  /** Jobs with no runtime history nor progress */
+  unestimated: number;
+}
+
+// =============================================================================
+// License Preflight
+// =============================================================================
+
+export interface LicenseStatus {
+  expires_on: string | null;
+  tokens_in_use: number | null;
+  tokens_max: number | null;
+  error: string | null;
+}
+
+export type PreflightOutcome =
+  | { outcome: 'ready'; reason: string | null }
+  | { outcome: 'delay'; reason: string }
+  | { outcome: 'block'; reason: string };
+
+export interface LicenseReport {
+  status: LicenseStatus;
+  outcome: PreflightOutcome;
+}
+
+// =============================================================================
+// Wrapper Deployment
+// =============================================================================
+
+export type WrapperStatus =
+  | { state: 'missing' }
+  | { state: 'up_to_date' }
+  | { state: 'outdated'; installed: string | null }
+  | { state: 'newer'; installed: string };
+
+export interface ServerUpgrade {
+  /** Wrapper state before the upgrade */
+  wrapper: WrapperStatus;
+  wrapper_deployed: boolean;
+  /** Server schema version before / after (0 = no database) */
+  schema_from: number;
+  schema_to: number;
+}
+
+export interface ServerInfo {
+  name: string;
+  host: string;
+  user: string;
+  port: number;
+  /** SSH manager initialized for this server */
+  connected: boolean;
 }
 
 // =============================================================================
@@ -153,3 +535,74 @@ export interface DependencyAnalysis {
   local_files: LocalDependency[];
   external_packages: ExternalPackage[];
 }
+
+// =============================================================================
+// Code Versions
+// =============================================================================
+
+export interface FileHash {
+  path: string;
+  sha256: string;
+}
+
+export interface CodeVersion {
+  captured_at: string;
+  code_root: string;
+  git_commit: string | null;
+  git_branch: string | null;
+  git_dirty: boolean;
+  git_diff: string | null;
+  files: FileHash[];
+}
+
+// =============================================================================
+// Environment Fingerprints
+// =============================================================================
+
+export interface EnvFingerprint {
+  captured_at: string;
+  hostname: string | null;
+  kernel: string | null;
+  cpu_model: string | null;
+  cpu_cores: number | null;
+  python_version: string | null;
+  uv_lock_sha256: string | null;
+  solver_version: string | null;
+  packages: Record<string, string>;
+}
+
+export interface FingerprintChange {
+  field: string;
+  left: string | null;
+  right: string | null;
+}
+
+// =============================================================================
+// Reports
+// =============================================================================
+
+export type ReportFormat = 'csv' | 'markdown' | 'html';
+
+// =============================================================================
+// Results (ingested benchmark_results.csv)
+// =============================================================================
+
+export type ResultColumnKind = 'integer' | 'real' | 'boolean' | 'text';
+
+export interface ResultColumn {
+  name: string;
+  kind: ResultColumnKind;
+}
+
+export interface ResultTable {
+  columns: ResultColumn[];
+  rows: (string | number | boolean | null)[][];
+}
+
+export interface ResultSummary {
+  job_id: number;
+  rows: number;
+  solved: number;
+  sgm_runtime: number | null;
+  max_gap: number | null;
+}